@@ -18,10 +18,9 @@ pub(crate) fn get_keyboard_state() -> WinResult<[u8; 256]> {
     });
     Ok(arr)
 }
-pub(crate) fn num_input_events() -> WinResult<u32> {
+pub(crate) fn num_input_events(handle: HANDLE) -> WinResult<u32> {
     let mut num: DWORD = 0;
     os_err!(unsafe {
-        let handle = handle!(STDIN);
         let num_p = &mut num as *mut DWORD;
         consoleapi::GetNumberOfConsoleInputEvents(handle, num_p)
     });
@@ -35,15 +34,14 @@ pub(crate) fn num_mouse_buttons() -> WinResult<u32> {
     });
     Ok(num)
 }
-pub(crate) fn peek_input(length: usize) -> WinResult<Vec<INPUT_RECORD>> {
-    self::read_or_peek(length, true)
+pub(crate) fn peek_input(handle: HANDLE, length: usize) -> WinResult<Vec<INPUT_RECORD>> {
+    self::read_or_peek(handle, length, true)
 }
-pub(crate) fn read_input(length: usize) -> WinResult<Vec<INPUT_RECORD>> {
-    self::read_or_peek(length, false)
+pub(crate) fn read_input(handle: HANDLE, length: usize) -> WinResult<Vec<INPUT_RECORD>> {
+    self::read_or_peek(handle, length, false)
 }
-pub(crate) fn write_input(buffer: Vec<INPUT_RECORD>) -> WinResult<()> {
+pub(crate) fn write_input(handle: HANDLE, buffer: Vec<INPUT_RECORD>) -> WinResult<()> {
     os_err!(unsafe {
-        let handle = handle!(STDIN);
         let length = buffer.len() as DWORD;
         if length == 0 {
             return Ok(());
@@ -52,12 +50,12 @@ pub(crate) fn write_input(buffer: Vec<INPUT_RECORD>) -> WinResult<()> {
 
         let written_p = &mut 0u32 as *mut DWORD;
         let buffer_p = &buffer[0] as *const INPUT_RECORD;
-        wincon::WriteConsoleInputA(handle, buffer_p, length, written_p)
+        wincon::WriteConsoleInputW(handle, buffer_p, length, written_p)
     });
     Ok(())
 }
 
-fn read_or_peek(length: usize, peek: bool) -> WinResult<Vec<INPUT_RECORD>> {
+fn read_or_peek(handle: HANDLE, length: usize, peek: bool) -> WinResult<Vec<INPUT_RECORD>> {
     if length == 0 {
         return Ok(Vec::new());
     }
@@ -65,7 +63,6 @@ fn read_or_peek(length: usize, peek: bool) -> WinResult<Vec<INPUT_RECORD>> {
     let mut num: DWORD = 0;
     let mut buffer: Box<[INPUT_RECORD]>;
     os_err!(unsafe {
-        let handle = handle!(STDIN);
         buffer = {
             let vec = vec![mem::zeroed(); length];
             vec.into_boxed_slice()
@@ -76,9 +73,9 @@ fn read_or_peek(length: usize, peek: bool) -> WinResult<Vec<INPUT_RECORD>> {
         let num_p = &mut num as *mut DWORD;
 
         if peek {
-            consoleapi::PeekConsoleInputA(handle, buffer_p, length, num_p)
+            wincon::PeekConsoleInputW(handle, buffer_p, length, num_p)
         } else {
-            consoleapi::ReadConsoleInputA(handle, buffer_p, length, num_p)
+            consoleapi::ReadConsoleInputW(handle, buffer_p, length, num_p)
         }
     });
     Ok(buf_to_vec!(buffer, num))
@@ -1,12 +1,156 @@
 use super::*;
-use std;
+use std::cell::RefCell;
+#[cfg(not(windows))]
+use std::io::{Error as IoError, ErrorKind};
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::io::IsTerminal;
 use std::io::Write;
-use std::sync::Mutex;
-
+#[cfg(unix)]
+use std::mem::ManuallyDrop;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(windows)]
+use std::process::{Child, Command};
+#[cfg(windows)]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(windows)]
+use std::sync::mpsc::{self, Receiver};
+#[cfg(windows)]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(windows)]
+type CtrlHandler = Arc<dyn Fn(CtrlEvent) -> bool + Send + Sync>;
+
+#[cfg(windows)]
 lazy_static! {
+    static ref CTRL_HANDLERS: Mutex<Vec<(u64, CtrlHandler)>> = Mutex::new(vec![]);
     static ref PAGES: Mutex<Vec<CodePage>> = Mutex::new(vec![]);
 }
+#[cfg(windows)]
+static NEXT_CTRL_HANDLER: AtomicU64 = AtomicU64::new(0);
+// The bit of the font family which is set for TrueType fonts.
+const TMPF_TRUETYPE: u32 = 0x4;
+
+thread_local! {
+    static BACKEND: RefCell<Option<Box<dyn ConsoleBackend>>> = RefCell::new(None);
+    static COLOR_METRIC: RefCell<ColorMetric> = RefCell::new(ColorMetric::default());
+}
+
+/**
+Adds a handler which is called when the console sends a signal to the process, and returns
+a token which removes it; see [`remove_ctrl_handler`].
+
+Handlers are called on a separate thread, from the most recently added to the least,
+until one returns `true` to indicate that it handled the event. If none does, the process
+is ended. The process is ended after a [`CtrlEvent::Close`] event regardless, once the
+handler returns.
+
+# Arguments
+* `handler` - The function to call with each event.
+
+# Examples
+Ignores CTRL + C, but allows CTRL + BREAK to end the process.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, CtrlEvent};
+# fn main() {
+let token = console::add_ctrl_handler(|event| event == CtrlEvent::CtrlC).unwrap();
+console::remove_ctrl_handler(token).unwrap();
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`CtrlEvent::Close`]: enum.CtrlEvent.html#variant.Close
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+[`remove_ctrl_handler`]: fn.remove_ctrl_handler.html
+*/
+#[cfg(windows)]
+pub fn add_ctrl_handler(
+    handler: impl Fn(CtrlEvent) -> bool + Send + Sync + 'static,
+) -> WinResult<CtrlHandlerToken> {
+    let mut handlers = lock_ctrl_handlers();
+    if handlers.is_empty() {
+        os_err!(unsafe { consoleapi::SetConsoleCtrlHandler(Some(dispatch_ctrl_event), 1) });
+    }
+
+    let id = NEXT_CTRL_HANDLER.fetch_add(1, Ordering::Relaxed);
+    handlers.push((id, Arc::new(handler)));
+    Ok(CtrlHandlerToken { id })
+}
+/**
+Allocates a new console for the process, and opens the standard handles which are not
+redirected to a file or pipe on it. A process can only be attached to one console,
+so this fails if the process is already attached; see [`free`](fn.free.html).
+
+This allows programs built for the GUI subsystem to open a console on demand.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+if !console::is_attached() {
+    console::alloc().unwrap();
+}
+console::set_title("Log").unwrap();
+println!("Hello from a new console!");
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn alloc() -> WinResult<()> {
+    os_err!(unsafe { consoleapi::AllocConsole() });
+    reopen_std_handles()
+}
+/**
+Attaches the process to the console of another process, and opens the standard handles
+which are not redirected to a file or pipe on it. A process can only be attached to one
+console, so this fails if the process is already attached; see [`free`](fn.free.html).
+
+# Arguments
+* `process_id` - The ID of the process whose console should be used. If None, the console of
+  the parent process is used.
+
+# Examples
+Writes to the console of the process which started this one, such as a command prompt.
+
+```no_run
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+console::free().unwrap();
+console::attach(None).unwrap();
+println!("Hello, parent!");
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
 
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn attach(process_id: impl Into<Option<u32>>) -> WinResult<()> {
+    let id = process_id.into().unwrap_or(ATTACH_PARENT_PROCESS);
+    os_err!(unsafe { wincon::AttachConsole(id) });
+    reopen_std_handles()
+}
 /**
 Generates a tone on the speaker.
 
@@ -25,6 +169,7 @@ console::beep(440, 1000);
 # }
 ```
 */
+#[cfg(windows)]
 pub fn beep(frequency: u32, duration: u32) {
     unsafe {
         utilapiset::Beep(frequency, duration);
@@ -38,6 +183,8 @@ Clears the console.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 println!("Hello, wolrd!");
 println!("Wait, that's not right..");
 console::clear().unwrap();
@@ -54,9 +201,9 @@ println!("Hello, world!");
 */
 pub fn clear() -> WinResult<()> {
     let size = get_buffer_size()?;
-    let length = DWORD::from(size.x) * DWORD::from(size.y);
-    fill_char(32, length, COORD { X: 0, Y: 0 })?;
-    fill_attributes(get_text_attributes()?, length, COORD { X: 0, Y: 0 })?;
+    let length = u32::from(size.x) * u32::from(size.y);
+    fill_char(' ', length, Vector2::new(0, 0))?;
+    fill_attrs(get_text_attributes()?.into(), length, Vector2::new(0, 0))?;
     set_cursor_position(0, 0)
 }
 /**
@@ -67,6 +214,8 @@ Clears the console input history.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::clear_history().unwrap();
 # }
 ```
@@ -85,6 +234,139 @@ pub fn clear_history() -> WinResult<()> {
     Ok(())
 }
 /**
+Adds a handler which sends every console signal to an asynchronous stream, and returns the
+stream along with a token which removes the handler; see [`remove_ctrl_handler`].
+
+Events are considered handled while the stream exists. The stream can be awaited alongside
+[input events](../input/struct.InputContext.html#method.stream) in any async runtime.
+
+# Examples
+```no_run
+# extern crate futures;
+# extern crate winconsole;
+# use futures::executor;
+# use winconsole::console::{self, CtrlEvent};
+# fn main() {
+let (events, token) = console::ctrl_event_stream().unwrap();
+for event in executor::block_on_stream(events) {
+    if event == CtrlEvent::CtrlC {
+        break;
+    }
+}
+console::remove_ctrl_handler(token).unwrap();
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+[`remove_ctrl_handler`]: fn.remove_ctrl_handler.html
+*/
+#[cfg(all(windows, feature = "async"))]
+pub fn ctrl_event_stream() -> WinResult<(CtrlEventStream, CtrlHandlerToken)> {
+    let stream = CtrlEventStream::new();
+    let token = add_ctrl_handler(stream.handler())?;
+    Ok((stream, token))
+}
+/**
+Adds a handler which sends every console signal to a channel, and returns the receiving end
+of the channel along with a token which removes the handler; see [`remove_ctrl_handler`].
+
+Events are considered handled while the receiver exists, which allows a main loop to receive
+them alongside input events rather than having the process ended.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::console::{self, CtrlEvent};
+# fn main() {
+let (events, token) = console::ctrl_events().unwrap();
+loop {
+    if let Ok(CtrlEvent::CtrlC) = events.try_recv() {
+        println!("Exiting.");
+        break;
+    }
+    // Process input events here.
+}
+console::remove_ctrl_handler(token).unwrap();
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+[`remove_ctrl_handler`]: fn.remove_ctrl_handler.html
+*/
+#[cfg(windows)]
+pub fn ctrl_events() -> WinResult<(Receiver<CtrlEvent>, CtrlHandlerToken)> {
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let token = add_ctrl_handler(move |event| match sender.lock() {
+        Ok(sender) => sender.send(event).is_ok(),
+        Err(_) => false,
+    })?;
+    Ok((receiver, token))
+}
+/**
+Fills the console window with a specified set of text attributes starting
+at a specified location, and returns the number of cells which were filled.
+Unlike [`fill_colors`], this can set attribute flags such as underscores.
+
+# Arguments
+* `attributes` - The attributes to fill the console with.
+* `column` - The column at which the fill should begin.
+* `row` - The row at which the fill should begin.
+* `max_length` - The maximum amount of cells to fill.
+  If None, fill the entirety of the console from the start position.
+
+# Examples
+Underlines the first ten cells.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ConsoleColor, TextAttributes};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut attributes = TextAttributes::new(ConsoleColor::Gray, ConsoleColor::Black);
+attributes.flags.Underscore = true;
+console::fill_attributes(attributes, 0, 0, 10).unwrap();
+# }
+```
+
+# Errors
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`fill_colors`]: fn.fill_colors.html
+*/
+pub fn fill_attributes(
+    attributes: TextAttributes,
+    column: u16,
+    row: u16,
+    max_length: impl Into<Option<u32>>,
+) -> WinResult<u32> {
+    let coords = Vector2::new(column, row);
+    let length = match max_length.into() {
+        Some(len) => len,
+        None => {
+            let size = get_buffer_size()?;
+            let con_length = u32::from(size.x) * u32::from(size.y);
+            let start_pos = u32::from(column) * u32::from(row);
+            if start_pos > con_length {
+                return Ok(0);
+            }
+            con_length - start_pos
+        }
+    };
+    fill_attrs(attributes.into(), length, coords)
+}
+/**
 Fills the console window with a specified character starting
 at a specified location, and returns the number of cells which were filled.
 Note that this only changes the character; the colors of each cell will remain the same.
@@ -94,7 +376,7 @@ Note that this only changes the character; the colors of each cell will remain t
 * `column` - The column at which the fill should begin.
 * `row` - The row at which the fill should begin.
 * `max_length` - The maximum amount of cells to fill.
-If None, fill the entirety of the console from the start position.
+  If None, fill the entirety of the console from the start position.
 
 # Examples
 Fills the entire console with 'Z'.
@@ -103,6 +385,8 @@ Fills the entire console with 'Z'.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::fill_character('Z', 0, 0, None).unwrap();
 # }
 ```
@@ -112,6 +396,8 @@ Fills the first ten cells with 'G'.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::fill_character('G', 0, 0, 10).unwrap();
 # }
 ```
@@ -129,23 +415,20 @@ pub fn fill_character(
     row: u16,
     max_length: impl Into<Option<u32>>,
 ) -> WinResult<u32> {
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
+    let coords = Vector2::new(column, row);
     let length = match max_length.into() {
         Some(len) => len,
         None => {
             let size = get_buffer_size()?;
-            let con_length = DWORD::from(size.x) * DWORD::from(size.y);
-            let start_pos = DWORD::from(column) * DWORD::from(row);
+            let con_length = u32::from(size.x) * u32::from(size.y);
+            let start_pos = u32::from(column) * u32::from(row);
             if start_pos > con_length {
                 return Ok(0);
             }
             con_length - start_pos
         }
     };
-    fill_char(chr as CHAR, length, coords)
+    fill_char(chr, length, coords)
 }
 /**
 Fills the console window with a specified set of colors starting
@@ -153,11 +436,11 @@ at a specified location, and returns the number of cells which were filled.
 
 # Arguments
 * `colors` - The colors to fill the console with. The first item in the tuple is
-the foreground color, and the second item is the background color.
+  the foreground color, and the second item is the background color.
 * `column` - The column at which the fill should begin.
 * `row` - The row at which the fill should begin.
 * `max_length` - The maximum amount of cells to fill.
-If None, fill the entirety of the console from the start position.
+  If None, fill the entirety of the console from the start position.
 
 # Examples
 Fills the entire console with a blue foreground and red background.
@@ -167,6 +450,8 @@ Fills the entire console with a blue foreground and red background.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::fill_colors((ConsoleColor::Blue, ConsoleColor::Red), 0, 0, None).unwrap();
 # }
 ```
@@ -184,24 +469,7 @@ pub fn fill_colors(
     row: u16,
     max_length: impl Into<Option<u32>>,
 ) -> WinResult<u32> {
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
-    let length = match max_length.into() {
-        Some(len) => len,
-        None => {
-            let size = get_buffer_size()?;
-            let con_length = DWORD::from(size.x) * DWORD::from(size.y);
-            let start_pos = DWORD::from(column) * DWORD::from(row);
-            if start_pos > con_length {
-                return Ok(0);
-            }
-            con_length - start_pos
-        }
-    };
-    let attrs = WORD::from(colors.0.get_value() | ((colors.1.get_value()) << 4));
-    fill_attributes(attrs, length, coords)
+    fill_attributes(TextAttributes::from(colors), column, row, max_length)
 }
 /**
 Flushes the console input buffer.
@@ -211,6 +479,8 @@ Flushes the console input buffer.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::flush_input().unwrap();
 # }
 ```
@@ -223,11 +493,7 @@ console::flush_input().unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn flush_input() -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(STDIN);
-        wincon::FlushConsoleInputBuffer(handle)
-    });
-    Ok(())
+    with_backend(|b| b.flush_input())
 }
 /**
 Flushes the console output buffer.
@@ -251,39 +517,79 @@ pub fn flush_output() -> WinResult<()> {
     Ok(())
 }
 /**
-Sends a ctrl signal to a process group which shares the console.
+Detaches the process from its console. The console is closed if no other processes are
+attached to it. Standard handles which referred to the console become invalid.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+console::free().unwrap();
+assert!(!console::is_attached());
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn free() -> WinResult<()> {
+    os_err!(unsafe { wincon::FreeConsole() });
+    Ok(())
+}
+/**
+Generates a Ctrl event for a group of processes which share the console of the calling process.
 
 # Arguments
-* `break_event`- Should a CTRL + BREAK signal be generated? Otherwise, a CTRL + C signal will be generated.
-A CTRL + C signal cannot be generated for a process group.
-* `process_group_id` - The ID of the process group to generate the event on. If None, generate the event on
-processes which share the console.
+* `event` - The event to generate. Only [`CtrlEvent::CtrlC`] and [`CtrlEvent::CtrlBreak`]
+  can be generated. Processes in a group other than that of the console ignore CTRL + C
+  unless they enable it, so CTRL + BREAK should be used to stop them.
+* `group` - The processes to generate the event for. A `u32` or a `&Child` can be given
+  to target the process group with that ID.
 
 # Examples
-Generates a CTRL event.
+Starts a program in a new process group, and asks it to stop.
 
-```
+```no_run
 # extern crate winconsole;
-# use winconsole::console;
+# use winconsole::console::{self, CtrlEvent};
+# use std::process::Command;
 # fn main() {
-console::generate_ctrl_event(false, None).unwrap();
+let mut child = console::spawn_process_group(&mut Command::new("ping").arg("-t").arg("::1")).unwrap();
+console::generate_ctrl_event(CtrlEvent::CtrlBreak, &child).unwrap();
+child.wait().unwrap();
 # }
 ```
 
 # Errors
+* [`ArgumentError`]: Returned if `event` cannot be generated.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+[`CtrlEvent::CtrlBreak`]: enum.CtrlEvent.html#variant.CtrlBreak
+[`CtrlEvent::CtrlC`]: enum.CtrlEvent.html#variant.CtrlC
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
-pub fn generate_ctrl_event(
-    break_event: bool,
-    process_group_id: impl Into<Option<u32>>,
-) -> WinResult<()> {
-    let id: u32 = match process_group_id.into() {
-        None => 0,
-        Some(id) => id,
+#[cfg(windows)]
+pub fn generate_ctrl_event(event: CtrlEvent, group: impl Into<ProcessGroup>) -> WinResult<()> {
+    if event != CtrlEvent::CtrlC && event != CtrlEvent::CtrlBreak {
+        throw_err!(ArgumentError::new(
+            "event",
+            "only CTRL + C and CTRL + BREAK events can be generated"
+        ));
+    }
+
+    let event = match event {
+        CtrlEvent::CtrlC => wincon::CTRL_C_EVENT,
+        _ => wincon::CTRL_BREAK_EVENT,
+    };
+    let id = match group.into() {
+        ProcessGroup::Console => 0,
+        ProcessGroup::Id(id) => id,
     };
-    let event = bool_to_num!(break_event) as u32;
     os_err!(unsafe { wincon::GenerateConsoleCtrlEvent(event, id) });
     Ok(())
 }
@@ -313,31 +619,45 @@ console::getch(false).unwrap();
 [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
+#[cfg(windows)]
 pub fn getch(suppress: bool) -> WinResult<char> {
-    let old_mode = get_input_mode()?;
-    let mut mode = old_mode;
-    mode.EchoInput = false;
-    mode.LineInput = false;
-    set_input_mode(mode)?;
-
-    let mut res: CHAR = 0;
-    os_err!(unsafe {
-        let mut num: DWORD = 0;
-        let handle = handle!(STDIN);
-        let buffer_p = &mut res as *mut CHAR as *mut VOID;
-        let control_p: *mut CONSOLE_READCONSOLE_CONTROL = ptr::null_mut();
-        consoleapi::ReadConsoleA(handle, buffer_p, 1, &mut num, control_p)
-    });
-    let res = res as u8;
-    set_input_mode(old_mode)?;
-
-    if !suppress {
-        let out = io::stdout();
-        let mut out = out.lock();
-        out.write_all(&[res])?;
-        out.flush()?;
+    ConsoleInput::stdin()?.getch(suppress)
+}
+/**
+Returns the IDs of the processes which are attached to the console, including this one.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+let processes = console::get_attached_processes().unwrap();
+println!("{} processes share this console.", processes.len());
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs, such as if the process is not attached to a console.
+
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn get_attached_processes() -> WinResult<Vec<u32>> {
+    let mut buffer: Vec<DWORD> = vec![0; 16];
+    loop {
+        let count = unsafe {
+            let buffer_p = buffer.as_mut_ptr();
+            wincon::GetConsoleProcessList(buffer_p, buffer.len() as DWORD)
+        };
+        os_err!(count);
+
+        let count = count as usize;
+        if count <= buffer.len() {
+            buffer.truncate(count);
+            return Ok(buffer);
+        }
+        buffer.resize(count, 0);
     }
-    Ok(res as char)
 }
 /**
 Returns the current background color of the console.
@@ -347,6 +667,8 @@ Returns the current background color of the console.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let background = console::get_background_color().unwrap();
 println!("Background color: {}", background);
 # }
@@ -360,8 +682,7 @@ println!("Background color: {}", background);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_background_color() -> WinResult<ConsoleColor> {
-    let attrs = get_text_attributes()?;
-    Ok(ConsoleColor::from((attrs & 0xF0) >> 4))
+    Ok(get_text_attributes()?.background)
 }
 /**
 Returns the size of the console output buffer.
@@ -371,6 +692,8 @@ Returns the size of the console output buffer.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let buffer_size = console::get_buffer_size().unwrap();
 console::set_buffer_size(buffer_size.x + 1, buffer_size.y + 1);
 # }
@@ -384,8 +707,47 @@ console::set_buffer_size(buffer_size.x + 1, buffer_size.y + 1);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_buffer_size() -> WinResult<Vector2<u16>> {
-    let coords = get_screen_buffer_info()?.dwSize;
-    Ok(Vector2::new(coords.X as u16, coords.Y as u16))
+    Ok(get_screen_buffer_info()?.buffer_size)
+}
+/**
+Returns a report of what the standard streams are attached to, and which console features
+are available. This does not fail; features which cannot be determined, such as those of
+a console which is not attached, are reported as unavailable.
+
+Checking for virtual terminal support may briefly enable virtual terminal processing on the
+console output; the previous output mode is restored afterwards.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+let capabilities = console::get_capabilities();
+println!("Standard output: {:?}", capabilities.output);
+println!("Virtual terminal sequences: {}", capabilities.virtual_terminal);
+println!("Mouse input: {}", capabilities.mouse_input);
+# }
+```
+*/
+pub fn get_capabilities() -> ConsoleCapabilities {
+    let input = get_stream_kind(StandardStream::Input);
+    let output = get_stream_kind(StandardStream::Output);
+    let error = get_stream_kind(StandardStream::Error);
+
+    let console_output = output == StreamKind::Console;
+    let mouse_input = input == StreamKind::Console
+        && with_backend(|b| b.get_num_mouse_buttons()).is_ok_and(|num| num > 0);
+    let unicode_font = output == StreamKind::Pty
+        || (console_output && get_font().is_ok_and(|font| font.family & TMPF_TRUETYPE != 0));
+    ConsoleCapabilities {
+        input,
+        output,
+        error,
+        virtual_terminal: output == StreamKind::Pty
+            || (console_output && supports_virtual_terminal()),
+        mouse_input,
+        unicode_font,
+    }
 }
 /**
 Returns a `CodePageInfo` object which contains information about the `CodePage`.
@@ -413,10 +775,11 @@ println!("{}", info.name);
 [`FromUtf16Error`]: ../errors/enum.WinError.html#FromUtf16.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
+#[cfg(windows)]
 pub fn get_code_page_info(page: CodePage) -> WinResult<CodePageInfo> {
-    let mut info: CPINFOEXA = unsafe { mem::zeroed() };
+    let mut info: CPINFOEXW = unsafe { mem::zeroed() };
     let identifier: u16 = page.into();
-    os_err!(unsafe { winnls::GetCPInfoExA(u32::from(identifier), 0, &mut info) });
+    os_err!(unsafe { winnls::GetCPInfoExW(u32::from(identifier), 0, &mut info) });
 
     let mut cpi = CodePageInfo::default();
     cpi.max_char_size = info.MaxCharSize as u8;
@@ -424,7 +787,7 @@ pub fn get_code_page_info(page: CodePage) -> WinResult<CodePageInfo> {
     cpi.lead_byte = info.LeadByte;
     cpi.unicode_default = String::from_utf16(&[info.UnicodeDefaultChar])?;
     cpi.code_page = CodePage::from(info.CodePage as u16);
-    cpi.name = buf_to_str!(info.CodePageName);
+    cpi.name = utf16::decode(&info.CodePageName)?;
 
     Ok(cpi)
 }
@@ -437,6 +800,8 @@ Returns the RGB color value of a `ConsoleColor`.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let black = console::get_color(ConsoleColor::Black).unwrap();
 println!("{:?}", black);
 # }
@@ -455,6 +820,8 @@ The indices of the returned array correspond with `ConsoleColor` values.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mapping = console::get_color_mapping().unwrap();
 let black = mapping[0];
 println!("{:?}", black);
@@ -469,12 +836,26 @@ println!("{:?}", black);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_color_mapping() -> WinResult<[RGB8; 16]> {
-    let colors = get_screen_buffer_info_ex()?.ColorTable;
-    let mut ret = [RGB8 { r: 0, g: 0, b: 0 }; 16];
-    for i in 0..16 {
-        ret[i] = make_rgb!(colors[i]);
-    }
-    Ok(ret)
+    with_backend(|b| b.get_color_mapping())
+}
+/**
+Returns the metric used to find the nearest palette color to colors passed to
+[`set_background`] and [`set_foreground`] on the current thread.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ColorMetric};
+# fn main() {
+assert_eq!(console::get_color_metric(), ColorMetric::default());
+# }
+```
+
+[`set_background`]: fn.set_background.html
+[`set_foreground`]: fn.set_foreground.html
+*/
+pub fn get_color_metric() -> ColorMetric {
+    COLOR_METRIC.with(|metric| *metric.borrow())
 }
 /**
 Returns the current position of the console cursor.
@@ -484,6 +865,8 @@ Returns the current position of the console cursor.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let position = console::get_cursor_position().unwrap();
 println!("{:?}", position);
 # }
@@ -497,8 +880,7 @@ println!("{:?}", position);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_cursor_position() -> WinResult<Vector2<u16>> {
-    let pos = get_screen_buffer_info()?.dwCursorPosition;
-    Ok(Vector2::new(pos.X as u16, pos.Y as u16))
+    Ok(get_screen_buffer_info()?.cursor_position)
 }
 /**
 Returns the size of the console cursor.
@@ -509,6 +891,8 @@ The size of the console cursor will always be between 0 and 100 (inclusive).
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let cursor_size = console::get_cursor_size().unwrap();
 println!("{}", cursor_size);
 # }
@@ -522,8 +906,7 @@ println!("{}", cursor_size);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_cursor_size() -> WinResult<u8> {
-    let info = get_cursor_info()?;
-    Ok(info.dwSize as u8)
+    with_backend(|b| b.get_cursor_size())
 }
 /**
 Returns information about the current console font.
@@ -533,6 +916,8 @@ Returns information about the current console font.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let font = console::get_font().unwrap();
 println!("{}", font.name);
 # }
@@ -548,15 +933,7 @@ println!("{}", font.name);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_font() -> WinResult<ConsoleFont> {
-    let info = get_font_info_ex(false)?;
-    let size = info.dwFontSize;
-    Ok(ConsoleFont {
-        family: info.FontFamily,
-        index: info.nFont,
-        name: buf_to_str!(info.FaceName),
-        size: Vector2::new(size.X as u16, size.Y as u16),
-        weight: info.FontWeight,
-    })
+    with_backend(|b| b.get_font())
 }
 /**
 Returns the current foreground color of the console.
@@ -566,6 +943,8 @@ Returns the current foreground color of the console.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let foreground = console::get_foreground_color().unwrap();
 println!("Foreground color: {}", foreground);
 # }
@@ -579,8 +958,7 @@ println!("Foreground color: {}", foreground);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_foreground_color() -> WinResult<ConsoleColor> {
-    let attrs = get_text_attributes()?;
-    Ok(ConsoleColor::from(attrs & 0xF))
+    Ok(get_text_attributes()?.foreground)
 }
 /**
 Returns a `HistoryInfo` object containing information about console input history settings.
@@ -590,6 +968,8 @@ Returns a `HistoryInfo` object containing information about console input histor
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let history_settings = console::get_history_info().unwrap();
 println!("{:?}", history_settings);
 # }
@@ -601,16 +981,7 @@ println!("{:?}", history_settings);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_history_info() -> WinResult<HistoryInfo> {
-    let mut info: CONSOLE_HISTORY_INFO = unsafe { mem::zeroed() };
-    info.cbSize = mem::size_of::<CONSOLE_HISTORY_INFO>() as DWORD;
-    os_err!(unsafe { wincon::GetConsoleHistoryInfo(&mut info) });
-
-    let mut history = HistoryInfo::default();
-    history.size = info.HistoryBufferSize;
-    history.number_of_buffers = info.NumberOfHistoryBuffers;
-    history.duplicates_allowed = info.dwFlags & 0x1 == 0;
-
-    Ok(history)
+    with_backend(|b| b.get_history_info())
 }
 /**
 Returns the input code page used by the console.
@@ -626,7 +997,7 @@ println!("{}", page);
 ```
 */
 pub fn get_input_code_page() -> CodePage {
-    CodePage::from(unsafe { consoleapi::GetConsoleCP() } as u16)
+    with_backend(|b| b.get_input_code_page()).unwrap_or(CodePage::None)
 }
 /**
 Returns settings related to console input.
@@ -636,6 +1007,8 @@ Returns settings related to console input.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mode = console::get_input_mode().unwrap();
 println!("{}", mode);
 # }
@@ -649,8 +1022,7 @@ println!("{}", mode);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_input_mode() -> WinResult<InputSettings> {
-    let mode = get_mode(STDIN)?;
-    Ok(InputSettings::from(mode))
+    with_backend(|b| b.get_input_mode())
 }
 /**
 Returns a list of installed code pages.
@@ -672,6 +1044,7 @@ for page in pages {
 
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
+#[cfg(windows)]
 pub fn get_installed_code_pages() -> WinResult<Vec<CodePage>> {
     get_code_pages(1)
 }
@@ -684,6 +1057,8 @@ based on the current font and the size of the display.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let largest_size = console::get_largest_window_size().unwrap();
 println!("Largest size: {:?}", largest_size);
 # }
@@ -697,16 +1072,7 @@ println!("Largest size: {:?}", largest_size);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_largest_window_size() -> WinResult<Vector2<u16>> {
-    let coord = unsafe {
-        let handle = handle!(STDOUT);
-        wincon::GetLargestConsoleWindowSize(handle)
-    };
-
-    if coord.X == 0 && coord.Y == 0 {
-        return os_err!();
-    }
-
-    Ok(Vector2::new(coord.X as u16, coord.Y as u16))
+    with_backend(|b| b.get_largest_window_size())
 }
 /**
 Returns the original title of the console window.
@@ -716,6 +1082,8 @@ Returns the original title of the console window.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let original_title = console::get_original_title().unwrap();
 println!("{}", original_title);
 # }
@@ -723,20 +1091,15 @@ println!("{}", original_title);
 
 # Errors
 * [`FromUtf8Error`]: Returned if an error occurs while converting to a string.
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
 [`FromUtf8Error`]: ../errors/enum.WinError.html#FromUtf8.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_original_title() -> WinResult<String> {
-    let mut buffer: [CHAR; MAX_PATH] = [0; MAX_PATH];
-
-    let length = unsafe {
-        let buffer_p = &mut buffer[0] as *mut CHAR;
-        wincon::GetConsoleOriginalTitleA(buffer_p, MAX_PATH as u32)
-    };
-    os_err!(length, true);
-    Ok(buf_to_str!(buffer))
+    with_backend(|b| b.get_original_title())
 }
 /**
 Returns the output code page used by the console.
@@ -752,7 +1115,7 @@ println!("{}", page);
 ```
 */
 pub fn get_output_code_page() -> CodePage {
-    CodePage::from(unsafe { consoleapi::GetConsoleOutputCP() } as u16)
+    with_backend(|b| b.get_output_code_page()).unwrap_or(CodePage::None)
 }
 /**
 Returns settings related to console output.
@@ -762,6 +1125,8 @@ Returns settings related to console output.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mode = console::get_output_mode().unwrap();
 println!("{}", mode);
 # }
@@ -775,8 +1140,7 @@ println!("{}", mode);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_output_mode() -> WinResult<OutputSettings> {
-    let mode = get_mode(STDOUT)?;
-    Ok(OutputSettings::from(mode))
+    with_backend(|b| b.get_output_mode())
 }
 /**
 Returns the current scroll position of a window scroll bar.
@@ -789,6 +1153,8 @@ Returns the current scroll position of a window scroll bar.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let vertical_position = console::get_scroll_position(true).unwrap();
 println!("{}", vertical_position);
 # }
@@ -802,11 +1168,11 @@ println!("{}", vertical_position);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_scroll_position(vertical: bool) -> WinResult<u16> {
-    let rect = get_screen_buffer_info()?.srWindow;
+    let rect = get_screen_buffer_info()?.window;
     if vertical {
-        Ok(rect.Top as u16)
+        Ok(rect.top)
     } else {
-        Ok(rect.Left as u16)
+        Ok(rect.left)
     }
 }
 /**
@@ -817,6 +1183,8 @@ Returns a `SelectionInfo` object containing information about console selection.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let selection = console::get_selection_info().unwrap();
 println!("{:?}", selection);
 # }
@@ -828,27 +1196,7 @@ println!("{:?}", selection);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_selection_info() -> WinResult<SelectionInfo> {
-    let mut info: CONSOLE_SELECTION_INFO = unsafe { mem::zeroed() };
-    os_err!(unsafe { wincon::GetConsoleSelectionInfo(&mut info) });
-
-    let anchor = info.dwSelectionAnchor;
-    let rect = info.srSelection;
-    let flags = info.dwFlags;
-    let rect = Rect::new(
-        rect.Top as u16,
-        rect.Left as u16,
-        rect.Bottom as u16,
-        rect.Top as u16,
-    );
-
-    let mut selection = SelectionInfo::default();
-    selection.anchor = Vector2::new(anchor.X as u16, anchor.Y as u16);
-    selection.empty = flags & 0x2 == 0;
-    selection.mouse_down = flags & 0x8 != 0;
-    selection.rect = rect;
-    selection.selecting = flags & 0x1 != 0;
-
-    Ok(selection)
+    with_backend(|b| b.get_selection_info())
 }
 /**
 Returns a `ConsoleState` object containing information about the current state of the console.
@@ -856,7 +1204,7 @@ Returns a `ConsoleState` object containing information about the current state o
 # Arguments
 * `copy_output` - Should the state contain information about the output buffer?
 * `copy_all` - Should the state copy all of the output buffer (as opposed to reading the buffer from
-the start to the current cursor position)?
+  the start to the current cursor position)?
 
 # Examples
 Retrieves the state of the console, copying the output up to the location of the
@@ -866,6 +1214,8 @@ console cursor.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let state = console::get_state(true, false).unwrap();
 println!("{}", state.output.len());
 # }
@@ -906,11 +1256,33 @@ pub fn get_state(copy_output: bool, copy_all: bool) -> WinResult<ConsoleState> {
             u32::from(buffer_size.x) * u32::from(cursor_position.y) + u32::from(cursor_position.x)
         };
         state.output = read_output(0, 0, length)?;
-        state.output_colors = read_output_colors(0, 0, length)?;
+        state.output_colors = read_output_attributes(0, 0, length)?;
     }
     Ok(state)
 }
 /**
+Returns what a standard stream is attached to: a console, a pipe, a file or a pty.
+Console functions which operate on a stream are only available if it is attached to a console.
+
+# Arguments
+* `stream` - The standard stream to query.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, StandardStream, StreamKind};
+# fn main() {
+if console::get_stream_kind(StandardStream::Output) == StreamKind::Console {
+    let size = console::get_buffer_size().unwrap();
+    println!("The buffer is {} columns wide.", size.x);
+}
+# }
+```
+*/
+pub fn get_stream_kind(stream: StandardStream) -> StreamKind {
+    with_backend(|b| Ok(b.get_stream_kind(stream))).unwrap_or_else(|_| detect_stream_kind(stream))
+}
+/**
 Returns a list of supported code pages.
 
 # Examples
@@ -930,10 +1302,36 @@ for page in pages {
 
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
+#[cfg(windows)]
 pub fn get_supported_code_pages() -> WinResult<Vec<CodePage>> {
     get_code_pages(2)
 }
 /**
+Returns the text attributes which are applied to text subsequently written to the console.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let attributes = console::get_text_attributes().unwrap();
+println!("Colors: {} on {}", attributes.foreground, attributes.background);
+# }
+```
+
+# Errors
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+pub fn get_text_attributes() -> WinResult<TextAttributes> {
+    Ok(TextAttributes::from(get_screen_buffer_info()?.attributes))
+}
+/**
 Returns the title of the console window.
 
 # Examples
@@ -941,6 +1339,8 @@ Returns the title of the console window.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let title = console::get_title().unwrap();
 println!("{}", title);
 # }
@@ -948,20 +1348,15 @@ println!("{}", title);
 
 # Errors
 * [`FromUtf8Error`]: Returned if an error occurs while converting to a string.
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
 [`FromUtf8Error`]: ../errors/enum.WinError.html#FromUtf8.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_title() -> WinResult<String> {
-    let mut buffer: [CHAR; MAX_PATH] = [0; MAX_PATH];
-
-    let length = unsafe {
-        let buffer_p = &mut buffer[0] as *mut CHAR;
-        wincon::GetConsoleTitleA(buffer_p, MAX_PATH as u32)
-    };
-    os_err!(length, true);
-    Ok(buf_to_str!(buffer))
+    with_backend(|b| b.get_title())
 }
 /**
 Returns the size of the window in character cells.
@@ -972,6 +1367,8 @@ These dimensions also serve as minimum values for the size of the buffer.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let size = console::get_window_size().unwrap();
 println!("Minimum columns: {}. Minimum rows: {}.", size.x, size.y);
 # }
@@ -985,13 +1382,30 @@ println!("Minimum columns: {}. Minimum rows: {}.", size.x, size.y);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_window_size() -> WinResult<Vector2<u16>> {
-    let rect = get_screen_buffer_info()?.srWindow;
+    let rect = get_screen_buffer_info()?.window;
     Ok(Vector2::new(
-        (rect.Right - rect.Left + 1) as u16,
-        (rect.Bottom - rect.Top + 1) as u16,
+        rect.right - rect.left + 1,
+        rect.bottom - rect.top + 1,
     ))
 }
 /**
+Is the process attached to a console?
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+println!("Attached? {}", console::is_attached());
+# }
+```
+*/
+#[cfg(windows)]
+pub fn is_attached() -> bool {
+    let mut id: DWORD = 0;
+    unsafe { wincon::GetConsoleProcessList(&mut id, 1) != 0 }
+}
+/**
 Returns a boolean representing whether or not the console cursor is visible.
 
 # Examples
@@ -999,6 +1413,8 @@ Returns a boolean representing whether or not the console cursor is visible.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let visible = console::is_cursor_visible().unwrap();
 println!("Is the cursor visible? {}", visible);
 # }
@@ -1012,8 +1428,7 @@ println!("Is the cursor visible? {}", visible);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn is_cursor_visible() -> WinResult<bool> {
-    let info = get_cursor_info()?;
-    Ok(info.bVisible == 1)
+    with_backend(|b| b.is_cursor_visible())
 }
 /**
 Returns a boolean representing whether or not the supplied value is a valid code page.
@@ -1032,6 +1447,7 @@ assert_eq!(valid, false);
 # }
 ```
 */
+#[cfg(windows)]
 pub fn is_valid_code_page(identifier: u16) -> bool {
     let valid = unsafe { winnls::IsValidCodePage(u32::from(identifier)) };
     valid != 0
@@ -1052,6 +1468,8 @@ Maps `ConsoleColor::Black` to white.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 use rgb::RGB8;
 console::map_color(ConsoleColor::Black, RGB8 { r: 255, g: 255, b: 255 }).unwrap();
 # }
@@ -1065,11 +1483,11 @@ console::map_color(ConsoleColor::Black, RGB8 { r: 255, g: 255, b: 255 }).unwrap(
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn map_color(color: ConsoleColor, rgb: RGB8) -> WinResult<()> {
-    let mut info = get_screen_buffer_info_ex()?;
-    info.ColorTable[color.get_value() as usize] = make_colorref!(rgb);
-    info.srWindow.Bottom += 1;
-    info.srWindow.Right += 1;
-    set_screen_buffer_info_ex(&mut info)
+    with_backend(|b| {
+        let mut mapping = b.get_color_mapping()?;
+        mapping[color.get_value() as usize] = rgb;
+        b.set_color_mapping(&mapping)
+    })
 }
 /**
 Moves data from a rectangle of the console output to another point in the output.
@@ -1090,6 +1508,8 @@ so the contents of the console screen buffer outside the clipping rectangle are
 # use winconsole::console;
 # use winconsole::console::{Rect, Vector2};
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let scroll = Rect::new(0, 0, 10, 10);
 let dest = Vector2::new(0, 3);
 console::move_contents(scroll, dest, None, None, None, None).unwrap();
@@ -1111,10 +1531,7 @@ pub fn move_contents(
     fill_fg_color: impl Into<Option<ConsoleColor>>,
     fill_bg_color: impl Into<Option<ConsoleColor>>,
 ) -> WinResult<()> {
-    let fill_char = match fill_char.into() {
-        Some(f) => f,
-        None => ' ',
-    };
+    let fill_char = fill_char.into().unwrap_or(' ');
     let attrs = {
         let fg_color = match fill_fg_color.into() {
             Some(f) => f,
@@ -1124,48 +1541,10 @@ pub fn move_contents(
             Some(f) => f,
             None => get_background_color()?,
         };
-        ((bg_color as WORD) << 4) | (fg_color as WORD)
-    };
-    let dest = COORD {
-        X: dest.x,
-        Y: dest.y,
-    };
-    let scroll = SMALL_RECT {
-        Top: scroll.top as i16,
-        Bottom: scroll.bottom as i16,
-        Left: scroll.left as i16,
-        Right: scroll.right as i16,
+        ((bg_color as u16) << 4) | (fg_color as u16)
     };
 
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let scroll_p = &scroll as *const SMALL_RECT;
-        let clip_p = match clip.into() {
-            Some(c) => {
-                let rect = SMALL_RECT {
-                    Top: c.top as i16,
-                    Bottom: c.bottom as i16,
-                    Left: c.left as i16,
-                    Right: c.right as i16,
-                };
-                &rect as *const SMALL_RECT
-            }
-            None => ptr::null(),
-        };
-        let info_p = {
-            let mut char_info: CHAR_INFO = mem::zeroed();
-            let mut chr: CHAR_INFO_Char = mem::zeroed();
-            *chr.AsciiChar_mut() = fill_char as CHAR;
-
-            char_info.Attributes = attrs;
-            char_info.Char = chr;
-            &char_info as *const CHAR_INFO
-        };
-
-        wincon::ScrollConsoleScreenBufferA(handle, scroll_p, clip_p, dest, info_p)
-    });
-
-    Ok(())
+    with_backend(|b| b.scroll_contents(scroll, clip.into(), dest, fill_char, attrs))
 }
 /**
 Reads a string from the console output starting at a specified location.
@@ -1184,6 +1563,8 @@ Reads the entire console output buffer.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let output = console::read_output(0, 0, None).unwrap();
 println!("{}", output.len());
 # }
@@ -1214,8 +1595,8 @@ pub fn read_output(column: u16, row: u16, max_length: impl Into<Option<u32>>) ->
         Some(len) => len,
         None => {
             let size = get_buffer_size()?;
-            let con_length = DWORD::from(size.x) * DWORD::from(size.y);
-            let start_pos = DWORD::from(column) * DWORD::from(row);
+            let con_length = u32::from(size.x) * u32::from(size.y);
+            let start_pos = u32::from(column) * u32::from(row);
             if start_pos > con_length {
                 return Ok(String::new());
             }
@@ -1227,38 +1608,29 @@ pub fn read_output(column: u16, row: u16, max_length: impl Into<Option<u32>>) ->
         return Ok(String::new());
     }
 
-    let mut num: DWORD = 0;
-    let mut buffer: Box<[CHAR]> = buf!(max_length as usize);
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
-
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let buffer_p = &mut (*buffer)[0] as *mut CHAR;
-        wincon::ReadConsoleOutputCharacterA(handle, buffer_p, max_length, coords, &mut num)
-    });
-    Ok(buf_to_str!(buffer))
+    let coords = Vector2::new(column, row);
+    with_backend(|b| b.read_characters(coords, max_length))
 }
 /**
-Reads colors from the console output starting at a specified location, and returns a vector of tuples.
-The first item in each tuple is the foreground color, and the second is the background color.
+Reads text attributes from the console output starting at a specified location.
+Unlike [`read_output_colors`], this includes attribute flags such as underscores.
 
 # Arguments
 * `column` - The column at which reading should begin.
 * `row` - The row at which reading should begin.
-* `max_length` - The maximum amount of colors to read. If None, the entire output buffer is read.
+* `max_length` - The maximum amount of cells to read. If None, the entire output buffer is read.
 
 # Examples
-Prints the colors in the first cell.
+Checks whether the first cell is underlined.
 
 ```
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
-let colors = console::read_output_colors(0, 0, 1).unwrap();
-println!("{} {}", colors[0].0, colors[0].1);
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let attributes = console::read_output_attributes(0, 0, 1).unwrap();
+println!("{}", attributes[0].flags.Underscore);
 # }
 ```
 
@@ -1270,12 +1642,14 @@ println!("{} {}", colors[0].0, colors[0].1);
 [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
 [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`read_output_colors`]: fn.read_output_colors.html
 */
-pub fn read_output_colors(
+pub fn read_output_attributes(
     column: u16,
     row: u16,
     max_length: impl Into<Option<u32>>,
-) -> WinResult<Vec<(ConsoleColor, ConsoleColor)>> {
+) -> WinResult<Vec<TextAttributes>> {
     let buffer_size = get_buffer_size()?;
     if column >= buffer_size.x {
         throw_err!(ArgumentError::new(
@@ -1289,8 +1663,8 @@ pub fn read_output_colors(
         Some(len) => len,
         None => {
             let size = get_buffer_size()?;
-            let con_length = DWORD::from(size.x) * DWORD::from(size.y);
-            let start_pos = DWORD::from(column) * DWORD::from(row);
+            let con_length = u32::from(size.x) * u32::from(size.y);
+            let start_pos = u32::from(column) * u32::from(row);
             if start_pos > con_length {
                 return Ok(Vec::new());
             }
@@ -1301,28 +1675,128 @@ pub fn read_output_colors(
         return Ok(Vec::new());
     }
 
-    let mut num: DWORD = 0;
-    let mut buffer: Box<[WORD]> = buf!(max_length as usize);
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
+    let coords = Vector2::new(column, row);
+    let buffer = with_backend(|b| b.read_attributes(coords, max_length))?;
+    Ok(buffer.into_iter().map(TextAttributes::from).collect())
+}
+/**
+Reads colors from the console output starting at a specified location, and returns a vector of tuples.
+The first item in each tuple is the foreground color, and the second is the background color.
+
+# Arguments
+* `column` - The column at which reading should begin.
+* `row` - The row at which reading should begin.
+* `max_length` - The maximum amount of colors to read. If None, the entire output buffer is read.
+
+# Examples
+Prints the colors in the first cell.
+
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let colors = console::read_output_colors(0, 0, 1).unwrap();
+println!("{} {}", colors[0].0, colors[0].1);
+# }
+```
+
+# Errors
+* [`ArgumentError`]: Returned if `column` or `row` is not within the output buffer.
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+pub fn read_output_colors(
+    column: u16,
+    row: u16,
+    max_length: impl Into<Option<u32>>,
+) -> WinResult<Vec<(ConsoleColor, ConsoleColor)>> {
+    let attributes = read_output_attributes(column, row, max_length)?;
+    Ok(attributes.iter().map(TextAttributes::get_colors).collect())
+}
+/**
+Reads the cells within a rectangle of the console output.
+
+# Arguments
+* `rect` - The rectangle to read.
+
+# Examples
+Reads the first row of the console output.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, Rect};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let columns = console::get_buffer_size().unwrap().x;
+let cells = console::read_region(Rect::new(0, 0, columns - 1, 0)).unwrap();
+for cell in cells.get_cells() {
+    print!("{}", cell.character);
+}
+# }
+```
+
+# Errors
+* [`ArgumentError`]: Returned if `rect` is inverted or not within the output buffer.
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+pub fn read_region(rect: Rect) -> WinResult<CellBuffer> {
+    check_region(rect)?;
+    with_backend(|b| b.read_region(rect))
+}
+/**
+Removes a handler which was added with [`add_ctrl_handler`] or [`ctrl_events`].
+Once no handlers remain, signals are processed normally.
+
+# Arguments
+* `token` - The token which was returned when the handler was added.
+
+# Errors
+* [`IoError`]: Returned if an OS error occurs.
+
+[`add_ctrl_handler`]: fn.add_ctrl_handler.html
+[`ctrl_events`]: fn.ctrl_events.html
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn remove_ctrl_handler(token: CtrlHandlerToken) -> WinResult<()> {
+    let mut handlers = lock_ctrl_handlers();
+    let length = handlers.len();
+    handlers.retain(|&(id, _)| id != token.id);
+    if length > 0 && handlers.is_empty() {
+        os_err!(unsafe { consoleapi::SetConsoleCtrlHandler(Some(dispatch_ctrl_event), 0) });
+    }
+    Ok(())
+}
+/**
+Removes the backend installed on the current thread by [`set_backend`], so that
+functions in this module operate on the console attached to the process again.
+On other platforms than Windows, they return an error until a backend is installed.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+console::reset_backend();
+# }
+```
 
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let buffer_p = &mut (*buffer)[0] as *mut WORD;
-        wincon::ReadConsoleOutputAttribute(handle, buffer_p, max_length, coords, &mut num)
-    });
-    let vec: Vec<(ConsoleColor, ConsoleColor)> = buffer
-        .iter()
-        .map(|attrs| {
-            (
-                ConsoleColor::from(attrs & 0xF),
-                ConsoleColor::from((attrs & 0xF0) >> 4),
-            )
-        })
-        .collect();
-    Ok(vec)
+[`set_backend`]: fn.set_backend.html
+*/
+pub fn reset_backend() {
+    BACKEND.with(|current| *current.borrow_mut() = None);
 }
 /**
 Scrolls the console window by the specified amount relative to its current position, in character cells.
@@ -1340,6 +1814,8 @@ Scrolls down 5 rows.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::scroll_by(5, true).unwrap();
 # }
 ```
@@ -1353,12 +1829,12 @@ console::scroll_by(5, true).unwrap();
 */
 pub fn scroll_by(amount: i16, vertical: bool) -> WinResult<()> {
     let position = if vertical {
-        get_screen_buffer_info()?.srWindow.Top
+        get_screen_buffer_info()?.window.top
     } else {
-        get_screen_buffer_info()?.srWindow.Left
+        get_screen_buffer_info()?.window.left
     };
 
-    scroll(position + amount, 1, vertical)
+    scroll(position as i16 + amount, vertical)
 }
 /**
 Scrolls the console window to the specified position, in character cells.
@@ -1376,6 +1852,8 @@ Scrolls to the top of the window.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::scroll_to(0, true).unwrap();
 # }
 ```
@@ -1388,7 +1866,44 @@ console::scroll_to(0, true).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn scroll_to(position: u16, vertical: bool) -> WinResult<()> {
-    scroll(position as i16, 1, vertical)
+    scroll(position as i16, vertical)
+}
+/**
+Sets the background color of text subsequently written to the console, which may be an
+xterm 256-color index or an RGB color.
+
+If virtual terminal processing is enabled in the output mode, colors other than palette
+colors are set by writing a virtual terminal sequence to the console output, and are
+displayed exactly. Otherwise, the nearest palette color under the current color mapping
+is used, as measured by the metric set with [`set_color_metric`].
+
+# Arguments
+* `color` - The color which will be assigned to the background.
+
+# Examples
+```
+# extern crate rgb;
+# extern crate winconsole;
+# use rgb::RGB8;
+# use winconsole::console;
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+console::set_background(RGB8 { r: 30, g: 30, b: 60 }).unwrap();
+# }
+```
+
+# Errors
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an IO or OS error occurs.
+
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`set_color_metric`]: fn.set_color_metric.html
+*/
+pub fn set_background(color: impl Into<Color>) -> WinResult<()> {
+    set_color(color.into(), true)
 }
 /**
 Sets the background color of the console.
@@ -1402,6 +1917,8 @@ Sets the background color of the console.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_background_color(ConsoleColor::DarkBlue).unwrap();
 # }
 ```
@@ -1414,10 +1931,50 @@ console::set_background_color(ConsoleColor::DarkBlue).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_background_color(color: ConsoleColor) -> WinResult<()> {
-    let color = color as WORD;
-    let current = get_text_attributes()?;
+    let mut attributes = get_text_attributes()?;
+    attributes.background = color;
+    set_text_attributes(attributes)
+}
+/**
+Installs a backend which the functions in this module will use on the current thread,
+in place of the console attached to the process.
+On other platforms than Windows, there is no default console, so functions in this module
+return an [`IoError`] until a backend such as a [`MemoryConsole`] is installed.
+[`get_stream_kind`] still reports what the standard streams are really attached to:
+terminals are reported as ptys, and pipes and files as such.
+
+Functions which are not part of [`ConsoleBackend`] (such as `beep`, `getch` and the
+code page enumeration functions) always operate on the real console, and are only
+available on Windows.
+
+# Arguments
+* `backend` - The backend to install.
+
+# Examples
+Runs console functions against an in-memory console.
+
+```
+# extern crate winconsole;
+# use winconsole::console;
+# use winconsole::console::MemoryConsole;
+# fn main() {
+let memory = MemoryConsole::new(80, 25);
+console::set_backend(memory.clone());
+
+console::write_output("Hello, world!", 0, 0).unwrap();
+assert_eq!(memory.lines()[0], "Hello, world!");
+
+console::reset_backend();
+# }
+```
 
-    set_text_attributes(color << 4 | (current & 0xF))
+[`ConsoleBackend`]: trait.ConsoleBackend.html
+[`get_stream_kind`]: fn.get_stream_kind.html
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+[`MemoryConsole`]: struct.MemoryConsole.html
+*/
+pub fn set_backend<B: ConsoleBackend + 'static>(backend: B) {
+    BACKEND.with(|current| *current.borrow_mut() = Some(Box::new(backend)));
 }
 /**
 Sets the size of the output buffer.
@@ -1431,6 +1988,8 @@ Sets the size of the output buffer.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_buffer_size(200, 100).unwrap();
 # }
 ```
@@ -1458,16 +2017,7 @@ pub fn set_buffer_size(width: u16, height: u16) -> WinResult<()> {
             "height must be more than window height"
         ));
     }
-    let coords = COORD {
-        X: width as i16,
-        Y: height as i16,
-    };
-
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::SetConsoleScreenBufferSize(handle, coords)
-    });
-    Ok(())
+    with_backend(|b| b.set_buffer_size(Vector2::new(width, height)))
 }
 /**
 Sets the color mapping of the console.
@@ -1482,6 +2032,8 @@ The indices of the array correspond with `ConsoleColor` values.
 # extern crate rgb;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 use rgb::RGB8;
 let mut mapping = console::get_color_mapping().unwrap();
 mapping[0] = RGB8 { r: 255, g: 255, b: 255 };
@@ -1497,24 +2049,41 @@ console::set_color_mapping(&mapping).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_color_mapping(mapping: &[RGB8; 16]) -> WinResult<()> {
-    let mut info = get_screen_buffer_info_ex()?;
-    let mut colors = info.ColorTable;
-    for i in 0..16 {
-        let color = mapping[i];
-        colors[i] = make_colorref!(color);
-    }
+    with_backend(|b| b.set_color_mapping(mapping))
+}
+/**
+Sets the metric used to find the nearest palette color to colors passed to
+[`set_background`] and [`set_foreground`] on the current thread, when they can't be
+displayed exactly. The default is `ColorMetric::Cie76`.
+
+# Arguments
+* `metric` - The metric to use.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, Color, ColorMetric};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+console::set_color_metric(ColorMetric::Redmean);
+console::set_foreground(Color::Indexed(208)).unwrap();
+# }
+```
 
-    info.ColorTable = colors;
-    info.srWindow.Bottom += 1;
-    info.srWindow.Right += 1;
-    set_screen_buffer_info_ex(&mut info)
+[`set_background`]: fn.set_background.html
+[`set_foreground`]: fn.set_foreground.html
+*/
+pub fn set_color_metric(metric: ColorMetric) {
+    COLOR_METRIC.with(|current| *current.borrow_mut() = metric);
 }
 /**
 Adds or removes a handler routine from the console.
+See [`add_ctrl_handler`](fn.add_ctrl_handler.html) for a safe alternative which accepts closures.
 
 # Arguments
 * `routine` - The callback function. If this is None, a value of `true` for `add`
-will ignore CTRL + C input, and a value of `false` will restore normal processing.
+  will ignore CTRL + C input, and a value of `false` will restore normal processing.
 * `add` - Should the routine be added or removed?
 
 # Examples
@@ -1541,6 +2110,7 @@ console::set_ctrl_handler(Some(handler), true).unwrap();
 # See
 [`HandlerRoutine`](https://docs.microsoft.com/en-us/windows/console/handlerroutine).
 */
+#[cfg(windows)]
 pub fn set_ctrl_handler(handler: Option<HandlerRoutine>, add: bool) -> WinResult<()> {
     os_err!(unsafe { consoleapi::SetConsoleCtrlHandler(handler, bool_to_num!(add)) });
     Ok(())
@@ -1559,6 +2129,8 @@ Sets the cursor position to the start position of the console.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_cursor_position(0, 0).unwrap();
 # }
 ```
@@ -1587,15 +2159,7 @@ pub fn set_cursor_position(column: u16, row: u16) -> WinResult<()> {
         ));
     }
 
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::SetConsoleCursorPosition(handle, coords)
-    });
-    Ok(())
+    with_backend(|b| b.set_cursor_position(Vector2::new(column, row)))
 }
 /**
 Sets the size of the console cursor. Must be between 0 and 100 (inclusive).
@@ -1608,6 +2172,8 @@ Sets the size of the console cursor. Must be between 0 and 100 (inclusive).
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_cursor_size(50).unwrap();
 # }
 ```
@@ -1629,10 +2195,7 @@ pub fn set_cursor_size(size: u8) -> WinResult<()> {
         ));
     }
 
-    let mut info = get_cursor_info()?;
-    info.dwSize = u32::from(size);
-    set_cursor_info(info)?;
-    Ok(())
+    with_backend(|b| b.set_cursor_size(size))
 }
 /**
 Sets the visibility of the console cursor.
@@ -1647,6 +2210,8 @@ Hides the console cursor.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_cursor_visible(false).unwrap();
 # }
 ```
@@ -1659,10 +2224,7 @@ console::set_cursor_visible(false).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_cursor_visible(visible: bool) -> WinResult<()> {
-    let mut info = get_cursor_info()?;
-    info.bVisible = bool_to_num!(visible);
-    set_cursor_info(info)?;
-    Ok(())
+    with_backend(|b| b.set_cursor_visible(visible))
 }
 /**
 Sets information about the console font.
@@ -1677,6 +2239,8 @@ Changes the console font to Consolas.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mut font = console::get_font().unwrap();
 font.name = "Consolas".to_string();
 console::set_font(&font).unwrap();
@@ -1691,16 +2255,43 @@ console::set_font(&font).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_font(font: &ConsoleFont) -> WinResult<()> {
-    let mut info: CONSOLE_FONT_INFOEX = unsafe { mem::zeroed() };
-    info.nFont = font.index as DWORD;
-    info.dwFontSize = COORD {
-        X: font.size.x as i16,
-        Y: font.size.y as i16,
-    };
-    info.FontFamily = font.family as UINT;
-    info.FontWeight = font.weight as UINT;
-    info.FaceName = str_to_buf_w!(font.name, 32);
-    set_font_info_ex(&mut info, false)
+    with_backend(|b| b.set_font(font))
+}
+/**
+Sets the foreground color of text subsequently written to the console, which may be an
+xterm 256-color index or an RGB color.
+
+If virtual terminal processing is enabled in the output mode, colors other than palette
+colors are set by writing a virtual terminal sequence to the console output, and are
+displayed exactly. Otherwise, the nearest palette color under the current color mapping
+is used, as measured by the metric set with [`set_color_metric`].
+
+# Arguments
+* `color` - The color which will be assigned to the foreground.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, Color};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+console::set_foreground(Color::Indexed(208)).unwrap();
+println!("This is orange, or close to it.");
+# }
+```
+
+# Errors
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an IO or OS error occurs.
+
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`set_color_metric`]: fn.set_color_metric.html
+*/
+pub fn set_foreground(color: impl Into<Color>) -> WinResult<()> {
+    set_color(color.into(), false)
 }
 /**
 Sets the foreground color of the console.
@@ -1714,6 +2305,8 @@ Sets the foreground color of the console.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_foreground_color(ConsoleColor::Red).unwrap();
 # }
 ```
@@ -1726,10 +2319,9 @@ console::set_foreground_color(ConsoleColor::Red).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_foreground_color(color: ConsoleColor) -> WinResult<()> {
-    let color = color as WORD;
-    let current = get_text_attributes()?;
-
-    set_text_attributes((current & 0xF0) | color)
+    let mut attributes = get_text_attributes()?;
+    attributes.foreground = color;
+    set_text_attributes(attributes)
 }
 /**
 Sets information about console input history settings.
@@ -1742,6 +2334,8 @@ Sets information about console input history settings.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mut history_settings = console::get_history_info().unwrap();
 history_settings.duplicates_allowed = false;
 console::set_history_info(history_settings).unwrap();
@@ -1754,14 +2348,7 @@ console::set_history_info(history_settings).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_history_info(history: HistoryInfo) -> WinResult<()> {
-    let mut info: CONSOLE_HISTORY_INFO = unsafe { mem::zeroed() };
-    info.cbSize = mem::size_of::<CONSOLE_HISTORY_INFO>() as DWORD;
-    info.HistoryBufferSize = history.size;
-    info.NumberOfHistoryBuffers = history.number_of_buffers;
-    info.dwFlags = bool_to_num!(!history.duplicates_allowed);
-
-    os_err!(unsafe { wincon::SetConsoleHistoryInfo(&mut info) });
-    Ok(())
+    with_backend(|b| b.set_history_info(history))
 }
 /**
 Sets the input code page to be used by the console.
@@ -1775,6 +2362,8 @@ Sets the input code page to be used by the console.
 # use winconsole::console;
 # use winconsole::console::CodePage;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_input_code_page(CodePage::utf_8).unwrap();
 # }
 ```
@@ -1793,9 +2382,7 @@ pub fn set_input_code_page(page: CodePage) -> WinResult<()> {
             "page cannot be CodePage::None or CodePage::Invalid"
         ));
     }
-    let page: u16 = page.into();
-    os_err!(unsafe { wincon::SetConsoleCP(u32::from(page)) });
-    Ok(())
+    with_backend(|b| b.set_input_code_page(page))
 }
 /**
 Sets settings related to console input.
@@ -1808,6 +2395,8 @@ Sets settings related to console input.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mut mode = console::get_input_mode().unwrap();
 mode.InsertMode = true;
 console::set_input_mode(mode).unwrap();
@@ -1830,9 +2419,7 @@ pub fn set_input_mode(settings: InputSettings) -> WinResult<()> {
             "disabling LineInput requires EchoInput to be disabled"
         ));
     }
-    let mode: u32 = settings.into();
-    set_mode(STDIN, mode)?;
-    Ok(())
+    with_backend(|b| b.set_input_mode(settings))
 }
 /**
 Sets the output code page to be used by the console.
@@ -1846,6 +2433,8 @@ Sets the output code page to be used by the console.
 # use winconsole::console;
 # use winconsole::console::CodePage;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_output_code_page(CodePage::IBM437).unwrap();
 # }
 ```
@@ -1864,9 +2453,7 @@ pub fn set_output_code_page(page: CodePage) -> WinResult<()> {
             "page cannot be CodePage::None or CodePage::Invalid"
         ));
     }
-    let page: u16 = page.into();
-    os_err!(unsafe { wincon::SetConsoleOutputCP(u32::from(page)) });
-    Ok(())
+    with_backend(|b| b.set_output_code_page(page))
 }
 /**
 Sets settings related to console output.
@@ -1879,6 +2466,8 @@ Sets settings related to console output.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let mut mode = console::get_output_mode().unwrap();
 mode.WrapAtEol = false;
 mode.DisableNewlineAutoReturn = true;
@@ -1894,8 +2483,7 @@ console::set_output_mode(mode).unwrap();
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_output_mode(settings: OutputSettings) -> WinResult<()> {
-    let mode: u32 = settings.into();
-    set_mode(STDOUT, mode)
+    with_backend(|b| b.set_output_mode(settings))
 }
 /**
 Sets the state of the console to a `ConsoleState`.
@@ -1910,6 +2498,8 @@ Sets the state of the console to a `ConsoleState`.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 println!("Message 1.");
 let state = console::get_state(true, false).unwrap();
 println!("Message 2.");
@@ -1945,7 +2535,7 @@ pub fn set_state(state: &ConsoleState, clear: bool, write_output: bool) -> WinRe
         self::clear()?;
     }
     if write_output {
-        write_output_colors(&state.output_colors, 0, 0)?;
+        write_output_attributes(&state.output_colors, 0, 0)?;
         self::write_output(&state.output, 0, 0)?;
     }
     set_cursor_position(state.cursor_position.x, state.cursor_position.y)?;
@@ -1953,6 +2543,41 @@ pub fn set_state(state: &ConsoleState, clear: bool, write_output: bool) -> WinRe
     Ok(())
 }
 /**
+Sets the text attributes which are applied to text subsequently written to the console.
+Unlike [`set_foreground_color`] and [`set_background_color`], this can set attribute flags
+such as underscores.
+
+# Arguments
+* `attributes` - The attributes to apply.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ConsoleColor, TextAttributes};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut attributes = TextAttributes::new(ConsoleColor::Yellow, ConsoleColor::Black);
+attributes.flags.Underscore = true;
+console::set_text_attributes(attributes).unwrap();
+println!("Underlined text.");
+# }
+```
+
+# Errors
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`set_background_color`]: fn.set_background_color.html
+[`set_foreground_color`]: fn.set_foreground_color.html
+*/
+pub fn set_text_attributes(attributes: TextAttributes) -> WinResult<()> {
+    with_backend(|b| b.set_text_attributes(attributes.into()))
+}
+/**
 Sets the title of the console window.
 
 # Arguments
@@ -1963,23 +2588,21 @@ Sets the title of the console window.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::set_title("My Console").unwrap();
 # }
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_title(title: &str) -> WinResult<()> {
-    let mut buffer = str_to_buf!(title, MAX_PATH);
-    os_err!(unsafe {
-        let buffer_p = &mut buffer[0] as *mut CHAR;
-        wincon::SetConsoleTitleA(buffer_p)
-    });
-
-    Ok(())
+    with_backend(|b| b.set_title(title))
 }
 /**
 Sets the size of the console window in character cells.
@@ -1990,6 +2613,12 @@ If the screen buffer is too small for the new size, it is resized as well.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# {
+# let memory = console::MemoryConsole::new(80, 25);
+# memory.set_largest_window_size(console::Vector2::new(240, 67));
+# console::set_backend(memory);
+# }
 let size = console::get_window_size().unwrap();
 console::set_window_size(size.x + 10, size.y + 10).unwrap();
 # }
@@ -1997,8 +2626,8 @@ console::set_window_size(size.x + 10, size.y + 10).unwrap();
 
 # Errors
 * [`ArgumentError`]: Returned if either `columns` or `rows` is less than zero,
-exceeds the maximum window size, or if addition will result in a value which is
-greater than the i16 maximum value.
+  exceeds the maximum window size, or if addition will result in a value which is
+  greater than the i16 maximum value.
 * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
 * [`IoError`]: Returned if an OS error occurs.
 
@@ -2016,74 +2645,101 @@ pub fn set_window_size(columns: u16, rows: u16) -> WinResult<()> {
         throw_err!(ArgumentError::new("rows", "rows must greater than zero"));
     }
 
-    let csbi = get_screen_buffer_info()?;
-    let buffer_size = csbi.dwSize;
-    let mut window_rect = csbi.srWindow;
+    let info = get_screen_buffer_info()?;
+    let buffer_size = info.buffer_size;
+    let mut window_rect = info.window;
 
     let mut needs_resize = false;
-    let mut resize_x = buffer_size.X;
-    let mut resize_y = buffer_size.Y;
+    let mut resize_x = buffer_size.x;
+    let mut resize_y = buffer_size.y;
 
-    let columns_i16 = columns as i16;
-    let rows_i16 = rows as i16;
-    let left = window_rect.Left;
-    let top = window_rect.Top;
+    let max = i16::MAX as u16;
+    let left = window_rect.left;
+    let top = window_rect.top;
 
-    if buffer_size.X < left + columns_i16 {
-        if window_rect.Left >= std::i16::MAX - columns_i16 {
+    if buffer_size.x < left + columns {
+        if left >= max - columns {
             throw_err!(ArgumentError::new(
                 "columns",
                 "(window left + columns) is greater than i16::MAX"
             ));
         }
-        resize_x = left + columns_i16;
+        resize_x = left + columns;
         needs_resize = true;
     }
-    if buffer_size.Y < top + rows_i16 {
-        if window_rect.Top >= std::i16::MAX - rows_i16 {
+    if buffer_size.y < top + rows {
+        if top >= max - rows {
             throw_err!(ArgumentError::new(
                 "rows",
                 "(window top + rows) is greater than i16::MAX"
             ));
         }
-        resize_y = top + rows_i16;
+        resize_y = top + rows;
         needs_resize = true;
     }
 
     if needs_resize {
-        set_buffer_size(resize_x as u16, resize_y as u16)?;
+        set_buffer_size(resize_x, resize_y)?;
     }
 
-    window_rect.Right = left + columns_i16 - 1;
-    window_rect.Bottom = top + rows_i16 - 1;
-    unsafe {
-        let handle = handle!(STDOUT);
-        let rect_p = &window_rect as *const SMALL_RECT;
-
-        if wincon::SetConsoleWindowInfo(handle, 1, rect_p) == 0 {
-            let err = os_err!();
+    window_rect.right = left + columns - 1;
+    window_rect.bottom = top + rows - 1;
+    with_backend(|b| {
+        if let Err(err) = b.set_window_rect(window_rect) {
             if needs_resize {
-                wincon::SetConsoleScreenBufferSize(handle, buffer_size);
+                let _ = b.set_buffer_size(buffer_size);
             }
 
-            let max = wincon::GetLargestConsoleWindowSize(handle);
-            if columns > (max.X as u16) {
+            let max = b.get_largest_window_size()?;
+            if columns > max.x {
                 throw_err!(ArgumentError::new(
                     "columns",
                     "columns is greater than maximum window columns"
                 ));
-            } else if rows > (max.Y as u16) {
+            } else if rows > max.y {
                 throw_err!(ArgumentError::new(
                     "rows",
                     "rows is greater than maximum window rows"
                 ));
             }
 
-            return err;
+            return Err(err);
         }
-    }
+        Ok(())
+    })
+}
+/**
+Starts a command as the root of a new process group, which Ctrl events can be generated for
+with [`generate_ctrl_event`]. The process shares the console of the calling process,
+but does not receive the Ctrl events which are generated for the console.
 
-    Ok(())
+Any creation flags which were set on the command are replaced.
+
+# Arguments
+* `command` - The command to start.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::console::{self, CtrlEvent, ProcessGroup};
+# use std::process::Command;
+# fn main() {
+let child = console::spawn_process_group(&mut Command::new("cargo").arg("test")).unwrap();
+let group = ProcessGroup::from(&child);
+console::generate_ctrl_event(CtrlEvent::CtrlBreak, group).unwrap();
+# }
+```
+
+# Errors
+* [`IoError`]: Returned if the process cannot be started.
+
+[`generate_ctrl_event`]: fn.generate_ctrl_event.html
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+#[cfg(windows)]
+pub fn spawn_process_group(command: &mut Command) -> WinResult<Child> {
+    let child = command.creation_flags(CREATE_NEW_PROCESS_GROUP).spawn()?;
+    Ok(child)
 }
 /**
 Writes characters to the output at a specified position, and returns the
@@ -2105,6 +2761,8 @@ Writes `"Hello, world!"` on the 10th row starting at the 10th column.
 # extern crate winconsole;
 # use winconsole::console;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 console::write_output("Hello, world!", 10, 10).unwrap();
 # }
 ```
@@ -2130,24 +2788,72 @@ pub fn write_output(string: &str, column: u16, row: u16) -> WinResult<u32> {
         throw_err!(ArgumentError::new("row", "row must be within the buffer"));
     }
 
-    let mut num: DWORD = 0;
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
-    let chars: Box<[CHAR]> = str_to_buf!(string);
-    let length = chars.len() as DWORD;
-    if length == 0 {
+    if string.is_empty() {
         return Ok(0);
     }
 
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let chars_p = &(*chars)[0] as *const CHAR;
-        wincon::WriteConsoleOutputCharacterA(handle, chars_p, length, coords, &mut num)
-    });
+    let coords = Vector2::new(column, row);
+    with_backend(|b| b.write_characters(string, coords))
+}
+/**
+Changes the text attributes of the console output starting at a specified position, and returns the
+number of cells which were written to.
+Unlike [`write_output_colors`], this can set attribute flags such as underscores.
+
+# Arguments
+* `attributes` - The attributes to write to the console.
+* `column` - The column at which writing will begin.
+* `row` - The row at which writing will begin.
+
+# Examples
+Underlines four cells starting at <0, 0>.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ConsoleColor, TextAttributes};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut attributes = TextAttributes::new(ConsoleColor::White, ConsoleColor::Black);
+attributes.flags.Underscore = true;
+console::write_output_attributes(&[attributes; 4], 0, 0).unwrap();
+# }
+```
+
+# Errors
+* [`ArgumentError`]: Returned if `column` or `row` is not within the output buffer.
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
+
+[`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+
+[`write_output_colors`]: fn.write_output_colors.html
+*/
+pub fn write_output_attributes(
+    attributes: &[TextAttributes],
+    column: u16,
+    row: u16,
+) -> WinResult<u32> {
+    let buffer_size = get_buffer_size()?;
+
+    if column >= buffer_size.x {
+        throw_err!(ArgumentError::new(
+            "column",
+            "column must be within the buffer"
+        ));
+    } else if row >= buffer_size.y {
+        throw_err!(ArgumentError::new("row", "row must be within the buffer"));
+    }
+
+    if attributes.is_empty() {
+        return Ok(0);
+    }
 
-    Ok(num)
+    let coords = Vector2::new(column, row);
+    let attrs: Vec<u16> = attributes.iter().map(|attrs| u16::from(*attrs)).collect();
+    with_backend(|b| b.write_attributes(&attrs, coords))
 }
 /**
 Changes the output colors starting at a specified position, and returns the
@@ -2155,7 +2861,7 @@ number of cells which were written to.
 
 # Arguments
 * `colors` - The colors to write to the console. The first item in each tuple is the foreground color,
-and the second is the background color.
+  and the second is the background color.
 * `column` - The column at which writing will begin.
 * `row` - The row at which writing will begin.
 
@@ -2167,6 +2873,8 @@ Writes colors to the console starting at <0, 0>.
 # use winconsole::console;
 # use winconsole::console::ConsoleColor;
 # fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
 let colors = vec![
    (ConsoleColor::Red, ConsoleColor::Blue),
    (ConsoleColor::Blue, ConsoleColor::Red),
@@ -2191,72 +2899,117 @@ pub fn write_output_colors(
     column: u16,
     row: u16,
 ) -> WinResult<u32> {
-    let buffer_size = get_buffer_size()?;
+    let attributes: Vec<TextAttributes> =
+        colors.iter().cloned().map(TextAttributes::from).collect();
+    write_output_attributes(&attributes, column, row)
+}
 
-    if column >= buffer_size.x {
-        throw_err!(ArgumentError::new(
-            "column",
-            "column must be within the buffer"
-        ));
-    } else if row >= buffer_size.y {
-        throw_err!(ArgumentError::new("row", "row must be within the buffer"));
-    }
+/**
+Writes cells to a rectangle of the console output, starting with the top-left cell of
+`buffer`. Cells which lie outside of either `rect` or `buffer` are left unchanged.
 
-    let mut num: DWORD = 0;
-    let coords = COORD {
-        X: column as i16,
-        Y: row as i16,
-    };
-    let length = colors.len() as DWORD;
-    if length == 0 {
-        return Ok(0);
-    }
+# Arguments
+* `rect` - The rectangle to write to.
+* `buffer` - The cells to write.
 
-    let attrs: Box<[WORD]> = {
-        let res: Vec<WORD> = colors
-            .iter()
-            .map(|&(ref fg, ref bg)| WORD::from(fg.get_value() | ((bg.get_value()) << 4)))
-            .collect();
-        res.into_boxed_slice()
-    };
+# Examples
+Draws a red box in the top-left corner of the console output.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, Cell, CellBuffer, ConsoleColor, Rect};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let cell = Cell::new(' ', ConsoleColor::Gray, ConsoleColor::Red);
+let buffer = CellBuffer::filled(4, 2, cell);
+console::write_region(Rect::new(0, 0, 3, 1), &buffer).unwrap();
+# }
+```
 
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let attrs_p = &(*attrs)[0] as *const WORD;
-        wincon::WriteConsoleOutputAttribute(handle, attrs_p, length, coords, &mut num)
-    });
+# Errors
+* [`ArgumentError`]: Returned if `rect` is inverted or not within the output buffer.
+* [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+* [`IoError`]: Returned if an OS error occurs.
 
-    Ok(num)
+[`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+[`IoError`]: ../errors/enum.WinError.html#Io.v
+*/
+pub fn write_region(rect: Rect, buffer: &CellBuffer) -> WinResult<()> {
+    check_region(rect)?;
+    with_backend(|b| b.write_region(rect, buffer))
 }
 
-fn fill_attributes(attributes: WORD, length: DWORD, coords: COORD) -> WinResult<DWORD> {
-    let mut num: DWORD = 0;
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::FillConsoleOutputAttribute(handle, attributes, length, coords, &mut num)
-    });
-    Ok(num)
+fn check_region(rect: Rect) -> WinResult<()> {
+    let buffer_size = get_buffer_size()?;
+    if rect.left > rect.right || rect.top > rect.bottom {
+        throw_err!(ArgumentError::new("rect", "rect must not be inverted"));
+    } else if rect.right >= buffer_size.x || rect.bottom >= buffer_size.y {
+        throw_err!(ArgumentError::new("rect", "rect must be within the buffer"));
+    }
+    Ok(())
+}
+fn fill_attrs(attributes: u16, length: u32, coords: Vector2<u16>) -> WinResult<u32> {
+    with_backend(|b| b.fill_attributes(attributes, length, coords))
+}
+fn fill_char(character: char, length: u32, coords: Vector2<u16>) -> WinResult<u32> {
+    with_backend(|b| b.fill_character(character, length, coords))
+}
+// Used when no backend is installed. Terminals are reported as ptys, since they interpret virtual terminal sequences but
+// console functions can't operate on them.
+#[cfg(unix)]
+fn detect_stream_kind(stream: StandardStream) -> StreamKind {
+    let fd = match stream {
+        StandardStream::Input => 0,
+        StandardStream::Output => 1,
+        StandardStream::Error => 2,
+    };
+    // The descriptor is only borrowed, so it must not be closed when the file is dropped.
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    if file.is_terminal() {
+        return StreamKind::Pty;
+    }
+    match file.metadata() {
+        Ok(metadata) if metadata.file_type().is_fifo() => StreamKind::Pipe,
+        Ok(metadata) if metadata.is_file() => StreamKind::File,
+        _ => StreamKind::Unknown,
+    }
 }
-fn fill_char(character: CHAR, length: DWORD, coords: COORD) -> WinResult<DWORD> {
-    let mut num: DWORD = 0;
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::FillConsoleOutputCharacterA(handle, character, length, coords, &mut num)
-    });
-    Ok(num)
+#[cfg(not(unix))]
+fn detect_stream_kind(_stream: StandardStream) -> StreamKind {
+    StreamKind::Unknown
+}
+#[cfg(windows)]
+unsafe extern "system" fn dispatch_ctrl_event(event_type: DWORD) -> BOOL {
+    let event = match event_type {
+        wincon::CTRL_C_EVENT => CtrlEvent::CtrlC,
+        wincon::CTRL_BREAK_EVENT => CtrlEvent::CtrlBreak,
+        wincon::CTRL_CLOSE_EVENT => CtrlEvent::Close,
+        wincon::CTRL_LOGOFF_EVENT => CtrlEvent::Logoff,
+        wincon::CTRL_SHUTDOWN_EVENT => CtrlEvent::Shutdown,
+        _ => return 0,
+    };
+    // The handlers are copied, so that they can add or remove handlers themselves.
+    let handlers: Vec<CtrlHandler> = lock_ctrl_handlers()
+        .iter()
+        .rev()
+        .map(|(_, handler)| handler.clone())
+        .collect();
+    // Unwinding out of the handler routine would abort the process.
+    let handled = panic::catch_unwind(AssertUnwindSafe(|| {
+        handlers.iter().any(|handler| handler(event))
+    }));
+    bool_to_num!(handled.unwrap_or(false))
 }
+#[cfg(windows)]
 fn get_code_pages(flags: u32) -> WinResult<Vec<CodePage>> {
-    unsafe extern "system" fn enum_pages(ptr: *mut i8) -> i32 {
-        let mut identifier = String::new();
-        let mut offset = 0;
-        loop {
-            let chr = *ptr.offset(offset) as u8 as char;
-            if chr == '\0' {
-                break;
-            }
-            identifier.push(chr);
-            offset += 1;
+    unsafe extern "system" fn enum_pages(ptr: *mut WCHAR) -> i32 {
+        let mut length = 0;
+        while *ptr.add(length) != 0 {
+            length += 1;
         }
+        let identifier = utf16::decode_lossy(std::slice::from_raw_parts(ptr, length));
         if let Ok(id) = identifier.parse::<u16>() {
             let cp = CodePage::from(id);
             if cp != CodePage::Invalid {
@@ -2270,7 +3023,7 @@ fn get_code_pages(flags: u32) -> WinResult<Vec<CodePage>> {
         1
     }
 
-    os_err!(unsafe { winnls::EnumSystemCodePagesA(Some(enum_pages), flags) });
+    os_err!(unsafe { winnls::EnumSystemCodePagesW(Some(enum_pages), flags) });
 
     match PAGES.lock() {
         Ok(mut pages) => {
@@ -2286,124 +3039,118 @@ fn get_code_pages(flags: u32) -> WinResult<Vec<CodePage>> {
         }
     }
 }
-fn get_cursor_info() -> WinResult<CONSOLE_CURSOR_INFO> {
-    let mut info: CONSOLE_CURSOR_INFO = unsafe { mem::zeroed() };
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::GetConsoleCursorInfo(handle, &mut info)
-    });
-    Ok(info)
-}
-fn get_font_info_ex(maximum: bool) -> WinResult<CONSOLE_FONT_INFOEX> {
-    let mut info: CONSOLE_FONT_INFOEX = unsafe { mem::zeroed() };
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        info.cbSize = mem::size_of::<CONSOLE_FONT_INFOEX>() as DWORD;
-        wincon::GetCurrentConsoleFontEx(handle, bool_to_num!(maximum), &mut info)
-    });
-    Ok(info)
-}
-fn get_mode(handle_id: DWORD) -> WinResult<DWORD> {
-    let mut num: DWORD = 0;
-    os_err!(unsafe {
-        let handle = handle!(handle_id);
-        consoleapi::GetConsoleMode(handle, &mut num)
-    });
-    Ok(num)
-}
-fn get_screen_buffer_info() -> WinResult<CONSOLE_SCREEN_BUFFER_INFO> {
-    let mut csbi: CONSOLE_SCREEN_BUFFER_INFO = unsafe { mem::zeroed() };
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::GetConsoleScreenBufferInfo(handle, &mut csbi)
-    });
-    Ok(csbi)
-}
-fn get_screen_buffer_info_ex() -> WinResult<CONSOLE_SCREEN_BUFFER_INFOEX> {
-    let mut csbi: CONSOLE_SCREEN_BUFFER_INFOEX = unsafe { mem::zeroed() };
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        csbi.cbSize = mem::size_of::<CONSOLE_SCREEN_BUFFER_INFOEX>() as DWORD;
-        wincon::GetConsoleScreenBufferInfoEx(handle, &mut csbi)
-    });
-    Ok(csbi)
-}
-fn get_text_attributes() -> WinResult<WORD> {
-    let csbi = get_screen_buffer_info()?;
-    Ok(csbi.wAttributes)
-}
-fn scroll(position: i16, absolute: i32, vertical: bool) -> WinResult<()> {
+fn get_screen_buffer_info() -> WinResult<ScreenBufferInfo> {
+    with_backend(|b| b.get_screen_buffer_info())
+}
+#[cfg(windows)]
+fn lock_ctrl_handlers() -> MutexGuard<'static, Vec<(u64, CtrlHandler)>> {
+    match CTRL_HANDLERS.lock() {
+        Ok(handlers) => handlers,
+        Err(err) => err.into_inner(),
+    }
+}
+// Opens the standard handles which are invalid or refer to a closed console on the
+// current console. Redirected handles are left alone.
+#[cfg(windows)]
+fn reopen_std_handles() -> WinResult<()> {
+    let streams = [
+        (StandardStream::Input, STDIN, "CONIN$"),
+        (StandardStream::Output, STDOUT, "CONOUT$"),
+        (StandardStream::Error, STDERR, "CONOUT$"),
+    ];
+    let mut backend = WinApiConsole::new();
+    for &(stream, id, name) in &streams {
+        if backend.get_stream_kind(stream) != StreamKind::Unknown {
+            continue;
+        }
+
+        let handle = ConsoleHandle::open(name)?;
+        os_err!(unsafe { processenv::SetStdHandle(id, handle.get_raw()) });
+        // The handle now belongs to the process as a standard handle.
+        handle.into_raw();
+    }
+    Ok(())
+}
+fn scroll(position: i16, vertical: bool) -> WinResult<()> {
     let buffer_size = get_buffer_size()?;
-    let mut rect = get_screen_buffer_info()?.srWindow;
+    let mut rect = get_screen_buffer_info()?.window;
     let mut position = position;
 
     if vertical {
-        let max = buffer_size.y as i16 - rect.Bottom - 1;
-        let diff = rect.Bottom - rect.Top;
+        let diff = rect.bottom - rect.top;
+        let max = (buffer_size.y - diff - 1) as i16;
         if position > max {
             position = max;
         } else if position < 0 {
             position = 0;
         }
-        rect.Top = position;
-        rect.Bottom = position + diff;
+        rect.top = position as u16;
+        rect.bottom = position as u16 + diff;
     } else {
-        let max = buffer_size.x as i16 - rect.Right - 1;
-        let diff = rect.Right - rect.Left;
+        let diff = rect.right - rect.left;
+        let max = (buffer_size.x - diff - 1) as i16;
         if position > max {
             position = max;
         } else if position < 0 {
             position = 0;
         }
-        rect.Left = position;
-        rect.Right = position + diff;
+        rect.left = position as u16;
+        rect.right = position as u16 + diff;
     }
 
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let rect_p = &rect as *const SMALL_RECT;
-        wincon::SetConsoleWindowInfo(handle, absolute, rect_p)
-    });
-    Ok(())
+    with_backend(|b| b.set_window_rect(rect))
 }
-
-fn set_cursor_info(value: CONSOLE_CURSOR_INFO) -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let value_p = &value as *const CONSOLE_CURSOR_INFO;
-        wincon::SetConsoleCursorInfo(handle, value_p)
-    });
-    Ok(())
+fn set_color(color: Color, background: bool) -> WinResult<()> {
+    let metric = get_color_metric();
+    with_backend(|b| b.set_color(color, background, metric))
 }
+fn supports_virtual_terminal() -> bool {
+    let mode = match get_output_mode() {
+        Ok(mode) => mode,
+        Err(_) => return false,
+    };
+    if mode.VirtualTerminalProcessing {
+        return true;
+    }
 
-fn set_font_info_ex(value: &mut CONSOLE_FONT_INFOEX, maximum: bool) -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        value.cbSize = mem::size_of::<CONSOLE_FONT_INFOEX>() as DWORD;
-        let value_p = value as *mut CONSOLE_FONT_INFOEX;
-        wincon::SetCurrentConsoleFontEx(handle, bool_to_num!(maximum), value_p)
-    });
-    Ok(())
-}
-fn set_mode(handle_id: DWORD, value: DWORD) -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(handle_id);
-        consoleapi::SetConsoleMode(handle, value)
-    });
-    Ok(())
-}
-fn set_screen_buffer_info_ex(value: &mut CONSOLE_SCREEN_BUFFER_INFOEX) -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        let value_p = value as *mut CONSOLE_SCREEN_BUFFER_INFOEX;
-        wincon::SetConsoleScreenBufferInfoEx(handle, value_p)
-    });
-    Ok(())
-}
-fn set_text_attributes(value: WORD) -> WinResult<()> {
-    os_err!(unsafe {
-        let handle = handle!(STDOUT);
-        wincon::SetConsoleTextAttribute(handle, value)
-    });
-    Ok(())
+    let mut vt_mode = mode;
+    vt_mode.VirtualTerminalProcessing = true;
+    let supported = set_output_mode(vt_mode).is_ok();
+    if supported {
+        set_output_mode(mode).unwrap_or(());
+    }
+    supported
+}
+#[cfg(windows)]
+pub(crate) fn with_handles<T>(
+    input: Option<HANDLE>,
+    output: Option<HANDLE>,
+    f: impl FnOnce() -> WinResult<T>,
+) -> WinResult<T> {
+    struct Restore(Option<Option<Box<dyn ConsoleBackend>>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                BACKEND.with(|backend| *backend.borrow_mut() = previous);
+            }
+        }
+    }
+
+    let console: Box<dyn ConsoleBackend> = Box::new(WinApiConsole::with_handles(input, output));
+    let previous = BACKEND.with(|backend| backend.replace(Some(console)));
+    let _restore = Restore(Some(previous));
+    f()
+}
+fn with_backend<T>(f: impl FnOnce(&mut dyn ConsoleBackend) -> WinResult<T>) -> WinResult<T> {
+    BACKEND.with(|backend| match *backend.borrow_mut() {
+        Some(ref mut backend) => f(backend.as_mut()),
+        #[cfg(windows)]
+        None => f(&mut WinApiConsole::new()),
+        #[cfg(not(windows))]
+        None => Err(IoError::new(
+            ErrorKind::Unsupported,
+            "no console backend is installed; see console::set_backend",
+        )
+        .into()),
+    })
 }
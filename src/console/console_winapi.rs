@@ -0,0 +1,619 @@
+use super::*;
+use std::char::{self, REPLACEMENT_CHARACTER};
+use std::slice;
+use winapi::ctypes::c_void as VOID;
+use winapi::um::fileapi::{self, FILE_NAME_INFO};
+use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+use winapi::um::minwinbase::FileNameInfo;
+use winapi::um::winbase::{self, FILE_TYPE_DISK, FILE_TYPE_PIPE};
+
+// ReadConsoleOutput and WriteConsoleOutput fail for buffers larger than about 64 KiB.
+const REGION_CHUNK_SIZE: u32 = 8192;
+
+/// The console backend which operates on the console attached to the process.
+pub(crate) struct WinApiConsole {
+    input: Option<HANDLE>,
+    output: Option<HANDLE>,
+}
+
+impl WinApiConsole {
+    /// Returns a backend which operates on the standard input and output handles.
+    pub(crate) fn new() -> WinApiConsole {
+        WinApiConsole::with_handles(None, None)
+    }
+    /// Returns a backend which operates on console handles, using the standard handles
+    /// in place of those which are None.
+    pub(crate) fn with_handles(input: Option<HANDLE>, output: Option<HANDLE>) -> WinApiConsole {
+        WinApiConsole { input, output }
+    }
+
+    fn input(&self) -> WinResult<HANDLE> {
+        match self.input {
+            Some(input) => Ok(input),
+            None => Ok(unsafe { handle!(STDIN) }),
+        }
+    }
+    fn output(&self) -> WinResult<HANDLE> {
+        match self.output {
+            Some(output) => Ok(output),
+            None => Ok(unsafe { handle!(STDOUT) }),
+        }
+    }
+}
+
+impl ConsoleBackend for WinApiConsole {
+    fn fill_attributes(
+        &mut self,
+        attributes: u16,
+        length: u32,
+        position: Vector2<u16>,
+    ) -> WinResult<u32> {
+        let mut num: DWORD = 0;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let coords = make_coord(position);
+            wincon::FillConsoleOutputAttribute(handle, attributes, length, coords, &mut num)
+        });
+        Ok(num)
+    }
+    fn fill_character(&mut self, chr: char, length: u32, position: Vector2<u16>) -> WinResult<u32> {
+        let chr = make_wchar(chr, "chr")?;
+        let mut num: DWORD = 0;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let coords = make_coord(position);
+            wincon::FillConsoleOutputCharacterW(handle, chr, length, coords, &mut num)
+        });
+        Ok(num)
+    }
+    fn flush_input(&mut self) -> WinResult<()> {
+        os_err!(unsafe {
+            let handle = self.input()?;
+            wincon::FlushConsoleInputBuffer(handle)
+        });
+        Ok(())
+    }
+    fn get_color_mapping(&mut self) -> WinResult<[RGB8; 16]> {
+        let colors = get_screen_buffer_info_ex(self.output()?)?.ColorTable;
+        let mut ret = [RGB8 { r: 0, g: 0, b: 0 }; 16];
+        for i in 0..16 {
+            ret[i] = make_rgb!(colors[i]);
+        }
+        Ok(ret)
+    }
+    fn get_cursor_size(&mut self) -> WinResult<u8> {
+        Ok(get_cursor_info(self.output()?)?.dwSize as u8)
+    }
+    fn get_font(&mut self) -> WinResult<ConsoleFont> {
+        let mut info: CONSOLE_FONT_INFOEX = unsafe { mem::zeroed() };
+        os_err!(unsafe {
+            let handle = self.output()?;
+            info.cbSize = mem::size_of::<CONSOLE_FONT_INFOEX>() as DWORD;
+            wincon::GetCurrentConsoleFontEx(handle, 0, &mut info)
+        });
+
+        let size = info.dwFontSize;
+        Ok(ConsoleFont {
+            family: info.FontFamily,
+            index: info.nFont,
+            name: utf16::decode(&info.FaceName)?,
+            size: Vector2::new(size.X as u16, size.Y as u16),
+            weight: info.FontWeight,
+        })
+    }
+    fn get_history_info(&mut self) -> WinResult<HistoryInfo> {
+        let mut info: CONSOLE_HISTORY_INFO = unsafe { mem::zeroed() };
+        info.cbSize = mem::size_of::<CONSOLE_HISTORY_INFO>() as DWORD;
+        os_err!(unsafe { wincon::GetConsoleHistoryInfo(&mut info) });
+
+        Ok(HistoryInfo {
+            size: info.HistoryBufferSize,
+            number_of_buffers: info.NumberOfHistoryBuffers,
+            duplicates_allowed: info.dwFlags & 0x1 == 0,
+        })
+    }
+    fn get_input_code_page(&mut self) -> WinResult<CodePage> {
+        Ok(CodePage::from(unsafe { consoleapi::GetConsoleCP() } as u16))
+    }
+    fn get_input_mode(&mut self) -> WinResult<InputSettings> {
+        Ok(InputSettings::from(get_mode(self.input()?)?))
+    }
+    fn get_largest_window_size(&mut self) -> WinResult<Vector2<u16>> {
+        let coord = unsafe {
+            let handle = self.output()?;
+            wincon::GetLargestConsoleWindowSize(handle)
+        };
+
+        if coord.X == 0 && coord.Y == 0 {
+            return os_err!();
+        }
+
+        Ok(Vector2::new(coord.X as u16, coord.Y as u16))
+    }
+    fn get_num_mouse_buttons(&mut self) -> WinResult<u32> {
+        let mut num: DWORD = 0;
+        os_err!(unsafe { wincon::GetNumberOfConsoleMouseButtons(&mut num) });
+        Ok(num)
+    }
+    fn get_original_title(&mut self) -> WinResult<String> {
+        check_attached()?;
+        let mut buffer: [WCHAR; MAX_PATH] = [0; MAX_PATH];
+
+        let length = unsafe {
+            let buffer_p = &mut buffer[0] as *mut WCHAR;
+            wincon::GetConsoleOriginalTitleW(buffer_p, MAX_PATH as u32)
+        };
+        os_err!(length, true);
+        utf16::decode(&buffer)
+    }
+    fn get_output_code_page(&mut self) -> WinResult<CodePage> {
+        Ok(CodePage::from(
+            unsafe { consoleapi::GetConsoleOutputCP() } as u16
+        ))
+    }
+    fn get_output_mode(&mut self) -> WinResult<OutputSettings> {
+        Ok(OutputSettings::from(get_mode(self.output()?)?))
+    }
+    fn get_screen_buffer_info(&mut self) -> WinResult<ScreenBufferInfo> {
+        let mut csbi: CONSOLE_SCREEN_BUFFER_INFO = unsafe { mem::zeroed() };
+        os_err!(unsafe {
+            let handle = self.output()?;
+            wincon::GetConsoleScreenBufferInfo(handle, &mut csbi)
+        });
+
+        let size = csbi.dwSize;
+        let cursor = csbi.dwCursorPosition;
+        let maximum = csbi.dwMaximumWindowSize;
+        Ok(ScreenBufferInfo {
+            attributes: csbi.wAttributes,
+            buffer_size: Vector2::new(size.X as u16, size.Y as u16),
+            cursor_position: Vector2::new(cursor.X as u16, cursor.Y as u16),
+            maximum_window_size: Vector2::new(maximum.X as u16, maximum.Y as u16),
+            window: make_rect(csbi.srWindow),
+        })
+    }
+    fn get_selection_info(&mut self) -> WinResult<SelectionInfo> {
+        let mut info: CONSOLE_SELECTION_INFO = unsafe { mem::zeroed() };
+        os_err!(unsafe { wincon::GetConsoleSelectionInfo(&mut info) });
+
+        let anchor = info.dwSelectionAnchor;
+        let flags = info.dwFlags;
+
+        Ok(SelectionInfo {
+            anchor: Vector2::new(anchor.X as u16, anchor.Y as u16),
+            empty: flags & 0x2 == 0,
+            mouse_down: flags & 0x8 != 0,
+            rect: make_rect(info.srSelection),
+            selecting: flags & 0x1 != 0,
+        })
+    }
+    fn get_stream_kind(&mut self, stream: StandardStream) -> StreamKind {
+        let handle = match stream {
+            StandardStream::Input => self.input(),
+            StandardStream::Output => self.output(),
+            StandardStream::Error => Ok(unsafe { processenv::GetStdHandle(STDERR) }),
+        };
+        match handle {
+            Ok(handle) => get_handle_kind(handle),
+            Err(_) => StreamKind::Unknown,
+        }
+    }
+    fn get_title(&mut self) -> WinResult<String> {
+        check_attached()?;
+        let mut buffer: [WCHAR; MAX_PATH] = [0; MAX_PATH];
+
+        let length = unsafe {
+            let buffer_p = &mut buffer[0] as *mut WCHAR;
+            wincon::GetConsoleTitleW(buffer_p, MAX_PATH as u32)
+        };
+        os_err!(length, true);
+        utf16::decode(&buffer)
+    }
+    fn is_cursor_visible(&mut self) -> WinResult<bool> {
+        Ok(get_cursor_info(self.output()?)?.bVisible == 1)
+    }
+    fn read_attributes(&mut self, position: Vector2<u16>, length: u32) -> WinResult<Vec<u16>> {
+        let mut num: DWORD = 0;
+        let mut buffer: Box<[WORD]> = buf!(length as usize);
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let buffer_p = &mut (*buffer)[0] as *mut WORD;
+            let coords = make_coord(position);
+            wincon::ReadConsoleOutputAttribute(handle, buffer_p, length, coords, &mut num)
+        });
+
+        let mut vec = buffer.into_vec();
+        vec.truncate(num as usize);
+        Ok(vec)
+    }
+    fn read_characters(&mut self, position: Vector2<u16>, length: u32) -> WinResult<String> {
+        let mut num: DWORD = 0;
+        let mut buffer: Box<[WCHAR]> = buf!(length as usize);
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let buffer_p = &mut (*buffer)[0] as *mut WCHAR;
+            let coords = make_coord(position);
+            wincon::ReadConsoleOutputCharacterW(handle, buffer_p, length, coords, &mut num)
+        });
+        Ok(utf16::decode_lossy(&buffer[..num as usize]))
+    }
+    fn read_region(&mut self, rect: Rect) -> WinResult<CellBuffer> {
+        let columns = rect.right - rect.left + 1;
+        let mut buffer = CellBuffer::new(columns, rect.bottom - rect.top + 1);
+        for chunk in split_region(rect) {
+            let length = usize::from(columns) * usize::from(chunk.bottom - chunk.top + 1);
+            let mut infos: Vec<CHAR_INFO> = vec![unsafe { mem::zeroed() }; length];
+            let mut region = make_small_rect(chunk);
+            os_err!(unsafe {
+                let handle = self.output()?;
+                let size = make_coord(Vector2::new(columns, chunk.bottom - chunk.top + 1));
+                let infos_p = &mut infos[0] as *mut CHAR_INFO;
+                let origin = COORD { X: 0, Y: 0 };
+                wincon::ReadConsoleOutputW(handle, infos_p, size, origin, &mut region)
+            });
+
+            for (i, info) in infos.iter().enumerate() {
+                let unit = unsafe { *info.Char.UnicodeChar() };
+                let chr = char::from_u32(u32::from(unit)).unwrap_or(REPLACEMENT_CHARACTER);
+                let column = (i % usize::from(columns)) as u16;
+                let row = chunk.top - rect.top + (i / usize::from(columns)) as u16;
+                if let Some(cell) = buffer.get_mut(column, row) {
+                    *cell = Cell::from_attributes(chr, info.Attributes);
+                }
+            }
+        }
+        Ok(buffer)
+    }
+    fn scroll_contents(
+        &mut self,
+        scroll: Rect,
+        clip: Option<Rect>,
+        dest: Vector2<i16>,
+        fill_char: char,
+        fill_attributes: u16,
+    ) -> WinResult<()> {
+        let fill_char = make_wchar(fill_char, "fill_char")?;
+        let dest = COORD {
+            X: dest.x,
+            Y: dest.y,
+        };
+        let scroll = make_small_rect(scroll);
+        let clip = clip.map(make_small_rect);
+
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let scroll_p = &scroll as *const SMALL_RECT;
+            let clip_p = match clip {
+                Some(ref rect) => rect as *const SMALL_RECT,
+                None => ptr::null(),
+            };
+            let mut char_info: CHAR_INFO = mem::zeroed();
+            *char_info.Char.UnicodeChar_mut() = fill_char;
+            char_info.Attributes = fill_attributes;
+            let info_p = &char_info as *const CHAR_INFO;
+
+            wincon::ScrollConsoleScreenBufferW(handle, scroll_p, clip_p, dest, info_p)
+        });
+        Ok(())
+    }
+    fn set_buffer_size(&mut self, size: Vector2<u16>) -> WinResult<()> {
+        os_err!(unsafe {
+            let handle = self.output()?;
+            wincon::SetConsoleScreenBufferSize(handle, make_coord(size))
+        });
+        Ok(())
+    }
+    fn set_color(&mut self, color: Color, background: bool, metric: ColorMetric) -> WinResult<()> {
+        let exact = match color {
+            Color::Named(_) => false,
+            _ => self.get_output_mode()?.VirtualTerminalProcessing,
+        };
+        if !exact {
+            return set_nearest_color(self, color, background, metric);
+        }
+
+        let sequence = utf16::encode(&color.get_vt_sequence(background));
+        let mut num: DWORD = 0;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let sequence_p = sequence.as_ptr() as *const VOID;
+            let length = sequence.len() as DWORD;
+            consoleapi::WriteConsoleW(handle, sequence_p, length, &mut num, ptr::null_mut())
+        });
+        Ok(())
+    }
+    fn set_color_mapping(&mut self, mapping: &[RGB8; 16]) -> WinResult<()> {
+        let mut info = get_screen_buffer_info_ex(self.output()?)?;
+        for (i, color) in mapping.iter().enumerate() {
+            info.ColorTable[i] = make_colorref!(color);
+        }
+
+        info.srWindow.Bottom += 1;
+        info.srWindow.Right += 1;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let info_p = &mut info as *mut CONSOLE_SCREEN_BUFFER_INFOEX;
+            wincon::SetConsoleScreenBufferInfoEx(handle, info_p)
+        });
+        Ok(())
+    }
+    fn set_cursor_position(&mut self, position: Vector2<u16>) -> WinResult<()> {
+        os_err!(unsafe {
+            let handle = self.output()?;
+            wincon::SetConsoleCursorPosition(handle, make_coord(position))
+        });
+        Ok(())
+    }
+    fn set_cursor_size(&mut self, size: u8) -> WinResult<()> {
+        let mut info = get_cursor_info(self.output()?)?;
+        info.dwSize = u32::from(size);
+        set_cursor_info(self.output()?, info)
+    }
+    fn set_cursor_visible(&mut self, visible: bool) -> WinResult<()> {
+        let mut info = get_cursor_info(self.output()?)?;
+        info.bVisible = bool_to_num!(visible);
+        set_cursor_info(self.output()?, info)
+    }
+    fn set_font(&mut self, font: &ConsoleFont) -> WinResult<()> {
+        let mut info: CONSOLE_FONT_INFOEX = unsafe { mem::zeroed() };
+        info.cbSize = mem::size_of::<CONSOLE_FONT_INFOEX>() as DWORD;
+        info.nFont = font.index as DWORD;
+        info.dwFontSize = make_coord(font.size);
+        info.FontFamily = font.family as UINT;
+        info.FontWeight = font.weight as UINT;
+        utf16::encode_into(&font.name, &mut info.FaceName);
+
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let info_p = &mut info as *mut CONSOLE_FONT_INFOEX;
+            wincon::SetCurrentConsoleFontEx(handle, 0, info_p)
+        });
+        Ok(())
+    }
+    fn set_history_info(&mut self, history: HistoryInfo) -> WinResult<()> {
+        let mut info: CONSOLE_HISTORY_INFO = unsafe { mem::zeroed() };
+        info.cbSize = mem::size_of::<CONSOLE_HISTORY_INFO>() as DWORD;
+        info.HistoryBufferSize = history.size;
+        info.NumberOfHistoryBuffers = history.number_of_buffers;
+        info.dwFlags = bool_to_num!(!history.duplicates_allowed);
+
+        os_err!(unsafe { wincon::SetConsoleHistoryInfo(&mut info) });
+        Ok(())
+    }
+    fn set_input_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        let page: u16 = page.into();
+        os_err!(unsafe { wincon::SetConsoleCP(u32::from(page)) });
+        Ok(())
+    }
+    fn set_input_mode(&mut self, settings: InputSettings) -> WinResult<()> {
+        set_mode(self.input()?, settings.into())
+    }
+    fn set_output_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        let page: u16 = page.into();
+        os_err!(unsafe { wincon::SetConsoleOutputCP(u32::from(page)) });
+        Ok(())
+    }
+    fn set_output_mode(&mut self, settings: OutputSettings) -> WinResult<()> {
+        set_mode(self.output()?, settings.into())
+    }
+    fn set_text_attributes(&mut self, attributes: u16) -> WinResult<()> {
+        os_err!(unsafe {
+            let handle = self.output()?;
+            wincon::SetConsoleTextAttribute(handle, attributes)
+        });
+        Ok(())
+    }
+    fn set_title(&mut self, title: &str) -> WinResult<()> {
+        check_attached()?;
+        let buffer = utf16::encode_with_nul(title);
+        os_err!(unsafe {
+            let buffer_p = &buffer[0] as *const WCHAR;
+            wincon::SetConsoleTitleW(buffer_p)
+        });
+        Ok(())
+    }
+    fn set_window_rect(&mut self, rect: Rect) -> WinResult<()> {
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let rect = make_small_rect(rect);
+            let rect_p = &rect as *const SMALL_RECT;
+            wincon::SetConsoleWindowInfo(handle, 1, rect_p)
+        });
+        Ok(())
+    }
+    fn write_attributes(&mut self, attributes: &[u16], position: Vector2<u16>) -> WinResult<u32> {
+        let length = attributes.len() as DWORD;
+        if length == 0 {
+            return Ok(0);
+        }
+
+        let mut num: DWORD = 0;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let attrs_p = &attributes[0] as *const WORD;
+            let coords = make_coord(position);
+            wincon::WriteConsoleOutputAttribute(handle, attrs_p, length, coords, &mut num)
+        });
+        Ok(num)
+    }
+    fn write_characters(&mut self, string: &str, position: Vector2<u16>) -> WinResult<u32> {
+        let chars = utf16::encode(string);
+        let length = chars.len() as DWORD;
+        if length == 0 {
+            return Ok(0);
+        }
+
+        let mut num: DWORD = 0;
+        os_err!(unsafe {
+            let handle = self.output()?;
+            let chars_p = &chars[0] as *const WCHAR;
+            let coords = make_coord(position);
+            wincon::WriteConsoleOutputCharacterW(handle, chars_p, length, coords, &mut num)
+        });
+        Ok(num)
+    }
+    fn write_region(&mut self, rect: Rect, buffer: &CellBuffer) -> WinResult<()> {
+        let size = buffer.get_size();
+        if size.x == 0 || size.y == 0 {
+            return Ok(());
+        }
+        let rect = Rect::new(
+            rect.top,
+            rect.left,
+            rect.right.min(rect.left + size.x - 1),
+            rect.bottom.min(rect.top + size.y - 1),
+        );
+
+        let columns = rect.right - rect.left + 1;
+        for chunk in split_region(rect) {
+            let mut infos: Vec<CHAR_INFO> = Vec::new();
+            for y in chunk.top..=chunk.bottom {
+                let row = buffer.get_row(y - rect.top).unwrap_or(&[]);
+                for cell in &row[..usize::from(columns)] {
+                    let mut info: CHAR_INFO = unsafe { mem::zeroed() };
+                    let mut units = [0; 2];
+                    let unit = match cell.character.encode_utf16(&mut units) {
+                        &mut [unit] => unit,
+                        _ => REPLACEMENT_CHARACTER as WCHAR,
+                    };
+                    unsafe {
+                        *info.Char.UnicodeChar_mut() = unit;
+                    }
+                    info.Attributes = cell.get_attributes();
+                    infos.push(info);
+                }
+            }
+
+            let mut region = make_small_rect(chunk);
+            os_err!(unsafe {
+                let handle = self.output()?;
+                let size = make_coord(Vector2::new(columns, chunk.bottom - chunk.top + 1));
+                let infos_p = &infos[0] as *const CHAR_INFO;
+                let origin = COORD { X: 0, Y: 0 };
+                wincon::WriteConsoleOutputW(handle, infos_p, size, origin, &mut region)
+            });
+        }
+        Ok(())
+    }
+}
+
+// Title functions do not take a handle, and succeed with an empty title if there is no console.
+fn check_attached() -> WinResult<()> {
+    if !is_attached() {
+        throw_err!(InvalidHandleError);
+    }
+    Ok(())
+}
+fn get_cursor_info(handle: HANDLE) -> WinResult<CONSOLE_CURSOR_INFO> {
+    let mut info: CONSOLE_CURSOR_INFO = unsafe { mem::zeroed() };
+    os_err!(unsafe { wincon::GetConsoleCursorInfo(handle, &mut info) });
+    Ok(info)
+}
+fn get_handle_kind(handle: HANDLE) -> StreamKind {
+    if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+        return StreamKind::Unknown;
+    }
+
+    let mut mode: DWORD = 0;
+    if unsafe { consoleapi::GetConsoleMode(handle, &mut mode) } != 0 {
+        return StreamKind::Console;
+    }
+    match unsafe { fileapi::GetFileType(handle) } {
+        FILE_TYPE_DISK => StreamKind::File,
+        FILE_TYPE_PIPE if is_pty(handle) => StreamKind::Pty,
+        FILE_TYPE_PIPE => StreamKind::Pipe,
+        _ => StreamKind::Unknown,
+    }
+}
+fn get_mode(handle: HANDLE) -> WinResult<DWORD> {
+    let mut num: DWORD = 0;
+    os_err!(unsafe { consoleapi::GetConsoleMode(handle, &mut num) });
+    Ok(num)
+}
+fn get_screen_buffer_info_ex(handle: HANDLE) -> WinResult<CONSOLE_SCREEN_BUFFER_INFOEX> {
+    let mut csbi: CONSOLE_SCREEN_BUFFER_INFOEX = unsafe { mem::zeroed() };
+    os_err!(unsafe {
+        csbi.cbSize = mem::size_of::<CONSOLE_SCREEN_BUFFER_INFOEX>() as DWORD;
+        wincon::GetConsoleScreenBufferInfoEx(handle, &mut csbi)
+    });
+    Ok(csbi)
+}
+// Cygwin and MSYS ptys are named pipes such as `\msys-1888ae32e00d56aa-pty0-to-master`.
+fn is_pty(handle: HANDLE) -> bool {
+    let name_offset = mem::size_of::<DWORD>();
+    let mut buffer = vec![0 as DWORD; 1 + MAX_PATH / 2];
+    let size = (buffer.len() * mem::size_of::<DWORD>()) as DWORD;
+    let success = unsafe {
+        let buffer_p = buffer.as_mut_ptr() as *mut VOID;
+        winbase::GetFileInformationByHandleEx(handle, FileNameInfo, buffer_p, size)
+    };
+    if success == 0 {
+        return false;
+    }
+
+    let name = unsafe {
+        let info = &*(buffer.as_ptr() as *const FILE_NAME_INFO);
+        let length = (info.FileNameLength as usize / 2).min(MAX_PATH);
+        let name_p = (buffer.as_ptr() as *const u8).add(name_offset) as *const WCHAR;
+        String::from_utf16_lossy(slice::from_raw_parts(name_p, length))
+    };
+    (name.contains("msys-") || name.contains("cygwin-"))
+        && name.contains("-pty")
+        && (name.ends_with("-from-master") || name.ends_with("-to-master"))
+}
+fn make_coord(position: Vector2<u16>) -> COORD {
+    COORD {
+        X: position.x as i16,
+        Y: position.y as i16,
+    }
+}
+fn make_rect(rect: SMALL_RECT) -> Rect {
+    Rect::new(
+        rect.Top as u16,
+        rect.Left as u16,
+        rect.Right as u16,
+        rect.Bottom as u16,
+    )
+}
+fn make_small_rect(rect: Rect) -> SMALL_RECT {
+    SMALL_RECT {
+        Top: rect.top as i16,
+        Bottom: rect.bottom as i16,
+        Left: rect.left as i16,
+        Right: rect.right as i16,
+    }
+}
+fn make_wchar(chr: char, argument: &str) -> WinResult<WCHAR> {
+    let mut buffer = [0; 2];
+    if chr.encode_utf16(&mut buffer).len() != 1 {
+        throw_err!(ArgumentError::new(
+            argument,
+            "character must be in the Basic Multilingual Plane"
+        ));
+    }
+    Ok(buffer[0])
+}
+fn set_cursor_info(handle: HANDLE, value: CONSOLE_CURSOR_INFO) -> WinResult<()> {
+    os_err!(unsafe {
+        let value_p = &value as *const CONSOLE_CURSOR_INFO;
+        wincon::SetConsoleCursorInfo(handle, value_p)
+    });
+    Ok(())
+}
+fn set_mode(handle: HANDLE, value: DWORD) -> WinResult<()> {
+    os_err!(unsafe { consoleapi::SetConsoleMode(handle, value) });
+    Ok(())
+}
+fn split_region(rect: Rect) -> Vec<Rect> {
+    let columns = u32::from(rect.right - rect.left + 1);
+    let rows = (REGION_CHUNK_SIZE / columns).max(1);
+
+    let mut ret = Vec::new();
+    let mut top = u32::from(rect.top);
+    while top <= u32::from(rect.bottom) {
+        let bottom = (top + rows - 1).min(u32::from(rect.bottom));
+        ret.push(Rect::new(top as u16, rect.left, rect.right, bottom as u16));
+        top = bottom + 1;
+    }
+    ret
+}
@@ -0,0 +1,25 @@
+flags! {
+    /**
+     Text attribute flags which are not related to color.
+     The grid flags are only displayed if `OutputSettings::LVBGridWorldwide` is set.
+
+     # See
+     [Console Screen Buffers](https://docs.microsoft.com/en-us/windows/console/console-screen-buffers#character-attributes).
+     */
+    AttributeFlags<u16> {
+        /// Is the cell the leading byte of a double-byte character?
+        LeadingByte = 0x100,
+        /// Is the cell the trailing byte of a double-byte character?
+        TrailingByte = 0x200,
+        /// Should a horizontal line be drawn at the top of the cell?
+        GridHorizontal = 0x400,
+        /// Should a vertical line be drawn at the left of the cell?
+        GridLeftVertical = 0x800,
+        /// Should a vertical line be drawn at the right of the cell?
+        GridRightVertical = 0x1000,
+        /// Should the foreground and background colors be swapped?
+        ReverseVideo = 0x4000,
+        /// Should the cell be underlined?
+        Underscore = 0x8000,
+    }
+}
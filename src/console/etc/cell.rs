@@ -0,0 +1,97 @@
+use super::{AttributeFlags, ConsoleColor, TextAttributes};
+
+/// A character cell of a screen buffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cell {
+    /// The character displayed in the cell.
+    pub character: char,
+    /// The foreground color of the cell.
+    pub foreground: ConsoleColor,
+    /// The background color of the cell.
+    pub background: ConsoleColor,
+    /// Attribute flags which are not related to color.
+    pub flags: AttributeFlags,
+}
+
+impl Cell {
+    /**
+    Creates a new Cell with no attribute flags set.
+
+    # Arguments
+    * `character` - The character displayed in the cell.
+    * `foreground` - The foreground color of the cell.
+    * `background` - The background color of the cell.
+    */
+    pub fn new(character: char, foreground: ConsoleColor, background: ConsoleColor) -> Cell {
+        Cell {
+            character,
+            foreground,
+            background,
+            flags: AttributeFlags::default(),
+        }
+    }
+    /**
+    Creates a new Cell from a character and a set of text attributes.
+
+    # Arguments
+    * `character` - The character displayed in the cell.
+    * `attributes` - The text attributes of the cell, as used by the Windows API.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{Cell, ConsoleColor};
+    # fn main() {
+    let cell = Cell::from_attributes('a', 0x801C);
+    assert_eq!(cell.foreground, ConsoleColor::Red);
+    assert_eq!(cell.background, ConsoleColor::DarkBlue);
+    assert!(cell.flags.Underscore);
+    # }
+    ```
+    */
+    pub fn from_attributes(character: char, attributes: u16) -> Cell {
+        Cell::with_text_attributes(character, TextAttributes::from(attributes))
+    }
+    /**
+    Creates a new Cell from a character and a TextAttributes object.
+
+    # Arguments
+    * `character` - The character displayed in the cell.
+    * `attributes` - The attributes of the cell.
+    */
+    pub fn with_text_attributes(character: char, attributes: TextAttributes) -> Cell {
+        Cell {
+            character,
+            foreground: attributes.foreground,
+            background: attributes.background,
+            flags: attributes.flags,
+        }
+    }
+
+    /**
+    Returns the text attributes of the cell, as used by the Windows API.
+    */
+    pub fn get_attributes(&self) -> u16 {
+        self.get_text_attributes().into()
+    }
+    /**
+    Returns the attributes of the cell as a TextAttributes object.
+    */
+    pub fn get_text_attributes(&self) -> TextAttributes {
+        TextAttributes {
+            foreground: self.foreground,
+            background: self.background,
+            flags: self.flags,
+        }
+    }
+}
+
+impl Default for Cell {
+    /**
+    Returns a blank Cell with a gray foreground and black background.
+    */
+    fn default() -> Cell {
+        Cell::new(' ', ConsoleColor::Gray, ConsoleColor::Black)
+    }
+}
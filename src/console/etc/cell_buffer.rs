@@ -0,0 +1,334 @@
+use super::{Cell, ConsoleColor, Rect, Vector2};
+
+/**
+A rectangular grid of character cells.
+
+CellBuffers are used to read and write regions of the screen buffer at once
+(see `console::read_region` and `console::write_region`), and can be composed
+in memory beforehand. Methods which take a `Rect` clip it to the bounds of the buffer.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{Cell, CellBuffer, ConsoleColor, Rect};
+# fn main() {
+let mut buffer = CellBuffer::new(10, 3);
+let cell = Cell::new('#', ConsoleColor::Red, ConsoleColor::Black);
+buffer.fill(Rect::new(0, 0, 9, 0), cell);
+
+assert_eq!(buffer.get(9, 0), Some(cell));
+assert_eq!(buffer.get(0, 1), Some(Cell::default()));
+# }
+```
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellBuffer {
+    cells: Vec<Cell>,
+    size: Vector2<u16>,
+}
+
+impl CellBuffer {
+    /**
+    Creates a new CellBuffer filled with blank cells.
+
+    # Arguments
+    * `columns` - The amount of columns in the buffer.
+    * `rows` - The amount of rows in the buffer.
+    */
+    pub fn new(columns: u16, rows: u16) -> CellBuffer {
+        CellBuffer::filled(columns, rows, Cell::default())
+    }
+    /**
+    Creates a new CellBuffer filled with a cell.
+
+    # Arguments
+    * `columns` - The amount of columns in the buffer.
+    * `rows` - The amount of rows in the buffer.
+    * `cell` - The cell to fill the buffer with.
+    */
+    pub fn filled(columns: u16, rows: u16, cell: Cell) -> CellBuffer {
+        CellBuffer {
+            cells: vec![cell; usize::from(columns) * usize::from(rows)],
+            size: Vector2::new(columns, rows),
+        }
+    }
+
+    /**
+    Copies cells within a rectangle of another buffer to this buffer.
+    Cells which would be copied outside of this buffer are ignored.
+
+    # Arguments
+    * `source` - The buffer to copy cells from.
+    * `rect` - The rectangle of `source` to copy.
+    * `dest` - The position in this buffer to copy the top-left corner of `rect` to.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{Cell, CellBuffer, ConsoleColor, Rect, Vector2};
+    # fn main() {
+    let cell = Cell::new('x', ConsoleColor::Gray, ConsoleColor::Black);
+    let source = CellBuffer::filled(4, 4, cell);
+    let mut buffer = CellBuffer::new(5, 5);
+    buffer.blit(&source, Rect::new(0, 0, 3, 3), Vector2::new(3, 3));
+
+    assert_eq!(buffer.get(2, 2), Some(Cell::default()));
+    assert_eq!(buffer.get(4, 4), Some(cell));
+    # }
+    ```
+    */
+    pub fn blit(&mut self, source: &CellBuffer, rect: Rect, dest: Vector2<u16>) {
+        let rect = match source.clip(rect) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        for y in rect.top..=rect.bottom {
+            let row = u32::from(dest.y) + u32::from(y - rect.top);
+            if row >= u32::from(self.size.y) {
+                break;
+            }
+            for x in rect.left..=rect.right {
+                let column = u32::from(dest.x) + u32::from(x - rect.left);
+                if column >= u32::from(self.size.x) {
+                    break;
+                }
+                let index = self.index(column as u16, row as u16);
+                self.cells[index] = source.cells[source.index(x, y)];
+            }
+        }
+    }
+    /**
+    Compares this buffer with another buffer, returning the cells which differ as a list
+    of rectangles which are one row high. Cells which lie outside of `other` are considered
+    to differ.
+
+    # Arguments
+    * `other` - The buffer to compare with.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{Cell, CellBuffer, ConsoleColor, Rect};
+    # fn main() {
+    let old = CellBuffer::new(10, 3);
+    let mut new = old.clone();
+    let cell = Cell::new('!', ConsoleColor::Gray, ConsoleColor::Black);
+    new.fill(Rect::new(1, 2, 4, 1), cell);
+    new.fill(Rect::new(1, 7, 7, 1), cell);
+
+    assert_eq!(
+        new.compare(&old),
+        vec![Rect::new(1, 2, 4, 1), Rect::new(1, 7, 7, 1)]
+    );
+    # }
+    ```
+    */
+    pub fn compare(&self, other: &CellBuffer) -> Vec<Rect> {
+        let mut ret = Vec::new();
+        for y in 0..self.size.y {
+            let mut start = None;
+            for x in 0..self.size.x {
+                let changed = other.get(x, y) != self.get(x, y);
+                match (changed, start) {
+                    (true, None) => start = Some(x),
+                    (false, Some(left)) => {
+                        ret.push(Rect::new(y, left, x - 1, y));
+                        start = None;
+                    }
+                    _ => (),
+                }
+            }
+            if let Some(left) = start {
+                ret.push(Rect::new(y, left, self.size.x - 1, y));
+            }
+        }
+        ret
+    }
+    /**
+    Sets every cell within a rectangle to a cell.
+
+    # Arguments
+    * `rect` - The rectangle to fill.
+    * `cell` - The cell to fill the rectangle with.
+    */
+    pub fn fill(&mut self, rect: Rect, cell: Cell) {
+        let rect = match self.clip(rect) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        for y in rect.top..=rect.bottom {
+            let start = self.index(rect.left, y);
+            let end = self.index(rect.right, y);
+            for value in &mut self.cells[start..=end] {
+                *value = cell;
+            }
+        }
+    }
+    /**
+    Returns the cell at a position, or None if the position is outside of the buffer.
+
+    # Arguments
+    * `column` - The column of the cell.
+    * `row` - The row of the cell.
+    */
+    pub fn get(&self, column: u16, row: u16) -> Option<Cell> {
+        if column >= self.size.x || row >= self.size.y {
+            return None;
+        }
+        Some(self.cells[self.index(column, row)])
+    }
+    /**
+    Returns the cells of the buffer, in row-major order.
+    */
+    pub fn get_cells(&self) -> &[Cell] {
+        &self.cells
+    }
+    /**
+    Returns a mutable reference to the cell at a position, or None if the position is
+    outside of the buffer.
+
+    # Arguments
+    * `column` - The column of the cell.
+    * `row` - The row of the cell.
+    */
+    pub fn get_mut(&mut self, column: u16, row: u16) -> Option<&mut Cell> {
+        if column >= self.size.x || row >= self.size.y {
+            return None;
+        }
+        let index = self.index(column, row);
+        Some(&mut self.cells[index])
+    }
+    /**
+    Returns the cells of a row, or None if the row is outside of the buffer.
+
+    # Arguments
+    * `row` - The row to return.
+    */
+    pub fn get_row(&self, row: u16) -> Option<&[Cell]> {
+        if row >= self.size.y {
+            return None;
+        }
+        let start = self.index(0, row);
+        Some(&self.cells[start..start + usize::from(self.size.x)])
+    }
+    /**
+    Returns the size of the buffer, in character cells.
+    */
+    pub fn get_size(&self) -> Vector2<u16> {
+        self.size
+    }
+    /**
+    Returns the characters of each row of the buffer, with trailing whitespace removed.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{Cell, CellBuffer, ConsoleColor, Rect};
+    # fn main() {
+    let mut buffer = CellBuffer::new(5, 2);
+    let cell = Cell::new('=', ConsoleColor::Gray, ConsoleColor::Black);
+    buffer.fill(Rect::new(1, 1, 2, 1), cell);
+    assert_eq!(buffer.lines(), vec!["", " =="]);
+    # }
+    ```
+    */
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.size.y)
+            .map(|row| {
+                let line: String = self
+                    .get_row(row)
+                    .unwrap_or(&[])
+                    .iter()
+                    .map(|cell| cell.character)
+                    .collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+    /**
+    Returns a new buffer containing the cells within a rectangle of this buffer.
+    If the rectangle lies outside of this buffer, an empty buffer is returned.
+
+    # Arguments
+    * `rect` - The rectangle to copy.
+    */
+    pub fn slice(&self, rect: Rect) -> CellBuffer {
+        let rect = match self.clip(rect) {
+            Some(rect) => rect,
+            None => return CellBuffer::new(0, 0),
+        };
+
+        let mut cells = Vec::new();
+        for y in rect.top..=rect.bottom {
+            let start = self.index(rect.left, y);
+            let end = self.index(rect.right, y);
+            cells.extend_from_slice(&self.cells[start..=end]);
+        }
+        CellBuffer {
+            cells,
+            size: Vector2::new(rect.right - rect.left + 1, rect.bottom - rect.top + 1),
+        }
+    }
+    /**
+    Writes a string to a row of the buffer, and returns the number of cells written to.
+    Characters which do not fit within the row are discarded.
+
+    # Arguments
+    * `string` - The string to write.
+    * `column` - The column at which writing should begin.
+    * `row` - The row to write to.
+    * `foreground` - The foreground color of the written cells.
+    * `background` - The background color of the written cells.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{CellBuffer, ConsoleColor};
+    # fn main() {
+    let mut buffer = CellBuffer::new(8, 1);
+    let written = buffer.write_str("Hello, world!", 2, 0, ConsoleColor::Blue, ConsoleColor::Black);
+    assert_eq!(written, 6);
+    assert_eq!(buffer.lines(), vec!["  Hello,"]);
+    # }
+    ```
+    */
+    pub fn write_str(
+        &mut self,
+        string: &str,
+        column: u16,
+        row: u16,
+        foreground: ConsoleColor,
+        background: ConsoleColor,
+    ) -> u16 {
+        if row >= self.size.y {
+            return 0;
+        }
+
+        let mut written = 0;
+        for (x, chr) in (column..self.size.x).zip(string.chars()) {
+            let index = self.index(x, row);
+            self.cells[index] = Cell::new(chr, foreground, background);
+            written += 1;
+        }
+        written
+    }
+
+    fn clip(&self, rect: Rect) -> Option<Rect> {
+        if self.size.x == 0 || self.size.y == 0 {
+            return None;
+        }
+
+        let right = rect.right.min(self.size.x - 1);
+        let bottom = rect.bottom.min(self.size.y - 1);
+        if rect.left > right || rect.top > bottom {
+            return None;
+        }
+        Some(Rect::new(rect.top, rect.left, right, bottom))
+    }
+    fn index(&self, column: u16, row: u16) -> usize {
+        usize::from(row) * usize::from(self.size.x) + usize::from(column)
+    }
+}
@@ -1,5 +1,8 @@
+#[cfg(windows)]
 use super::CodePageInfo;
+#[cfg(windows)]
 use console;
+#[cfg(windows)]
 use errors::WinResult;
 
 enumeration! {
@@ -339,6 +342,7 @@ impl CodePage {
      [`FromUtf16Error`]: ../errors/enum.WinError.html#FromUtf16.v
      [`IoError`]: ../errors/enum.WinError.html#Io.v
     */
+    #[cfg(windows)]
     pub fn get_info(self) -> WinResult<CodePageInfo> {
         console::get_code_page_info(self)
     }
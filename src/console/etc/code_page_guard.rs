@@ -0,0 +1,99 @@
+use super::CodePage;
+use console;
+use errors::WinResult;
+
+/**
+Changes the input or output code page of the console, and restores the previous code page
+when it is dropped, including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{CodePage, CodePageGuard};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let _guard = CodePageGuard::output(CodePage::utf_8).unwrap();
+println!("¡Hola!");
+# }
+```
+*/
+#[derive(Debug)]
+pub struct CodePageGuard {
+    input: bool,
+    previous: Option<CodePage>,
+}
+
+impl CodePageGuard {
+    /**
+    Changes the input code page, and returns a guard which restores the previous code page.
+
+    # Arguments
+    * `page` - The code page to apply.
+
+    # Errors
+    * [`ArgumentError`]: Returned if `page` is `CodePage::None` or `CodePage::Invalid`.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn input(page: CodePage) -> WinResult<CodePageGuard> {
+        let previous = console::get_input_code_page();
+        console::set_input_code_page(page)?;
+        Ok(CodePageGuard {
+            input: true,
+            previous: Some(previous),
+        })
+    }
+    /**
+    Changes the output code page, and returns a guard which restores the previous code page.
+
+    # Arguments
+    * `page` - The code page to apply.
+
+    # Errors
+    * [`ArgumentError`]: Returned if `page` is `CodePage::None` or `CodePage::Invalid`.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn output(page: CodePage) -> WinResult<CodePageGuard> {
+        let previous = console::get_output_code_page();
+        console::set_output_code_page(page)?;
+        Ok(CodePageGuard {
+            input: false,
+            previous: Some(previous),
+        })
+    }
+
+    /**
+    Restores the previous code page, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`ArgumentError`]: Returned if the previous code page is no longer valid.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        self.restore_previous()
+    }
+
+    fn restore_previous(&mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) if self.input => console::set_input_code_page(previous),
+            Some(previous) => console::set_output_code_page(previous),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for CodePageGuard {
+    fn drop(&mut self) {
+        self.restore_previous().unwrap_or(())
+    }
+}
@@ -0,0 +1,167 @@
+use super::{ColorMetric, ConsoleColor};
+use rgb::RGB8;
+
+// Maps the low three bits of a console color to an ANSI color index, and vice versa.
+const ANSI_COLORS: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/**
+A color which may be displayed by the console: one of the 16 palette colors,
+an index into the xterm 256-color palette, or a 24-bit RGB color.
+
+Colors other than palette colors are only displayed exactly when virtual terminal
+sequences are processed; otherwise, the nearest palette color is used instead.
+
+# Examples
+```
+# extern crate rgb;
+# extern crate winconsole;
+# use rgb::RGB8;
+# use winconsole::console::{Color, ColorMetric, ConsoleColor};
+# fn main() {
+let mapping = [
+    RGB8 { r: 0, g: 0, b: 0 }, RGB8 { r: 0, g: 0, b: 128 },
+    RGB8 { r: 0, g: 128, b: 0 }, RGB8 { r: 0, g: 128, b: 128 },
+    RGB8 { r: 128, g: 0, b: 0 }, RGB8 { r: 128, g: 0, b: 128 },
+    RGB8 { r: 128, g: 128, b: 0 }, RGB8 { r: 192, g: 192, b: 192 },
+    RGB8 { r: 128, g: 128, b: 128 }, RGB8 { r: 0, g: 0, b: 255 },
+    RGB8 { r: 0, g: 255, b: 0 }, RGB8 { r: 0, g: 255, b: 255 },
+    RGB8 { r: 255, g: 0, b: 0 }, RGB8 { r: 255, g: 0, b: 255 },
+    RGB8 { r: 255, g: 255, b: 0 }, RGB8 { r: 255, g: 255, b: 255 },
+];
+let orange = Color::from(RGB8 { r: 250, g: 40, b: 20 });
+assert_eq!(orange.to_console_color(&mapping, ColorMetric::Cie76), ConsoleColor::Red);
+assert_eq!(Color::Indexed(196).to_rgb(&mapping), RGB8 { r: 255, g: 0, b: 0 });
+# }
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    /// One of the 16 console palette colors.
+    Named(ConsoleColor),
+    /// An index into the xterm 256-color palette. Indices below 16 refer to palette colors,
+    /// in ANSI order.
+    Indexed(u8),
+    /// A 24-bit RGB color.
+    Rgb(RGB8),
+}
+
+impl Color {
+    /**
+    Returns the virtual terminal sequence which sets the foreground or background color
+    of subsequently written text to this color.
+
+    # Arguments
+    * `background` - Should the sequence set the background color? Otherwise, it sets
+      the foreground color.
+
+    # Examples
+    ```
+    # extern crate rgb;
+    # extern crate winconsole;
+    # use rgb::RGB8;
+    # use winconsole::console::{Color, ConsoleColor};
+    # fn main() {
+    assert_eq!(Color::Named(ConsoleColor::Red).get_vt_sequence(false), "\x1b[91m");
+    assert_eq!(Color::Indexed(208).get_vt_sequence(true), "\x1b[48;5;208m");
+    let rgb = Color::Rgb(RGB8 { r: 1, g: 2, b: 3 });
+    assert_eq!(rgb.get_vt_sequence(false), "\x1b[38;2;1;2;3m");
+    # }
+    ```
+    */
+    pub fn get_vt_sequence(&self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match *self {
+            Color::Named(color) => {
+                let index = ansi_color(color.into());
+                if index < 8 {
+                    format!("\x1b[{}m", base + index)
+                } else {
+                    format!("\x1b[{}m", base + 52 + index)
+                }
+            }
+            Color::Indexed(index) => format!("\x1b[{};5;{}m", base + 8, index),
+            Color::Rgb(rgb) => format!("\x1b[{};2;{};{};{}m", base + 8, rgb.r, rgb.g, rgb.b),
+        }
+    }
+    /**
+    Returns the palette color which is nearest to this color.
+    Palette colors, including xterm indices below 16, are returned unchanged.
+
+    # Arguments
+    * `mapping` - The RGB values of the palette colors, such as those returned by
+      [`get_color_mapping`](fn.get_color_mapping.html).
+    * `metric` - The method used to measure the difference between colors.
+    */
+    pub fn to_console_color(&self, mapping: &[RGB8; 16], metric: ColorMetric) -> ConsoleColor {
+        match *self {
+            Color::Named(color) => color,
+            Color::Indexed(index) if index < 16 => ConsoleColor::from(ansi_color(u16::from(index))),
+            _ => {
+                let rgb = self.to_rgb(mapping);
+                let mut nearest = 0;
+                let mut nearest_distance = metric.distance(rgb, mapping[0]);
+                for (i, color) in mapping.iter().enumerate().skip(1) {
+                    let distance = metric.distance(rgb, *color);
+                    if distance < nearest_distance {
+                        nearest = i;
+                        nearest_distance = distance;
+                    }
+                }
+                ConsoleColor::from(nearest as u16)
+            }
+        }
+    }
+    /**
+    Returns the RGB value of this color.
+
+    # Arguments
+    * `mapping` - The RGB values of the palette colors, used for palette colors and xterm
+      indices below 16.
+    */
+    pub fn to_rgb(&self, mapping: &[RGB8; 16]) -> RGB8 {
+        match *self {
+            Color::Named(color) => mapping[color.get_value() as usize],
+            Color::Indexed(index) if index < 16 => mapping[ansi_color(u16::from(index)) as usize],
+            Color::Indexed(index) if index < 232 => {
+                let index = index - 16;
+                RGB8 {
+                    r: CUBE_LEVELS[usize::from(index / 36)],
+                    g: CUBE_LEVELS[usize::from(index / 6 % 6)],
+                    b: CUBE_LEVELS[usize::from(index % 6)],
+                }
+            }
+            Color::Indexed(index) => {
+                let level = 8 + (index - 232) * 10;
+                RGB8 {
+                    r: level,
+                    g: level,
+                    b: level,
+                }
+            }
+            Color::Rgb(rgb) => rgb,
+        }
+    }
+}
+
+impl From<ConsoleColor> for Color {
+    fn from(color: ConsoleColor) -> Color {
+        Color::Named(color)
+    }
+}
+
+impl From<RGB8> for Color {
+    fn from(rgb: RGB8) -> Color {
+        Color::Rgb(rgb)
+    }
+}
+
+/// Converts between a console color value and an ANSI color index.
+pub(crate) fn ansi_color(color: u16) -> u16 {
+    let index = u16::from(ANSI_COLORS[(color & 0x7) as usize]);
+    if color & 0x8 != 0 {
+        index + 8
+    } else {
+        index
+    }
+}
@@ -0,0 +1,138 @@
+use super::{ConsoleColor, TextAttributes};
+use console;
+use errors::WinResult;
+
+/**
+Changes the colors of text subsequently written to the console, and restores the previous
+text attributes when it is dropped, including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ColorGuard, ConsoleColor};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+{
+    let _guard = ColorGuard::foreground(ConsoleColor::Red).unwrap();
+    println!("This is red.");
+}
+println!("This is not.");
+# }
+```
+*/
+#[derive(Debug)]
+pub struct ColorGuard {
+    previous: Option<TextAttributes>,
+}
+
+impl ColorGuard {
+    /**
+    Changes the foreground and background colors, and returns a guard which restores
+    the previous text attributes.
+
+    # Arguments
+    * `foreground` - The foreground color to apply.
+    * `background` - The background color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(foreground: ConsoleColor, background: ConsoleColor) -> WinResult<ColorGuard> {
+        ColorGuard::apply(|attributes| {
+            attributes.foreground = foreground;
+            attributes.background = background;
+        })
+    }
+    /**
+    Changes the background color, and returns a guard which restores the previous
+    text attributes.
+
+    # Arguments
+    * `color` - The background color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn background(color: ConsoleColor) -> WinResult<ColorGuard> {
+        ColorGuard::apply(|attributes| attributes.background = color)
+    }
+    /**
+    Changes the foreground color, and returns a guard which restores the previous
+    text attributes.
+
+    # Arguments
+    * `color` - The foreground color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn foreground(color: ConsoleColor) -> WinResult<ColorGuard> {
+        ColorGuard::apply(|attributes| attributes.foreground = color)
+    }
+    /**
+    Changes the text attributes, including attribute flags, and returns a guard which
+    restores the previous text attributes.
+
+    # Arguments
+    * `attributes` - The text attributes to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn with_attributes(attributes: TextAttributes) -> WinResult<ColorGuard> {
+        ColorGuard::apply(|current| *current = attributes)
+    }
+
+    /**
+    Restores the previous text attributes, returning any error which occurs.
+    Dropping the guard also restores them, but ignores errors.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) => console::set_text_attributes(previous),
+            None => Ok(()),
+        }
+    }
+
+    fn apply(change: impl FnOnce(&mut TextAttributes)) -> WinResult<ColorGuard> {
+        let previous = console::get_text_attributes()?;
+        let mut attributes = previous;
+        change(&mut attributes);
+        console::set_text_attributes(attributes)?;
+        Ok(ColorGuard {
+            previous: Some(previous),
+        })
+    }
+}
+
+impl Drop for ColorGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            console::set_text_attributes(previous).unwrap_or(())
+        }
+    }
+}
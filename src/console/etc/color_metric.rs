@@ -0,0 +1,104 @@
+use rgb::RGB8;
+
+/// Methods of measuring the difference between two colors, used to find the nearest
+/// palette color to an arbitrary color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ColorMetric {
+    /// The straight-line distance between colors in RGB space. This is the cheapest metric,
+    /// but matches human perception poorly.
+    Euclidean,
+    /// The "redmean" approximation, which weights the RGB components by how sensitive the eye
+    /// is to them.
+    Redmean,
+    /// The CIE76 color difference, which is the straight-line distance between colors in the
+    /// CIELAB color space.
+    Cie76,
+}
+
+impl ColorMetric {
+    /**
+    Returns the distance between two colors under this metric.
+    Only the ordering of distances is meaningful; their scale differs between metrics.
+
+    # Arguments
+    * `a` - The first color.
+    * `b` - The second color.
+
+    # Examples
+    ```
+    # extern crate rgb;
+    # extern crate winconsole;
+    # use rgb::RGB8;
+    # use winconsole::console::ColorMetric;
+    # fn main() {
+    let red = RGB8 { r: 255, g: 0, b: 0 };
+    let orange = RGB8 { r: 255, g: 128, b: 0 };
+    let black = RGB8 { r: 0, g: 0, b: 0 };
+    let metric = ColorMetric::Cie76;
+    assert!(metric.distance(red, orange) < metric.distance(red, black));
+    # }
+    ```
+    */
+    pub fn distance(self, a: RGB8, b: RGB8) -> f64 {
+        match self {
+            ColorMetric::Euclidean => {
+                let dr = f64::from(a.r) - f64::from(b.r);
+                let dg = f64::from(a.g) - f64::from(b.g);
+                let db = f64::from(a.b) - f64::from(b.b);
+                (dr * dr + dg * dg + db * db).sqrt()
+            }
+            ColorMetric::Redmean => {
+                let mean = (f64::from(a.r) + f64::from(b.r)) / 2.0;
+                let dr = f64::from(a.r) - f64::from(b.r);
+                let dg = f64::from(a.g) - f64::from(b.g);
+                let db = f64::from(a.b) - f64::from(b.b);
+                ((2.0 + mean / 256.0) * dr * dr
+                    + 4.0 * dg * dg
+                    + (2.0 + (255.0 - mean) / 256.0) * db * db)
+                    .sqrt()
+            }
+            ColorMetric::Cie76 => {
+                let (l1, a1, b1) = to_lab(a);
+                let (l2, a2, b2) = to_lab(b);
+                ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+            }
+        }
+    }
+}
+
+impl Default for ColorMetric {
+    /**
+    Returns `ColorMetric::Cie76`.
+    */
+    fn default() -> ColorMetric {
+        ColorMetric::Cie76
+    }
+}
+
+fn to_lab(color: RGB8) -> (f64, f64, f64) {
+    fn linear(value: u8) -> f64 {
+        let value = f64::from(value) / 255.0;
+        if value <= 0.040_45 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    fn f(t: f64) -> f64 {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    }
+
+    let (r, g, b) = (linear(color.r), linear(color.g), linear(color.b));
+    // sRGB to XYZ, relative to the D65 white point.
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
@@ -0,0 +1,13 @@
+/// Methods by which a [`ColorWriter`](struct.ColorWriter.html) applies colors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ColorMode {
+    /// Colors are applied by changing the text attributes of the console output.
+    /// This should only be used when the writer writes to the console.
+    Console,
+    /// Colors are applied by writing ANSI escape sequences, for terminals and files which are
+    /// viewed by programs that interpret them.
+    Ansi,
+    /// Colors are not applied, and only text is written.
+    Plain,
+}
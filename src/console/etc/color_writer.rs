@@ -0,0 +1,261 @@
+use super::{Color, ColorMode, ConsoleColor, StandardStream, StreamKind, TextAttributes};
+use console;
+use errors::WinResult;
+use std::io::{self, Write};
+
+/**
+A writer which keeps track of the current foreground and background colors, and applies
+them to any `io::Write` according to a [`ColorMode`](enum.ColorMode.html).
+
+This allows the same code to produce colored output in the console, and clean or
+ANSI-colored output when writing to a file, a pipe or a buffer.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ColorMode, ColorWriter, ConsoleColor};
+# use std::io::Write;
+# fn main() {
+let mut writer = ColorWriter::new(Vec::new(), ColorMode::Ansi).unwrap();
+writer.set_foreground(ConsoleColor::Red).unwrap();
+write!(writer, "error").unwrap();
+writer.reset().unwrap();
+assert_eq!(writer.into_inner(), b"\x1b[91merror\x1b[0m");
+
+let mut writer = ColorWriter::new(Vec::new(), ColorMode::Plain).unwrap();
+writer.set_foreground(ConsoleColor::Red).unwrap();
+write!(writer, "error").unwrap();
+assert_eq!(writer.into_inner(), b"error");
+# }
+```
+*/
+#[derive(Debug)]
+pub struct ColorWriter<W: Write> {
+    colors: (ConsoleColor, ConsoleColor),
+    initial: TextAttributes,
+    inner: W,
+    mode: ColorMode,
+}
+
+impl ColorWriter<io::Stderr> {
+    /**
+    Returns a ColorWriter which writes to the standard error. Colors are applied through the
+    console if the standard error is a console, as ANSI escape sequences if it is a pty,
+    and are not applied otherwise.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn stderr() -> WinResult<ColorWriter<io::Stderr>> {
+        let mode = detect_mode(StandardStream::Error);
+        ColorWriter::new(io::stderr(), mode)
+    }
+}
+
+impl ColorWriter<io::Stdout> {
+    /**
+    Returns a ColorWriter which writes to the standard output. Colors are applied through the
+    console if the standard output is a console, as ANSI escape sequences if it is a pty,
+    and are not applied otherwise.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn stdout() -> WinResult<ColorWriter<io::Stdout>> {
+        let mode = detect_mode(StandardStream::Output);
+        ColorWriter::new(io::stdout(), mode)
+    }
+}
+
+impl<W: Write> ColorWriter<W> {
+    /**
+    Creates a new ColorWriter. In [`ColorMode::Console`], the initial colors are those of the
+    console output; otherwise, they are gray on black.
+
+    # Arguments
+    * `inner` - The writer to write to.
+    * `mode` - The method by which colors are applied.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if `mode` is `ColorMode::Console` and an invalid handle
+      to the console output is retrieved or used.
+    * [`IoError`]: Returned if `mode` is `ColorMode::Console` and an OS error occurs.
+
+    [`ColorMode::Console`]: enum.ColorMode.html#variant.Console
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(inner: W, mode: ColorMode) -> WinResult<ColorWriter<W>> {
+        let initial = match mode {
+            ColorMode::Console => console::get_text_attributes()?,
+            _ => TextAttributes::default(),
+        };
+        Ok(ColorWriter {
+            colors: initial.get_colors(),
+            initial,
+            inner,
+            mode,
+        })
+    }
+
+    /**
+    Returns the current foreground and background colors as a tuple.
+    */
+    pub fn get_colors(&self) -> (ConsoleColor, ConsoleColor) {
+        self.colors
+    }
+    /**
+    Returns the method by which colors are applied.
+    */
+    pub fn get_mode(&self) -> ColorMode {
+        self.mode
+    }
+    /**
+    Returns a mutable reference to the underlying writer.
+    Text written directly to it is not guaranteed to have the current colors.
+    */
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+    /**
+    Returns a reference to the underlying writer.
+    */
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /**
+    Consumes the ColorWriter, returning the underlying writer.
+    The colors are not reset; see [`reset`](#method.reset).
+    */
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+    /**
+    Restores the initial colors. In [`ColorMode::Ansi`], this writes a sequence which resets
+    all attributes to the terminal's defaults.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing fails.
+
+    [`ColorMode::Ansi`]: enum.ColorMode.html#variant.Ansi
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn reset(&mut self) -> WinResult<()> {
+        match self.mode {
+            ColorMode::Console => {
+                self.inner.flush()?;
+                console::set_text_attributes(self.initial)?;
+            }
+            ColorMode::Ansi => self.inner.write_all(b"\x1b[0m")?,
+            ColorMode::Plain => {}
+        }
+        self.colors = self.initial.get_colors();
+        Ok(())
+    }
+    /**
+    Sets the background color of text subsequently written.
+
+    # Arguments
+    * `color` - The background color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn set_background(&mut self, color: ConsoleColor) -> WinResult<()> {
+        let foreground = self.colors.0;
+        self.set_colors(foreground, color)
+    }
+    /**
+    Sets the foreground and background colors of text subsequently written.
+    Nothing is applied if the colors are unchanged.
+
+    # Arguments
+    * `foreground` - The foreground color to apply.
+    * `background` - The background color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn set_colors(
+        &mut self,
+        foreground: ConsoleColor,
+        background: ConsoleColor,
+    ) -> WinResult<()> {
+        let (current_fg, current_bg) = self.colors;
+        match self.mode {
+            ColorMode::Console if (foreground, background) != self.colors => {
+                // Text which is still buffered must be written with the previous colors.
+                self.inner.flush()?;
+                let mut attributes = self.initial;
+                attributes.foreground = foreground;
+                attributes.background = background;
+                console::set_text_attributes(attributes)?;
+            }
+            ColorMode::Ansi => {
+                if foreground != current_fg {
+                    let sequence = Color::Named(foreground).get_vt_sequence(false);
+                    self.inner.write_all(sequence.as_bytes())?;
+                }
+                if background != current_bg {
+                    let sequence = Color::Named(background).get_vt_sequence(true);
+                    self.inner.write_all(sequence.as_bytes())?;
+                }
+            }
+            _ => {}
+        }
+        self.colors = (foreground, background);
+        Ok(())
+    }
+    /**
+    Sets the foreground color of text subsequently written.
+
+    # Arguments
+    * `color` - The foreground color to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn set_foreground(&mut self, color: ConsoleColor) -> WinResult<()> {
+        let background = self.colors.1;
+        self.set_colors(color, background)
+    }
+}
+
+impl<W: Write> Write for ColorWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn detect_mode(stream: StandardStream) -> ColorMode {
+    match console::get_stream_kind(stream) {
+        StreamKind::Console => ColorMode::Console,
+        StreamKind::Pty => ColorMode::Ansi,
+        _ => ColorMode::Plain,
+    }
+}
@@ -0,0 +1,196 @@
+use super::{
+    Cell, CellBuffer, CodePage, Color, ColorMetric, ConsoleFont, HistoryInfo, InputSettings,
+    OutputSettings, Rect, ScreenBufferInfo, SelectionInfo, StandardStream, StreamKind,
+    TextAttributes, Vector2,
+};
+use errors::WinResult;
+use rgb::RGB8;
+
+/**
+The primitive operations which the functions in the `console` module are built upon.
+
+By default, console functions operate on the console attached to the process. Another
+implementation can be installed for the current thread using [`set_backend`], which allows
+the `console` API to run against something other than a real console, such as a
+[`MemoryConsole`].
+
+Implementations should not call functions in the `console` module, as the backend is
+borrowed for the duration of each call.
+
+[`set_backend`]: fn.set_backend.html
+[`MemoryConsole`]: struct.MemoryConsole.html
+*/
+pub trait ConsoleBackend {
+    /// Fills cells with a set of text attributes, and returns the number of cells which were filled.
+    fn fill_attributes(
+        &mut self,
+        attributes: u16,
+        length: u32,
+        position: Vector2<u16>,
+    ) -> WinResult<u32>;
+    /// Fills cells with a character, and returns the number of cells which were filled.
+    fn fill_character(&mut self, chr: char, length: u32, position: Vector2<u16>) -> WinResult<u32>;
+    /// Discards all events in the input buffer.
+    fn flush_input(&mut self) -> WinResult<()>;
+    /// Returns the color mapping of the screen buffer.
+    fn get_color_mapping(&mut self) -> WinResult<[RGB8; 16]>;
+    /// Returns the size of the cursor, from 0 to 100 (inclusive).
+    fn get_cursor_size(&mut self) -> WinResult<u8>;
+    /// Returns information about the current font.
+    fn get_font(&mut self) -> WinResult<ConsoleFont>;
+    /// Returns information about input history settings.
+    fn get_history_info(&mut self) -> WinResult<HistoryInfo>;
+    /// Returns the input code page.
+    fn get_input_code_page(&mut self) -> WinResult<CodePage>;
+    /// Returns settings related to input.
+    fn get_input_mode(&mut self) -> WinResult<InputSettings>;
+    /// Returns the size of the largest possible window.
+    fn get_largest_window_size(&mut self) -> WinResult<Vector2<u16>>;
+    /**
+    Returns the number of buttons on the mouse used by the console.
+
+    The default implementation returns 0.
+    */
+    fn get_num_mouse_buttons(&mut self) -> WinResult<u32> {
+        Ok(0)
+    }
+    /// Returns the original title of the window.
+    fn get_original_title(&mut self) -> WinResult<String>;
+    /// Returns the output code page.
+    fn get_output_code_page(&mut self) -> WinResult<CodePage>;
+    /// Returns settings related to output.
+    fn get_output_mode(&mut self) -> WinResult<OutputSettings>;
+    /// Returns information about the screen buffer.
+    fn get_screen_buffer_info(&mut self) -> WinResult<ScreenBufferInfo>;
+    /// Returns information about the current selection.
+    fn get_selection_info(&mut self) -> WinResult<SelectionInfo>;
+    /**
+    Returns what a standard stream is attached to.
+
+    The default implementation returns `StreamKind::Unknown`.
+    */
+    fn get_stream_kind(&mut self, _stream: StandardStream) -> StreamKind {
+        StreamKind::Unknown
+    }
+    /// Returns the title of the window.
+    fn get_title(&mut self) -> WinResult<String>;
+    /// Returns whether or not the cursor is visible.
+    fn is_cursor_visible(&mut self) -> WinResult<bool>;
+    /// Reads up to `length` text attributes, starting at a position.
+    fn read_attributes(&mut self, position: Vector2<u16>, length: u32) -> WinResult<Vec<u16>>;
+    /// Reads up to `length` characters, starting at a position.
+    fn read_characters(&mut self, position: Vector2<u16>, length: u32) -> WinResult<String>;
+    /**
+    Reads the cells within a rectangle of the screen buffer.
+
+    The default implementation reads each row using `read_characters` and `read_attributes`.
+    */
+    fn read_region(&mut self, rect: Rect) -> WinResult<CellBuffer> {
+        let columns = rect.right - rect.left + 1;
+        let mut buffer = CellBuffer::new(columns, rect.bottom - rect.top + 1);
+        for y in rect.top..=rect.bottom {
+            let position = Vector2::new(rect.left, y);
+            let chars = self.read_characters(position, u32::from(columns))?;
+            let attrs = self.read_attributes(position, u32::from(columns))?;
+            for (x, (chr, attrs)) in chars.chars().zip(attrs).enumerate() {
+                if let Some(cell) = buffer.get_mut(x as u16, y - rect.top) {
+                    *cell = Cell::from_attributes(chr, attrs);
+                }
+            }
+        }
+        Ok(buffer)
+    }
+    /**
+    Moves the contents of the `scroll` rectangle so that its top-left corner is at `dest`.
+    Cells outside of `clip` are left unchanged, and cells which are left empty by the move
+    are filled with `fill_char` and `fill_attributes`.
+    */
+    fn scroll_contents(
+        &mut self,
+        scroll: Rect,
+        clip: Option<Rect>,
+        dest: Vector2<i16>,
+        fill_char: char,
+        fill_attributes: u16,
+    ) -> WinResult<()>;
+    /// Sets the size of the screen buffer.
+    fn set_buffer_size(&mut self, size: Vector2<u16>) -> WinResult<()>;
+    /**
+    Sets the foreground or background color used for new output to a color which may not be
+    one of the palette colors.
+
+    The default implementation sets the palette color which is nearest to `color` under the
+    color mapping, as measured by `metric`.
+    */
+    fn set_color(&mut self, color: Color, background: bool, metric: ColorMetric) -> WinResult<()> {
+        set_nearest_color(self, color, background, metric)
+    }
+    /// Sets the color mapping of the screen buffer.
+    fn set_color_mapping(&mut self, mapping: &[RGB8; 16]) -> WinResult<()>;
+    /// Sets the position of the cursor.
+    fn set_cursor_position(&mut self, position: Vector2<u16>) -> WinResult<()>;
+    /// Sets the size of the cursor.
+    fn set_cursor_size(&mut self, size: u8) -> WinResult<()>;
+    /// Sets the visibility of the cursor.
+    fn set_cursor_visible(&mut self, visible: bool) -> WinResult<()>;
+    /// Sets information about the current font.
+    fn set_font(&mut self, font: &ConsoleFont) -> WinResult<()>;
+    /// Sets information about input history settings.
+    fn set_history_info(&mut self, history: HistoryInfo) -> WinResult<()>;
+    /// Sets the input code page.
+    fn set_input_code_page(&mut self, page: CodePage) -> WinResult<()>;
+    /// Sets settings related to input.
+    fn set_input_mode(&mut self, settings: InputSettings) -> WinResult<()>;
+    /// Sets the output code page.
+    fn set_output_code_page(&mut self, page: CodePage) -> WinResult<()>;
+    /// Sets settings related to output.
+    fn set_output_mode(&mut self, settings: OutputSettings) -> WinResult<()>;
+    /// Sets the text attributes used for new output.
+    fn set_text_attributes(&mut self, attributes: u16) -> WinResult<()>;
+    /// Sets the title of the window.
+    fn set_title(&mut self, title: &str) -> WinResult<()>;
+    /// Sets the area of the buffer which is displayed in the window.
+    fn set_window_rect(&mut self, rect: Rect) -> WinResult<()>;
+    /// Writes text attributes starting at a position, and returns the number of cells written to.
+    fn write_attributes(&mut self, attributes: &[u16], position: Vector2<u16>) -> WinResult<u32>;
+    /// Writes characters starting at a position, and returns the number of cells written to.
+    fn write_characters(&mut self, string: &str, position: Vector2<u16>) -> WinResult<u32>;
+    /**
+    Writes cells to a rectangle of the screen buffer, starting with the top-left cell of
+    `buffer`. Cells which lie outside of either `rect` or `buffer` are left unchanged.
+
+    The default implementation writes each row using `write_characters` and
+    `write_attributes`.
+    */
+    fn write_region(&mut self, rect: Rect, buffer: &CellBuffer) -> WinResult<()> {
+        let size = buffer.get_size();
+        let columns = usize::from((rect.right - rect.left + 1).min(size.x));
+        let rows = (rect.bottom - rect.top + 1).min(size.y);
+        for y in 0..rows {
+            let cells = &buffer.get_row(y).unwrap_or(&[])[..columns];
+            let chars: String = cells.iter().map(|cell| cell.character).collect();
+            let attrs: Vec<u16> = cells.iter().map(Cell::get_attributes).collect();
+            let position = Vector2::new(rect.left, rect.top + y);
+            self.write_characters(&chars, position)?;
+            self.write_attributes(&attrs, position)?;
+        }
+        Ok(())
+    }
+}
+
+// Sets the foreground or background of the text attributes to the nearest palette color.
+pub(crate) fn set_nearest_color<B: ConsoleBackend + ?Sized>(
+    backend: &mut B,
+    color: Color,
+    background: bool,
+    metric: ColorMetric,
+) -> WinResult<()> {
+    let color = color.to_console_color(&backend.get_color_mapping()?, metric);
+    let mut attributes = TextAttributes::from(backend.get_screen_buffer_info()?.attributes);
+    if background {
+        attributes.background = color;
+    } else {
+        attributes.foreground = color;
+    }
+    backend.set_text_attributes(attributes.into())
+}
@@ -0,0 +1,42 @@
+use super::StreamKind;
+
+/**
+A report of what the standard streams are attached to, and which features are available.
+Features which cannot be determined are reported as unavailable.
+
+# Examples
+Picks a rendering mode based on the capabilities of the console.
+
+```
+# extern crate winconsole;
+# use winconsole::console;
+# fn main() {
+let capabilities = console::get_capabilities();
+if !capabilities.output.is_terminal() {
+    println!("plain output");
+} else if capabilities.virtual_terminal {
+    println!("\x1b[1mvirtual terminal output\x1b[0m");
+} else {
+    println!("console API output");
+}
+# }
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConsoleCapabilities {
+    /// What the standard input is attached to.
+    pub input: StreamKind,
+    /// What the standard output is attached to.
+    pub output: StreamKind,
+    /// What the standard error is attached to.
+    pub error: StreamKind,
+    /// Are virtual terminal sequences written to the standard output interpreted,
+    /// or can their processing be enabled?
+    pub virtual_terminal: bool,
+    /// Can mouse input be read from the standard input?
+    pub mouse_input: bool,
+    /// Is the output displayed with a font which supports Unicode (i.e. a TrueType font),
+    /// rather than a raster font?
+    pub unicode_font: bool,
+}
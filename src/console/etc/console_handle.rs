@@ -0,0 +1,71 @@
+use errors::WinResult;
+use std::ptr;
+use utf16;
+use winapi::um::fileapi::{self, OPEN_EXISTING};
+use winapi::um::handleapi::{self, INVALID_HANDLE_VALUE};
+use winapi::um::processenv;
+use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, GENERIC_READ, GENERIC_WRITE, HANDLE};
+
+/// A raw console handle, which is closed when dropped if it is owned.
+#[derive(Debug)]
+pub(crate) struct ConsoleHandle {
+    owned: bool,
+    raw: HANDLE,
+}
+
+// Console handles are not tied to the thread which opened them.
+unsafe impl Send for ConsoleHandle {}
+unsafe impl Sync for ConsoleHandle {}
+
+impl ConsoleHandle {
+    /// Returns a handle which is not closed when dropped.
+    pub(crate) fn borrowed(raw: HANDLE) -> ConsoleHandle {
+        ConsoleHandle { owned: false, raw }
+    }
+    /// Opens a console device, such as `CONIN$` or `CONOUT$`.
+    pub(crate) fn open(name: &str) -> WinResult<ConsoleHandle> {
+        let name = utf16::encode_with_nul(name);
+        let raw = unsafe {
+            fileapi::CreateFileW(
+                name.as_ptr(),
+                GENERIC_READ | GENERIC_WRITE,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                ptr::null_mut(),
+                OPEN_EXISTING,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if raw == INVALID_HANDLE_VALUE {
+            return os_err!();
+        }
+        Ok(ConsoleHandle::owned(raw))
+    }
+    /// Returns a handle which is closed when dropped.
+    pub(crate) fn owned(raw: HANDLE) -> ConsoleHandle {
+        ConsoleHandle { owned: true, raw }
+    }
+    /// Returns a standard handle, which is not closed when dropped.
+    pub(crate) fn std(id: u32) -> WinResult<ConsoleHandle> {
+        Ok(ConsoleHandle::borrowed(unsafe { handle!(id) }))
+    }
+
+    pub(crate) fn get_raw(&self) -> HANDLE {
+        self.raw
+    }
+    /// Returns the raw handle, which is no longer closed when the ConsoleHandle is dropped.
+    pub(crate) fn into_raw(mut self) -> HANDLE {
+        self.owned = false;
+        self.raw
+    }
+}
+
+impl Drop for ConsoleHandle {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe {
+                handleapi::CloseHandle(self.raw);
+            }
+        }
+    }
+}
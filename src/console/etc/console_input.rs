@@ -0,0 +1,193 @@
+use super::{ConsoleHandle, InputModeGuard, InputSettings};
+use console;
+use errors::WinResult;
+#[cfg(feature = "input")]
+use input::{self, InputContext, InputEvent};
+use std::io::{self, Write};
+use std::ptr;
+use std::sync::Arc;
+use utf16::Utf16Decoder;
+use winapi::ctypes::c_void as VOID;
+use winapi::shared::minwindef::DWORD;
+use winapi::um::consoleapi;
+use winapi::um::winbase::STD_INPUT_HANDLE;
+use winapi::um::wincon::CONSOLE_READCONSOLE_CONTROL;
+use winapi::um::winnt::{HANDLE, WCHAR};
+
+/**
+A handle to a console input buffer.
+
+Methods of a ConsoleInput mirror the free functions of the `console` and `input` modules
+which relate to input, but operate on the input buffer the handle refers to rather than on
+the standard input. This allows a redirected standard input to be bypassed by opening
+`CONIN$`, or a handle obtained elsewhere to be used.
+
+Cloning a ConsoleInput does not duplicate the handle. A handle opened by the ConsoleInput is
+closed when the last clone is dropped.
+
+# Examples
+Reads a key press from the console even if the standard input is redirected.
+
+```no_run
+# extern crate winconsole;
+# use winconsole::console::ConsoleInput;
+# fn main() {
+let input = ConsoleInput::open().unwrap();
+let chr = input.getch(true).unwrap();
+println!("Pressed: {}", chr);
+# }
+```
+*/
+#[derive(Clone, Debug)]
+pub struct ConsoleInput {
+    handle: Arc<ConsoleHandle>,
+}
+
+impl ConsoleInput {
+    /**
+    Returns a ConsoleInput which operates on an existing input buffer handle.
+    The handle is not closed when the ConsoleInput is dropped.
+
+    # Arguments
+    * `handle` - The handle to operate on.
+    */
+    pub fn from_raw(handle: HANDLE) -> ConsoleInput {
+        ConsoleInput::new(ConsoleHandle::borrowed(handle))
+    }
+    /**
+    Opens the input buffer of the console (`CONIN$`), regardless of whether
+    the standard input is redirected.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs, such as if the process has no console.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn open() -> WinResult<ConsoleInput> {
+        Ok(ConsoleInput::new(ConsoleHandle::open("CONIN$")?))
+    }
+    /**
+    Returns a ConsoleInput which operates on the standard input handle.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if the standard input handle is invalid.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    */
+    pub fn stdin() -> WinResult<ConsoleInput> {
+        Ok(ConsoleInput::new(ConsoleHandle::std(STD_INPUT_HANDLE)?))
+    }
+    pub(crate) fn new(handle: ConsoleHandle) -> ConsoleInput {
+        ConsoleInput {
+            handle: Arc::new(handle),
+        }
+    }
+
+    /**
+    Flushes the input buffer.
+    See [`flush_input`](fn.flush_input.html).
+    */
+    pub fn flush(&self) -> WinResult<()> {
+        self.with(console::flush_input)
+    }
+    /**
+    Returns the raw handle of the input buffer.
+    */
+    pub fn get_handle(&self) -> HANDLE {
+        self.handle.get_raw()
+    }
+    /**
+    Returns settings related to input from the input buffer.
+    See [`get_input_mode`](fn.get_input_mode.html).
+    */
+    pub fn get_input_mode(&self) -> WinResult<InputSettings> {
+        self.with(console::get_input_mode)
+    }
+    /**
+    Returns the number of input events which are available in the input buffer.
+    See [`input::get_num_input_events`](../input/fn.get_num_input_events.html).
+    */
+    #[cfg(feature = "input")]
+    pub fn get_num_input_events(&self) -> WinResult<u32> {
+        console::num_input_events(self.get_handle())
+    }
+    /**
+    Reads a single character from the input buffer.
+    See [`getch`](fn.getch.html).
+    */
+    pub fn getch(&self, suppress: bool) -> WinResult<char> {
+        let res = self.with(|| {
+            let mut mode = console::get_input_mode()?;
+            mode.EchoInput = false;
+            mode.LineInput = false;
+            let _guard = InputModeGuard::new(mode)?;
+
+            let mut decoder = Utf16Decoder::new();
+            loop {
+                let mut unit: WCHAR = 0;
+                os_err!(unsafe {
+                    let mut num: DWORD = 0;
+                    let buffer_p = &mut unit as *mut WCHAR as *mut VOID;
+                    let control_p: *mut CONSOLE_READCONSOLE_CONTROL = ptr::null_mut();
+                    consoleapi::ReadConsoleW(self.get_handle(), buffer_p, 1, &mut num, control_p)
+                });
+                if let Some(chr) = decoder.push(unit) {
+                    return Ok(chr);
+                }
+            }
+        })?;
+
+        if !suppress {
+            let out = io::stdout();
+            let mut out = out.lock();
+            let mut buffer = [0; 4];
+            out.write_all(res.encode_utf8(&mut buffer).as_bytes())?;
+            out.flush()?;
+        }
+        Ok(res)
+    }
+    /**
+    Sets settings related to input from the input buffer.
+    See [`set_input_mode`](fn.set_input_mode.html).
+    */
+    pub fn set_input_mode(&self, settings: InputSettings) -> WinResult<()> {
+        self.with(|| console::set_input_mode(settings))
+    }
+    /**
+    Creates and returns an InputContext which reads from the input buffer, and initialises input.
+    See [`input::start`](../input/fn.start.html).
+    */
+    #[cfg(feature = "input")]
+    pub fn start(&self) -> WinResult<InputContext> {
+        let mut ctx = InputContext::new(self.clone(), self.get_input_mode()?);
+        ctx.reset();
+        self.flush()?;
+        Ok(ctx)
+    }
+    /**
+    Adds an input event directly to the input buffer.
+    See [`input::write`](../input/fn.write.html).
+    */
+    #[cfg(feature = "input")]
+    pub fn write(
+        &self,
+        event: impl Into<InputEvent>,
+        button_status: impl Into<Option<[bool; 5]>>,
+    ) -> WinResult<()> {
+        let event = event.into();
+        if event == InputEvent::None {
+            return Ok(());
+        }
+
+        let button_status = match button_status.into() {
+            None => input::get_button_status(),
+            Some(status) => status,
+        };
+        let records = input::convert_to_records(event, button_status);
+        console::write_input(self.get_handle(), records)
+    }
+
+    fn with<T>(&self, f: impl FnOnce() -> WinResult<T>) -> WinResult<T> {
+        console::with_handles(Some(self.get_handle()), None, f)
+    }
+}
@@ -0,0 +1,442 @@
+use super::{
+    CellBuffer, ConsoleColor, ConsoleFont, ConsoleHandle, OutputSettings, Rect, TextAttributes,
+    Vector2,
+};
+use console;
+use errors::WinResult;
+use rgb::RGB8;
+use std::sync::Arc;
+use winapi::um::winbase::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE};
+use winapi::um::winnt::HANDLE;
+
+/**
+A handle to a console screen buffer.
+
+Methods of a ConsoleOutput mirror the free functions of the `console` module which relate to
+output, but operate on the screen buffer the handle refers to rather than on the standard output.
+This allows a redirected standard output to be bypassed by opening `CONOUT$`, or a handle
+obtained elsewhere to be used.
+
+Cloning a ConsoleOutput does not duplicate the handle. A handle opened by the ConsoleOutput is
+closed when the last clone is dropped.
+
+# Examples
+Writes to the console even if the standard output is redirected to a file.
+
+```
+# extern crate winconsole;
+# use winconsole::console::ConsoleOutput;
+# fn main() {
+let output = ConsoleOutput::open().unwrap();
+let position = output.get_cursor_position().unwrap();
+output.write_output("Hello, world!", position.x, position.y).unwrap();
+# }
+```
+*/
+#[derive(Clone, Debug)]
+pub struct ConsoleOutput {
+    handle: Arc<ConsoleHandle>,
+}
+
+impl ConsoleOutput {
+    /**
+    Returns a ConsoleOutput which operates on an existing screen buffer handle.
+    The handle is not closed when the ConsoleOutput is dropped.
+
+    # Arguments
+    * `handle` - The handle to operate on.
+    */
+    pub fn from_raw(handle: HANDLE) -> ConsoleOutput {
+        ConsoleOutput::new(ConsoleHandle::borrowed(handle))
+    }
+    /**
+    Opens the active screen buffer of the console (`CONOUT$`), regardless of whether
+    the standard output is redirected.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs, such as if the process has no console.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn open() -> WinResult<ConsoleOutput> {
+        Ok(ConsoleOutput::new(ConsoleHandle::open("CONOUT$")?))
+    }
+    /**
+    Returns a ConsoleOutput which operates on the standard error handle.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if the standard error handle is invalid.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    */
+    pub fn stderr() -> WinResult<ConsoleOutput> {
+        Ok(ConsoleOutput::new(ConsoleHandle::std(STD_ERROR_HANDLE)?))
+    }
+    /**
+    Returns a ConsoleOutput which operates on the standard output handle.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if the standard output handle is invalid.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    */
+    pub fn stdout() -> WinResult<ConsoleOutput> {
+        Ok(ConsoleOutput::new(ConsoleHandle::std(STD_OUTPUT_HANDLE)?))
+    }
+    pub(crate) fn new(handle: ConsoleHandle) -> ConsoleOutput {
+        ConsoleOutput {
+            handle: Arc::new(handle),
+        }
+    }
+
+    /**
+    Clears the screen buffer and moves the cursor to the top-left corner.
+    See [`clear`](fn.clear.html).
+    */
+    pub fn clear(&self) -> WinResult<()> {
+        self.with(console::clear)
+    }
+    /**
+    Fills the screen buffer with text attributes, and returns the number of cells written to.
+    See [`fill_attributes`](fn.fill_attributes.html).
+    */
+    pub fn fill_attributes(
+        &self,
+        attributes: TextAttributes,
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<u32> {
+        self.with(|| console::fill_attributes(attributes, column, row, max_length))
+    }
+    /**
+    Fills the screen buffer with a character, and returns the number of cells written to.
+    See [`fill_character`](fn.fill_character.html).
+    */
+    pub fn fill_character(
+        &self,
+        chr: char,
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<u32> {
+        self.with(|| console::fill_character(chr, column, row, max_length))
+    }
+    /**
+    Fills the screen buffer with foreground and background colors, and returns the number of cells written to.
+    See [`fill_colors`](fn.fill_colors.html).
+    */
+    pub fn fill_colors(
+        &self,
+        colors: (ConsoleColor, ConsoleColor),
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<u32> {
+        self.with(|| console::fill_colors(colors, column, row, max_length))
+    }
+    /**
+    Returns the current background color of the screen buffer.
+    See [`get_background_color`](fn.get_background_color.html).
+    */
+    pub fn get_background_color(&self) -> WinResult<ConsoleColor> {
+        self.with(console::get_background_color)
+    }
+    /**
+    Returns the size of the screen buffer.
+    See [`get_buffer_size`](fn.get_buffer_size.html).
+    */
+    pub fn get_buffer_size(&self) -> WinResult<Vector2<u16>> {
+        self.with(console::get_buffer_size)
+    }
+    /**
+    Returns the RGB color value of a ConsoleColor in the screen buffer.
+    See [`get_color`](fn.get_color.html).
+    */
+    pub fn get_color(&self, color: ConsoleColor) -> WinResult<RGB8> {
+        self.with(|| console::get_color(color))
+    }
+    /**
+    Returns the color mapping of the screen buffer.
+    See [`get_color_mapping`](fn.get_color_mapping.html).
+    */
+    pub fn get_color_mapping(&self) -> WinResult<[RGB8; 16]> {
+        self.with(console::get_color_mapping)
+    }
+    /**
+    Returns the position of the cursor in the screen buffer.
+    See [`get_cursor_position`](fn.get_cursor_position.html).
+    */
+    pub fn get_cursor_position(&self) -> WinResult<Vector2<u16>> {
+        self.with(console::get_cursor_position)
+    }
+    /**
+    Returns the size of the cursor in the screen buffer, from 0 to 100 (inclusive).
+    See [`get_cursor_size`](fn.get_cursor_size.html).
+    */
+    pub fn get_cursor_size(&self) -> WinResult<u8> {
+        self.with(console::get_cursor_size)
+    }
+    /**
+    Returns information about the font used by the screen buffer.
+    See [`get_font`](fn.get_font.html).
+    */
+    pub fn get_font(&self) -> WinResult<ConsoleFont> {
+        self.with(console::get_font)
+    }
+    /**
+    Returns the current foreground color of the screen buffer.
+    See [`get_foreground_color`](fn.get_foreground_color.html).
+    */
+    pub fn get_foreground_color(&self) -> WinResult<ConsoleColor> {
+        self.with(console::get_foreground_color)
+    }
+    /**
+    Returns the raw handle of the screen buffer.
+    */
+    pub fn get_handle(&self) -> HANDLE {
+        self.handle.get_raw()
+    }
+    /**
+    Returns the largest window size possible for the screen buffer, based on its font and the screen size.
+    See [`get_largest_window_size`](fn.get_largest_window_size.html).
+    */
+    pub fn get_largest_window_size(&self) -> WinResult<Vector2<u16>> {
+        self.with(console::get_largest_window_size)
+    }
+    /**
+    Returns settings related to output to the screen buffer.
+    See [`get_output_mode`](fn.get_output_mode.html).
+    */
+    pub fn get_output_mode(&self) -> WinResult<OutputSettings> {
+        self.with(console::get_output_mode)
+    }
+    /**
+    Returns the horizontal or vertical scroll position of the window within the screen buffer.
+    See [`get_scroll_position`](fn.get_scroll_position.html).
+    */
+    pub fn get_scroll_position(&self, vertical: bool) -> WinResult<u16> {
+        self.with(|| console::get_scroll_position(vertical))
+    }
+    /**
+    Returns the text attributes which are applied to text subsequently written to the screen buffer.
+    See [`get_text_attributes`](fn.get_text_attributes.html).
+    */
+    pub fn get_text_attributes(&self) -> WinResult<TextAttributes> {
+        self.with(console::get_text_attributes)
+    }
+    /**
+    Returns the size of the window displaying the screen buffer.
+    See [`get_window_size`](fn.get_window_size.html).
+    */
+    pub fn get_window_size(&self) -> WinResult<Vector2<u16>> {
+        self.with(console::get_window_size)
+    }
+    /**
+    Returns a boolean representing whether or not the cursor is visible in the screen buffer.
+    See [`is_cursor_visible`](fn.is_cursor_visible.html).
+    */
+    pub fn is_cursor_visible(&self) -> WinResult<bool> {
+        self.with(console::is_cursor_visible)
+    }
+    /**
+    Maps a ConsoleColor to an RGB value in the screen buffer.
+    See [`map_color`](fn.map_color.html).
+    */
+    pub fn map_color(&self, color: ConsoleColor, rgb: RGB8) -> WinResult<()> {
+        self.with(|| console::map_color(color, rgb))
+    }
+    /**
+    Moves a rectangle of the screen buffer to another position.
+    See [`move_contents`](fn.move_contents.html).
+    */
+    pub fn move_contents(
+        &self,
+        scroll: Rect,
+        dest: Vector2<i16>,
+        clip: impl Into<Option<Rect>>,
+        fill_char: impl Into<Option<char>>,
+        fill_fg_color: impl Into<Option<ConsoleColor>>,
+        fill_bg_color: impl Into<Option<ConsoleColor>>,
+    ) -> WinResult<()> {
+        self.with(|| {
+            console::move_contents(scroll, dest, clip, fill_char, fill_fg_color, fill_bg_color)
+        })
+    }
+    /**
+    Reads characters from the screen buffer starting at a specified location.
+    See [`read_output`](fn.read_output.html).
+    */
+    pub fn read_output(
+        &self,
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<String> {
+        self.with(|| console::read_output(column, row, max_length))
+    }
+    /**
+    Reads text attributes from the screen buffer starting at a specified location.
+    See [`read_output_attributes`](fn.read_output_attributes.html).
+    */
+    pub fn read_output_attributes(
+        &self,
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<Vec<TextAttributes>> {
+        self.with(|| console::read_output_attributes(column, row, max_length))
+    }
+    /**
+    Reads colors from the screen buffer starting at a specified location.
+    See [`read_output_colors`](fn.read_output_colors.html).
+    */
+    pub fn read_output_colors(
+        &self,
+        column: u16,
+        row: u16,
+        max_length: impl Into<Option<u32>>,
+    ) -> WinResult<Vec<(ConsoleColor, ConsoleColor)>> {
+        self.with(|| console::read_output_colors(column, row, max_length))
+    }
+    /**
+    Reads the cells within a rectangle of the screen buffer.
+    See [`read_region`](fn.read_region.html).
+    */
+    pub fn read_region(&self, rect: Rect) -> WinResult<CellBuffer> {
+        self.with(|| console::read_region(rect))
+    }
+    /**
+    Scrolls the window within the screen buffer by a number of cells.
+    See [`scroll_by`](fn.scroll_by.html).
+    */
+    pub fn scroll_by(&self, amount: i16, vertical: bool) -> WinResult<()> {
+        self.with(|| console::scroll_by(amount, vertical))
+    }
+    /**
+    Scrolls the window within the screen buffer to a position.
+    See [`scroll_to`](fn.scroll_to.html).
+    */
+    pub fn scroll_to(&self, position: u16, vertical: bool) -> WinResult<()> {
+        self.with(|| console::scroll_to(position, vertical))
+    }
+    /**
+    Sets the background color of text subsequently written to the screen buffer.
+    See [`set_background_color`](fn.set_background_color.html).
+    */
+    pub fn set_background_color(&self, color: ConsoleColor) -> WinResult<()> {
+        self.with(|| console::set_background_color(color))
+    }
+    /**
+    Sets the size of the screen buffer.
+    See [`set_buffer_size`](fn.set_buffer_size.html).
+    */
+    pub fn set_buffer_size(&self, width: u16, height: u16) -> WinResult<()> {
+        self.with(|| console::set_buffer_size(width, height))
+    }
+    /**
+    Sets the color mapping of the screen buffer.
+    See [`set_color_mapping`](fn.set_color_mapping.html).
+    */
+    pub fn set_color_mapping(&self, mapping: &[RGB8; 16]) -> WinResult<()> {
+        self.with(|| console::set_color_mapping(mapping))
+    }
+    /**
+    Sets the position of the cursor in the screen buffer.
+    See [`set_cursor_position`](fn.set_cursor_position.html).
+    */
+    pub fn set_cursor_position(&self, column: u16, row: u16) -> WinResult<()> {
+        self.with(|| console::set_cursor_position(column, row))
+    }
+    /**
+    Sets the size of the cursor in the screen buffer, from 0 to 100 (inclusive).
+    See [`set_cursor_size`](fn.set_cursor_size.html).
+    */
+    pub fn set_cursor_size(&self, size: u8) -> WinResult<()> {
+        self.with(|| console::set_cursor_size(size))
+    }
+    /**
+    Sets whether or not the cursor is visible in the screen buffer.
+    See [`set_cursor_visible`](fn.set_cursor_visible.html).
+    */
+    pub fn set_cursor_visible(&self, visible: bool) -> WinResult<()> {
+        self.with(|| console::set_cursor_visible(visible))
+    }
+    /**
+    Sets the font used by the screen buffer.
+    See [`set_font`](fn.set_font.html).
+    */
+    pub fn set_font(&self, font: &ConsoleFont) -> WinResult<()> {
+        self.with(|| console::set_font(font))
+    }
+    /**
+    Sets the foreground color of text subsequently written to the screen buffer.
+    See [`set_foreground_color`](fn.set_foreground_color.html).
+    */
+    pub fn set_foreground_color(&self, color: ConsoleColor) -> WinResult<()> {
+        self.with(|| console::set_foreground_color(color))
+    }
+    /**
+    Sets settings related to output to the screen buffer.
+    See [`set_output_mode`](fn.set_output_mode.html).
+    */
+    pub fn set_output_mode(&self, settings: OutputSettings) -> WinResult<()> {
+        self.with(|| console::set_output_mode(settings))
+    }
+    /**
+    Sets the text attributes which are applied to text subsequently written to the screen buffer.
+    See [`set_text_attributes`](fn.set_text_attributes.html).
+    */
+    pub fn set_text_attributes(&self, attributes: TextAttributes) -> WinResult<()> {
+        self.with(|| console::set_text_attributes(attributes))
+    }
+    /**
+    Sets the size of the window displaying the screen buffer.
+    See [`set_window_size`](fn.set_window_size.html).
+    */
+    pub fn set_window_size(&self, columns: u16, rows: u16) -> WinResult<()> {
+        self.with(|| console::set_window_size(columns, rows))
+    }
+    /**
+    Writes characters to the screen buffer starting at a specified location, and returns the number of cells written to.
+    See [`write_output`](fn.write_output.html).
+    */
+    pub fn write_output(&self, string: &str, column: u16, row: u16) -> WinResult<u32> {
+        self.with(|| console::write_output(string, column, row))
+    }
+    /**
+    Writes text attributes to the screen buffer starting at a specified location, and returns the number of cells written to.
+    See [`write_output_attributes`](fn.write_output_attributes.html).
+    */
+    pub fn write_output_attributes(
+        &self,
+        attributes: &[TextAttributes],
+        column: u16,
+        row: u16,
+    ) -> WinResult<u32> {
+        self.with(|| console::write_output_attributes(attributes, column, row))
+    }
+    /**
+    Writes colors to the screen buffer starting at a specified location, and returns the number of cells written to.
+    See [`write_output_colors`](fn.write_output_colors.html).
+    */
+    pub fn write_output_colors(
+        &self,
+        colors: &[(ConsoleColor, ConsoleColor)],
+        column: u16,
+        row: u16,
+    ) -> WinResult<u32> {
+        self.with(|| console::write_output_colors(colors, column, row))
+    }
+    /**
+    Writes cells to a rectangle of the screen buffer.
+    See [`write_region`](fn.write_region.html).
+    */
+    pub fn write_region(&self, rect: Rect, buffer: &CellBuffer) -> WinResult<()> {
+        self.with(|| console::write_region(rect, buffer))
+    }
+
+    fn with<T>(&self, f: impl FnOnce() -> WinResult<T>) -> WinResult<T> {
+        console::with_handles(None, Some(self.get_handle()), f)
+    }
+}
@@ -1,4 +1,6 @@
-use super::{CodePage, ConsoleColor, ConsoleFont, InputSettings, OutputSettings, Vector2};
+use super::{
+    CodePage, ConsoleColor, ConsoleFont, InputSettings, OutputSettings, TextAttributes, Vector2,
+};
 use rgb::RGB8;
 
 #[cfg(feature = "serde")]
@@ -35,8 +37,8 @@ pub struct ConsoleState {
     pub output: String,
     /// The console output code page.
     pub output_code_page: CodePage,
-    /// The colors of the console's output contents.
-    pub output_colors: Vec<(ConsoleColor, ConsoleColor)>,
+    /// The text attributes of the console's output contents.
+    pub output_colors: Vec<TextAttributes>,
     /// The console output mode.
     pub output_mode: OutputSettings,
     /// The console window title.
@@ -0,0 +1,15 @@
+/// Signals which the console sends to the processes attached to it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CtrlEvent {
+    /// CTRL + C was pressed, or was generated by a process.
+    CtrlC,
+    /// CTRL + BREAK was pressed, or was generated by a process.
+    CtrlBreak,
+    /// The console is being closed.
+    Close,
+    /// The user is logging off. This is only received by services.
+    Logoff,
+    /// The system is shutting down. This is only received by services.
+    Shutdown,
+}
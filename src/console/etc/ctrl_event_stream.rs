@@ -0,0 +1,69 @@
+use super::CtrlEvent;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::task::{Context, Poll, Waker};
+
+/**
+An asynchronous stream of the Ctrl events which the console sends to the process, which is
+returned from [`ctrl_event_stream`](fn.ctrl_event_stream.html).
+
+Events are considered handled while the stream exists. The stream never ends.
+*/
+#[derive(Debug)]
+pub struct CtrlEventStream {
+    queue: Arc<Mutex<EventQueue>>,
+}
+
+#[derive(Debug, Default)]
+struct EventQueue {
+    events: VecDeque<CtrlEvent>,
+    waker: Option<Waker>,
+}
+
+impl CtrlEventStream {
+    pub(crate) fn new() -> CtrlEventStream {
+        CtrlEventStream {
+            queue: Arc::new(Mutex::new(EventQueue::default())),
+        }
+    }
+
+    /// Returns a handler which adds events to the stream while it exists.
+    pub(crate) fn handler(&self) -> impl Fn(CtrlEvent) -> bool + Send + Sync + 'static {
+        let queue: Weak<Mutex<EventQueue>> = Arc::downgrade(&self.queue);
+        move |event| match queue.upgrade() {
+            Some(queue) => {
+                let mut queue = lock(&queue);
+                queue.events.push_back(event);
+                if let Some(waker) = queue.waker.take() {
+                    waker.wake();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Stream for CtrlEventStream {
+    type Item = CtrlEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<CtrlEvent>> {
+        let mut queue = lock(&self.queue);
+        match queue.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn lock(queue: &Mutex<EventQueue>) -> MutexGuard<'_, EventQueue> {
+    match queue.lock() {
+        Ok(queue) => queue,
+        Err(err) => err.into_inner(),
+    }
+}
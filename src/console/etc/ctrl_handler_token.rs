@@ -0,0 +1,14 @@
+/**
+Identifies a handler which was added with [`add_ctrl_handler`] or [`ctrl_events`], and is
+used to remove it with [`remove_ctrl_handler`].
+
+Dropping the token does not remove the handler.
+
+[`add_ctrl_handler`]: fn.add_ctrl_handler.html
+[`ctrl_events`]: fn.ctrl_events.html
+[`remove_ctrl_handler`]: fn.remove_ctrl_handler.html
+*/
+#[derive(Debug, PartialEq)]
+pub struct CtrlHandlerToken {
+    pub(crate) id: u64,
+}
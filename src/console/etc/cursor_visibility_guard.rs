@@ -0,0 +1,72 @@
+use console;
+use errors::WinResult;
+
+/**
+Changes the visibility of the cursor, and restores the previous value when it is dropped,
+including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::CursorVisibilityGuard;
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let _guard = CursorVisibilityGuard::new(false).unwrap();
+println!("Working...");
+# }
+```
+*/
+#[derive(Debug)]
+pub struct CursorVisibilityGuard {
+    previous: Option<bool>,
+}
+
+impl CursorVisibilityGuard {
+    /**
+    Changes the visibility of the cursor, and returns a guard which restores the previous value.
+
+    # Arguments
+    * `visible` - Should the cursor be visible?
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(visible: bool) -> WinResult<CursorVisibilityGuard> {
+        let previous = console::is_cursor_visible()?;
+        console::set_cursor_visible(visible)?;
+        Ok(CursorVisibilityGuard {
+            previous: Some(previous),
+        })
+    }
+
+    /**
+    Restores the previous value, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) => console::set_cursor_visible(previous),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for CursorVisibilityGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            console::set_cursor_visible(previous).unwrap_or(())
+        }
+    }
+}
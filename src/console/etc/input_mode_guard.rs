@@ -0,0 +1,74 @@
+use super::InputSettings;
+use console;
+use errors::WinResult;
+
+/**
+Changes settings related to console input, and restores the previous value when it is dropped,
+including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, InputModeGuard};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut mode = console::get_input_mode().unwrap();
+mode.EchoInput = false;
+let _guard = InputModeGuard::new(mode).unwrap();
+# }
+```
+*/
+#[derive(Debug)]
+pub struct InputModeGuard {
+    previous: Option<InputSettings>,
+}
+
+impl InputModeGuard {
+    /**
+    Changes settings related to console input, and returns a guard which restores the previous value.
+
+    # Arguments
+    * `settings` - The input settings to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(settings: InputSettings) -> WinResult<InputModeGuard> {
+        let previous = console::get_input_mode()?;
+        console::set_input_mode(settings)?;
+        Ok(InputModeGuard {
+            previous: Some(previous),
+        })
+    }
+
+    /**
+    Restores the previous value, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) => console::set_input_mode(previous),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for InputModeGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            console::set_input_mode(previous).unwrap_or(())
+        }
+    }
+}
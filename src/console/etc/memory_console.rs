@@ -0,0 +1,557 @@
+use super::{
+    set_nearest_color, CodePage, Color, ColorMetric, ConsoleBackend, ConsoleColor, ConsoleFont,
+    HistoryInfo, InputSettings, OutputSettings, Rect, ScreenBufferInfo, SelectionInfo,
+    StandardStream, StreamKind, Vector2,
+};
+use errors::{WinError, WinResult};
+use rgb::RGB8;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const DEFAULT_ATTRIBUTES: u16 = 0x07;
+const DEFAULT_COLORS: [u32; 16] = [
+    0x00_0000, 0x80_0000, 0x00_8000, 0x80_8000, 0x00_0080, 0x80_0080, 0x00_8080, 0xC0_C0C0,
+    0x80_8080, 0xFF_0000, 0x00_FF00, 0xFF_FF00, 0x00_00FF, 0xFF_00FF, 0x00_FFFF, 0xFF_FFFF,
+];
+
+/**
+A console which exists only in memory.
+
+A `MemoryConsole` models a screen buffer (characters, text attributes, the cursor and the window
+rectangle) along with the color mapping, title, code pages and modes of a console. Once it is
+installed with [`set_backend`], functions in the `console` module operate on it instead of the
+real console, so code which uses the console can be run and asserted on without one.
+
+Clones of a `MemoryConsole` share the same state.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, MemoryConsole};
+# fn main() {
+let memory = MemoryConsole::new(80, 25);
+console::set_backend(memory.clone());
+
+console::write_output("Hello, world!", 0, 1).unwrap();
+assert_eq!(memory.lines()[1], "Hello, world!");
+
+console::reset_backend();
+# }
+```
+
+[`set_backend`]: fn.set_backend.html
+*/
+#[derive(Clone, Debug)]
+pub struct MemoryConsole {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Clone, Debug)]
+struct MemoryState {
+    attributes: u16,
+    buffer_size: Vector2<u16>,
+    cells: Vec<(char, u16)>,
+    color_mapping: [RGB8; 16],
+    cursor_position: Vector2<u16>,
+    cursor_size: u8,
+    cursor_visible: bool,
+    // The colors which were last set with `set_color`, if the attributes still use them.
+    exact_colors: (Option<Color>, Option<Color>),
+    font: ConsoleFont,
+    history: HistoryInfo,
+    input_code_page: CodePage,
+    input_mode: InputSettings,
+    largest_window_size: Vector2<u16>,
+    original_title: String,
+    output_code_page: CodePage,
+    output_mode: OutputSettings,
+    stream_kinds: [StreamKind; 3],
+    title: String,
+    window: Rect,
+}
+
+impl MemoryConsole {
+    /**
+    Creates a new MemoryConsole with a blank screen buffer.
+    The window initially displays the entire buffer.
+
+    # Arguments
+    * `columns` - The amount of columns the screen buffer should have.
+    * `rows` - The amount of rows the screen buffer should have.
+
+    # Panics
+    Panics if `columns` or `rows` is zero.
+    */
+    pub fn new(columns: u16, rows: u16) -> MemoryConsole {
+        assert!(columns > 0 && rows > 0, "buffer size must be non-zero");
+
+        let input_mode = InputSettings {
+            EchoInput: true,
+            InsertMode: true,
+            LineInput: true,
+            MouseInput: true,
+            ProcessedInput: true,
+            ..InputSettings::default()
+        };
+        let output_mode = OutputSettings {
+            ProcessedOutput: true,
+            WrapAtEol: true,
+            ..OutputSettings::default()
+        };
+        let font = ConsoleFont {
+            family: 54,
+            name: String::from("Consolas"),
+            size: Vector2::new(8, 16),
+            weight: 400,
+            ..ConsoleFont::default()
+        };
+        let history = HistoryInfo {
+            size: 50,
+            number_of_buffers: 4,
+            duplicates_allowed: true,
+        };
+
+        let length = usize::from(columns) * usize::from(rows);
+        let state = MemoryState {
+            attributes: DEFAULT_ATTRIBUTES,
+            buffer_size: Vector2::new(columns, rows),
+            cells: vec![(' ', DEFAULT_ATTRIBUTES); length],
+            color_mapping: default_color_mapping(),
+            cursor_position: Vector2::new(0, 0),
+            cursor_size: 25,
+            cursor_visible: true,
+            exact_colors: (None, None),
+            font,
+            history,
+            input_code_page: CodePage::IBM437,
+            input_mode,
+            largest_window_size: Vector2::new(columns, rows),
+            original_title: String::new(),
+            output_code_page: CodePage::IBM437,
+            output_mode,
+            stream_kinds: [StreamKind::Console; 3],
+            title: String::new(),
+            window: Rect::new(0, 0, columns - 1, rows - 1),
+        };
+
+        MemoryConsole {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /**
+    Returns the foreground or background color used for new output. Colors which aren't
+    palette colors are returned as they were set, although the text attributes hold the
+    nearest palette color in their place.
+
+    # Arguments
+    * `background` - Should the background color be returned? Otherwise, the foreground
+      color is returned.
+
+    # Examples
+    ```
+    # extern crate rgb;
+    # extern crate winconsole;
+    # use rgb::RGB8;
+    # use winconsole::console::{self, Color, ConsoleColor, MemoryConsole};
+    # fn main() {
+    let memory = MemoryConsole::new(80, 25);
+    console::set_backend(memory.clone());
+
+    let orange = RGB8 { r: 250, g: 40, b: 20 };
+    console::set_foreground(orange).unwrap();
+    assert_eq!(memory.get_color(false), Color::Rgb(orange));
+    assert_eq!(console::get_foreground_color().unwrap(), ConsoleColor::Red);
+    assert_eq!(memory.get_color(true), Color::Named(ConsoleColor::Black));
+
+    console::reset_backend();
+    # }
+    ```
+    */
+    pub fn get_color(&self, background: bool) -> Color {
+        let state = self.state();
+        let (exact, value) = if background {
+            (state.exact_colors.1, (state.attributes & 0xF0) >> 4)
+        } else {
+            (state.exact_colors.0, state.attributes & 0xF)
+        };
+        exact.unwrap_or_else(|| Color::Named(ConsoleColor::from(value)))
+    }
+    /**
+    Returns the text of each row of the screen buffer, without trailing spaces.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{ConsoleBackend, MemoryConsole, Vector2};
+    # fn main() {
+    let mut memory = MemoryConsole::new(10, 2);
+    memory.write_characters("abc", Vector2::new(8, 0)).unwrap();
+    assert_eq!(memory.lines(), vec!["        ab", "c"]);
+    # }
+    ```
+    */
+    pub fn lines(&self) -> Vec<String> {
+        let state = self.state();
+        let width = usize::from(state.buffer_size.x);
+        state
+            .cells
+            .chunks(width)
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.0).collect();
+                line.trim_end_matches(' ').to_string()
+            })
+            .collect()
+    }
+    /**
+    Sets the size of the largest window which the console allows.
+    This defaults to the initial size of the buffer.
+
+    # Arguments
+    * `size` - The largest window size, in character cells.
+    */
+    pub fn set_largest_window_size(&self, size: Vector2<u16>) {
+        self.state().largest_window_size = size;
+    }
+    /**
+    Sets what a standard stream is reported to be attached to.
+    Every stream is reported to be attached to a console by default, as the MemoryConsole
+    stands in for one.
+
+    # Arguments
+    * `stream` - The standard stream to set the kind of.
+    * `kind` - The kind to report.
+    */
+    pub fn set_stream_kind(&self, stream: StandardStream, kind: StreamKind) {
+        self.state().stream_kinds[stream_index(stream)] = kind;
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+impl MemoryState {
+    fn index(&self, position: Vector2<u16>) -> WinResult<usize> {
+        if position.x >= self.buffer_size.x || position.y >= self.buffer_size.y {
+            return Err(invalid("position is outside of the buffer"));
+        }
+        Ok(usize::from(position.y) * usize::from(self.buffer_size.x) + usize::from(position.x))
+    }
+    fn range(&self, position: Vector2<u16>, length: u32) -> WinResult<(usize, usize)> {
+        let start = self.index(position)?;
+        let end = start.saturating_add(length as usize).min(self.cells.len());
+        Ok((start, end))
+    }
+}
+
+impl ConsoleBackend for MemoryConsole {
+    fn fill_attributes(
+        &mut self,
+        attributes: u16,
+        length: u32,
+        position: Vector2<u16>,
+    ) -> WinResult<u32> {
+        let mut state = self.state();
+        let (start, end) = state.range(position, length)?;
+        for cell in &mut state.cells[start..end] {
+            cell.1 = attributes;
+        }
+        Ok((end - start) as u32)
+    }
+    fn fill_character(&mut self, chr: char, length: u32, position: Vector2<u16>) -> WinResult<u32> {
+        let mut state = self.state();
+        let (start, end) = state.range(position, length)?;
+        for cell in &mut state.cells[start..end] {
+            cell.0 = chr;
+        }
+        Ok((end - start) as u32)
+    }
+    fn flush_input(&mut self) -> WinResult<()> {
+        Ok(())
+    }
+    fn get_color_mapping(&mut self) -> WinResult<[RGB8; 16]> {
+        Ok(self.state().color_mapping)
+    }
+    fn get_cursor_size(&mut self) -> WinResult<u8> {
+        Ok(self.state().cursor_size)
+    }
+    fn get_font(&mut self) -> WinResult<ConsoleFont> {
+        Ok(self.state().font.clone())
+    }
+    fn get_history_info(&mut self) -> WinResult<HistoryInfo> {
+        Ok(self.state().history)
+    }
+    fn get_input_code_page(&mut self) -> WinResult<CodePage> {
+        Ok(self.state().input_code_page)
+    }
+    fn get_input_mode(&mut self) -> WinResult<InputSettings> {
+        Ok(self.state().input_mode)
+    }
+    fn get_largest_window_size(&mut self) -> WinResult<Vector2<u16>> {
+        Ok(self.state().largest_window_size)
+    }
+    fn get_original_title(&mut self) -> WinResult<String> {
+        Ok(self.state().original_title.clone())
+    }
+    fn get_output_code_page(&mut self) -> WinResult<CodePage> {
+        Ok(self.state().output_code_page)
+    }
+    fn get_output_mode(&mut self) -> WinResult<OutputSettings> {
+        Ok(self.state().output_mode)
+    }
+    fn get_screen_buffer_info(&mut self) -> WinResult<ScreenBufferInfo> {
+        let state = self.state();
+        let largest = state.largest_window_size;
+        Ok(ScreenBufferInfo {
+            attributes: state.attributes,
+            buffer_size: state.buffer_size,
+            cursor_position: state.cursor_position,
+            maximum_window_size: Vector2::new(
+                largest.x.min(state.buffer_size.x),
+                largest.y.min(state.buffer_size.y),
+            ),
+            window: state.window,
+        })
+    }
+    fn get_selection_info(&mut self) -> WinResult<SelectionInfo> {
+        Ok(SelectionInfo {
+            empty: true,
+            ..SelectionInfo::default()
+        })
+    }
+    fn get_stream_kind(&mut self, stream: StandardStream) -> StreamKind {
+        self.state().stream_kinds[stream_index(stream)]
+    }
+    fn get_title(&mut self) -> WinResult<String> {
+        Ok(self.state().title.clone())
+    }
+    fn is_cursor_visible(&mut self) -> WinResult<bool> {
+        Ok(self.state().cursor_visible)
+    }
+    fn read_attributes(&mut self, position: Vector2<u16>, length: u32) -> WinResult<Vec<u16>> {
+        let state = self.state();
+        let (start, end) = state.range(position, length)?;
+        Ok(state.cells[start..end].iter().map(|cell| cell.1).collect())
+    }
+    fn read_characters(&mut self, position: Vector2<u16>, length: u32) -> WinResult<String> {
+        let state = self.state();
+        let (start, end) = state.range(position, length)?;
+        Ok(state.cells[start..end].iter().map(|cell| cell.0).collect())
+    }
+    fn scroll_contents(
+        &mut self,
+        scroll: Rect,
+        clip: Option<Rect>,
+        dest: Vector2<i16>,
+        fill_char: char,
+        fill_attributes: u16,
+    ) -> WinResult<()> {
+        let mut state = self.state();
+        let width = i32::from(state.buffer_size.x);
+        let height = i32::from(state.buffer_size.y);
+
+        let (left, top) = (i32::from(scroll.left), i32::from(scroll.top));
+        let right = i32::from(scroll.right).min(width - 1);
+        let bottom = i32::from(scroll.bottom).min(height - 1);
+        if left > right || top > bottom {
+            return Err(invalid("scroll rectangle is outside of the buffer"));
+        }
+
+        let clip = match clip {
+            Some(c) => (
+                i32::from(c.left),
+                i32::from(c.top),
+                i32::from(c.right).min(width - 1),
+                i32::from(c.bottom).min(height - 1),
+            ),
+            None => (0, 0, width - 1, height - 1),
+        };
+        let in_clip = |x: i32, y: i32| x >= clip.0 && y >= clip.1 && x <= clip.2 && y <= clip.3;
+        let index = |x: i32, y: i32| (y * width + x) as usize;
+
+        let (dx, dy) = (i32::from(dest.x) - left, i32::from(dest.y) - top);
+        let source = state.cells.clone();
+        for y in top..=bottom {
+            for x in left..=right {
+                if !in_clip(x, y) {
+                    continue;
+                }
+                let (sx, sy) = (x - dx, y - dy);
+                let covered = sx >= left && sx <= right && sy >= top && sy <= bottom;
+                if !covered {
+                    state.cells[index(x, y)] = (fill_char, fill_attributes);
+                }
+            }
+        }
+        for y in top..=bottom {
+            for x in left..=right {
+                let (tx, ty) = (x + dx, y + dy);
+                if tx < 0 || ty < 0 || tx >= width || ty >= height || !in_clip(tx, ty) {
+                    continue;
+                }
+                state.cells[index(tx, ty)] = source[index(x, y)];
+            }
+        }
+        Ok(())
+    }
+    fn set_buffer_size(&mut self, size: Vector2<u16>) -> WinResult<()> {
+        let mut state = self.state();
+        if size.x <= state.window.right || size.y <= state.window.bottom {
+            return Err(invalid("buffer must be at least as large as the window"));
+        }
+
+        let old_width = usize::from(state.buffer_size.x);
+        let (width, height) = (usize::from(size.x), usize::from(size.y));
+        let mut cells = vec![(' ', state.attributes); width * height];
+        for (y, row) in state.cells.chunks(old_width).take(height).enumerate() {
+            let length = row.len().min(width);
+            cells[y * width..y * width + length].copy_from_slice(&row[..length]);
+        }
+
+        state.cells = cells;
+        state.buffer_size = size;
+        state.cursor_position = Vector2::new(
+            state.cursor_position.x.min(size.x - 1),
+            state.cursor_position.y.min(size.y - 1),
+        );
+        Ok(())
+    }
+    fn set_color(&mut self, color: Color, background: bool, metric: ColorMetric) -> WinResult<()> {
+        set_nearest_color(self, color, background, metric)?;
+        let mut state = self.state();
+        if background {
+            state.exact_colors.1 = Some(color);
+        } else {
+            state.exact_colors.0 = Some(color);
+        }
+        Ok(())
+    }
+    fn set_color_mapping(&mut self, mapping: &[RGB8; 16]) -> WinResult<()> {
+        self.state().color_mapping = *mapping;
+        Ok(())
+    }
+    fn set_cursor_position(&mut self, position: Vector2<u16>) -> WinResult<()> {
+        let mut state = self.state();
+        state.index(position)?;
+        state.cursor_position = position;
+
+        let mut window = state.window;
+        if position.x < window.left {
+            window.right -= window.left - position.x;
+            window.left = position.x;
+        } else if position.x > window.right {
+            window.left += position.x - window.right;
+            window.right = position.x;
+        }
+        if position.y < window.top {
+            window.bottom -= window.top - position.y;
+            window.top = position.y;
+        } else if position.y > window.bottom {
+            window.top += position.y - window.bottom;
+            window.bottom = position.y;
+        }
+        state.window = window;
+        Ok(())
+    }
+    fn set_cursor_size(&mut self, size: u8) -> WinResult<()> {
+        self.state().cursor_size = size;
+        Ok(())
+    }
+    fn set_cursor_visible(&mut self, visible: bool) -> WinResult<()> {
+        self.state().cursor_visible = visible;
+        Ok(())
+    }
+    fn set_font(&mut self, font: &ConsoleFont) -> WinResult<()> {
+        self.state().font = font.clone();
+        Ok(())
+    }
+    fn set_history_info(&mut self, history: HistoryInfo) -> WinResult<()> {
+        self.state().history = history;
+        Ok(())
+    }
+    fn set_input_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        self.state().input_code_page = page;
+        Ok(())
+    }
+    fn set_input_mode(&mut self, settings: InputSettings) -> WinResult<()> {
+        self.state().input_mode = settings;
+        Ok(())
+    }
+    fn set_output_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        self.state().output_code_page = page;
+        Ok(())
+    }
+    fn set_output_mode(&mut self, settings: OutputSettings) -> WinResult<()> {
+        self.state().output_mode = settings;
+        Ok(())
+    }
+    fn set_text_attributes(&mut self, attributes: u16) -> WinResult<()> {
+        let mut state = self.state();
+        let changed = state.attributes ^ attributes;
+        if changed & 0xF != 0 {
+            state.exact_colors.0 = None;
+        }
+        if changed & 0xF0 != 0 {
+            state.exact_colors.1 = None;
+        }
+        state.attributes = attributes;
+        Ok(())
+    }
+    fn set_title(&mut self, title: &str) -> WinResult<()> {
+        self.state().title = title.to_string();
+        Ok(())
+    }
+    fn set_window_rect(&mut self, rect: Rect) -> WinResult<()> {
+        let mut state = self.state();
+        let size = state.buffer_size;
+        let largest = state.largest_window_size;
+        if rect.left > rect.right || rect.top > rect.bottom {
+            return Err(invalid("window rectangle is empty"));
+        } else if rect.right >= size.x || rect.bottom >= size.y {
+            return Err(invalid("window must be within the buffer"));
+        } else if rect.right - rect.left >= largest.x || rect.bottom - rect.top >= largest.y {
+            return Err(invalid("window is larger than the largest window size"));
+        }
+        state.window = rect;
+        Ok(())
+    }
+    fn write_attributes(&mut self, attributes: &[u16], position: Vector2<u16>) -> WinResult<u32> {
+        let mut state = self.state();
+        let (start, end) = state.range(position, attributes.len() as u32)?;
+        for (cell, attrs) in state.cells[start..end].iter_mut().zip(attributes) {
+            cell.1 = *attrs;
+        }
+        Ok((end - start) as u32)
+    }
+    fn write_characters(&mut self, string: &str, position: Vector2<u16>) -> WinResult<u32> {
+        let mut state = self.state();
+        let (start, end) = state.range(position, string.chars().count() as u32)?;
+        for (cell, chr) in state.cells[start..end].iter_mut().zip(string.chars()) {
+            cell.0 = chr;
+        }
+        Ok((end - start) as u32)
+    }
+}
+
+/// Returns the default color mapping of a console.
+pub(crate) fn default_color_mapping() -> [RGB8; 16] {
+    let mut color_mapping = [RGB8 { r: 0, g: 0, b: 0 }; 16];
+    for (i, color) in DEFAULT_COLORS.iter().enumerate() {
+        color_mapping[i] = make_rgb!(color);
+    }
+    color_mapping
+}
+fn invalid(message: &str) -> WinError {
+    WinError::from(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+fn stream_index(stream: StandardStream) -> usize {
+    match stream {
+        StandardStream::Input => 0,
+        StandardStream::Output => 1,
+        StandardStream::Error => 2,
+    }
+}
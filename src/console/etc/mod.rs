@@ -1,23 +1,116 @@
+mod attribute_flags;
+mod cell;
+mod cell_buffer;
 mod code_page;
+mod code_page_guard;
 mod code_page_info;
+mod color;
+mod color_guard;
+mod color_metric;
+mod color_mode;
+mod color_writer;
+mod console_backend;
+mod console_capabilities;
 mod console_color;
 mod console_font;
+#[cfg(windows)]
+mod console_handle;
+#[cfg(windows)]
+mod console_input;
+#[cfg(windows)]
+mod console_output;
 mod console_state;
+mod ctrl_event;
+#[cfg(all(windows, feature = "async"))]
+mod ctrl_event_stream;
+#[cfg(windows)]
+mod ctrl_handler_token;
+mod cursor_visibility_guard;
 mod history_info;
+mod input_mode_guard;
 mod input_settings;
+mod memory_console;
+mod output_mode_guard;
 mod output_settings;
+mod process_group;
+#[cfg(windows)]
+mod pseudo_console;
 mod rect;
+mod renderer;
+#[cfg(windows)]
+mod screen_buffer;
+#[cfg(windows)]
+mod screen_buffer_guard;
+mod screen_buffer_info;
 mod selection_info;
+mod standard_stream;
+mod state_guard;
+mod stream_kind;
+mod styled_span;
+mod styled_string;
+mod text_attributes;
+mod text_style;
+mod title_guard;
 mod vector2;
+mod vt_console;
+mod vt_parser;
 
+pub use self::attribute_flags::AttributeFlags;
+pub use self::cell::Cell;
+pub use self::cell_buffer::CellBuffer;
 pub use self::code_page::CodePage;
+pub use self::code_page_guard::CodePageGuard;
 pub use self::code_page_info::CodePageInfo;
+pub(crate) use self::color::ansi_color;
+pub use self::color::Color;
+pub use self::color_guard::ColorGuard;
+pub use self::color_metric::ColorMetric;
+pub use self::color_mode::ColorMode;
+pub use self::color_writer::ColorWriter;
+pub(crate) use self::console_backend::set_nearest_color;
+pub use self::console_backend::ConsoleBackend;
+pub use self::console_capabilities::ConsoleCapabilities;
 pub use self::console_color::ConsoleColor;
 pub use self::console_font::ConsoleFont;
+#[cfg(windows)]
+pub(crate) use self::console_handle::ConsoleHandle;
+#[cfg(windows)]
+pub use self::console_input::ConsoleInput;
+#[cfg(windows)]
+pub use self::console_output::ConsoleOutput;
 pub use self::console_state::ConsoleState;
+pub use self::ctrl_event::CtrlEvent;
+#[cfg(all(windows, feature = "async"))]
+pub use self::ctrl_event_stream::CtrlEventStream;
+#[cfg(windows)]
+pub use self::ctrl_handler_token::CtrlHandlerToken;
+pub use self::cursor_visibility_guard::CursorVisibilityGuard;
 pub use self::history_info::HistoryInfo;
+pub use self::input_mode_guard::InputModeGuard;
 pub use self::input_settings::InputSettings;
+pub(crate) use self::memory_console::default_color_mapping;
+pub use self::memory_console::MemoryConsole;
+pub use self::output_mode_guard::OutputModeGuard;
 pub use self::output_settings::OutputSettings;
+pub use self::process_group::ProcessGroup;
+#[cfg(windows)]
+pub use self::pseudo_console::PseudoConsole;
 pub use self::rect::Rect;
+pub use self::renderer::Renderer;
+#[cfg(windows)]
+pub use self::screen_buffer::ScreenBuffer;
+#[cfg(windows)]
+pub use self::screen_buffer_guard::ScreenBufferGuard;
+pub use self::screen_buffer_info::ScreenBufferInfo;
 pub use self::selection_info::SelectionInfo;
+pub use self::standard_stream::StandardStream;
+pub use self::state_guard::StateGuard;
+pub use self::stream_kind::StreamKind;
+pub use self::styled_span::StyledSpan;
+pub use self::styled_string::StyledString;
+pub use self::text_attributes::TextAttributes;
+pub use self::text_style::TextStyle;
+pub use self::title_guard::TitleGuard;
 pub use self::vector2::Vector2;
+pub use self::vt_console::VtConsole;
+pub use self::vt_parser::VtParser;
@@ -0,0 +1,74 @@
+use super::OutputSettings;
+use console;
+use errors::WinResult;
+
+/**
+Changes settings related to console output, and restores the previous value when it is dropped,
+including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, OutputModeGuard};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut mode = console::get_output_mode().unwrap();
+mode.WrapAtEol = false;
+let _guard = OutputModeGuard::new(mode).unwrap();
+# }
+```
+*/
+#[derive(Debug)]
+pub struct OutputModeGuard {
+    previous: Option<OutputSettings>,
+}
+
+impl OutputModeGuard {
+    /**
+    Changes settings related to console output, and returns a guard which restores the previous value.
+
+    # Arguments
+    * `settings` - The output settings to apply.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(settings: OutputSettings) -> WinResult<OutputModeGuard> {
+        let previous = console::get_output_mode()?;
+        console::set_output_mode(settings)?;
+        Ok(OutputModeGuard {
+            previous: Some(previous),
+        })
+    }
+
+    /**
+    Restores the previous value, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) => console::set_output_mode(previous),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for OutputModeGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            console::set_output_mode(previous).unwrap_or(())
+        }
+    }
+}
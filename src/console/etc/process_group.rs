@@ -0,0 +1,25 @@
+use std::process::Child;
+
+/**
+The processes which a Ctrl event is generated for; see
+[`generate_ctrl_event`](fn.generate_ctrl_event.html).
+*/
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ProcessGroup {
+    /// All processes which share the console of the calling process.
+    Console,
+    /// The process group with the given ID, which is the ID of the process that created it.
+    Id(u32),
+}
+
+impl From<u32> for ProcessGroup {
+    fn from(id: u32) -> ProcessGroup {
+        ProcessGroup::Id(id)
+    }
+}
+impl<'a> From<&'a Child> for ProcessGroup {
+    fn from(child: &'a Child) -> ProcessGroup {
+        ProcessGroup::Id(child.id())
+    }
+}
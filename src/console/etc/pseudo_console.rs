@@ -0,0 +1,334 @@
+use super::{ConsoleHandle, VtParser};
+use errors::{ArgumentError, WinResult};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::windows::io::{FromRawHandle, RawHandle};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::{mem, ptr};
+use utf16;
+use winapi::shared::basetsd::SIZE_T;
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::um::consoleapi;
+use winapi::um::minwinbase::STILL_ACTIVE;
+use winapi::um::namedpipeapi;
+use winapi::um::processthreadsapi::{self, LPPROC_THREAD_ATTRIBUTE_LIST, PROCESS_INFORMATION};
+use winapi::um::synchapi;
+use winapi::um::winbase::{EXTENDED_STARTUPINFO_PRESENT, INFINITE, STARTUPINFOEXW, WAIT_OBJECT_0};
+use winapi::um::wincontypes::{COORD, HPCON};
+use winapi::um::winnt::{HANDLE, HRESULT};
+
+const PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE: usize = 0x0002_0016;
+
+/**
+A pseudo console which hosts a child process, and parses its output into a grid of cells.
+
+The child process sees a console of the given size. Its output is read on a background
+thread and fed to a [`VtParser`], which maintains the screen the program would display.
+Input, such as keystrokes encoded as VT sequences, can be written to the child with
+[`write_input`].
+
+The pseudo console is closed when the PseudoConsole is dropped, which ends the child process
+if it is still attached to it. Pseudo consoles require Windows 10 version 1809 or later.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::console::PseudoConsole;
+# fn main() {
+let pty = PseudoConsole::spawn("cmd.exe /c echo hello", 80, 25).unwrap();
+pty.wait().unwrap();
+let parser = pty.close();
+assert_eq!(parser.lines()[0], "hello");
+# }
+```
+
+[`VtParser`]: struct.VtParser.html
+[`write_input`]: #method.write_input
+*/
+#[derive(Debug)]
+pub struct PseudoConsole {
+    console: HPCON,
+    input: File,
+    parser: Arc<Mutex<VtParser>>,
+    process: ConsoleHandle,
+    process_id: u32,
+    reader: Option<JoinHandle<()>>,
+}
+
+// The pseudo console handle may be used and closed from any thread.
+unsafe impl Send for PseudoConsole {}
+
+impl PseudoConsole {
+    /**
+    Creates a pseudo console and starts a process which is attached to it.
+
+    # Arguments
+    * `command_line` - The command line of the process to start.
+    * `columns` - The amount of columns the pseudo console has.
+    * `rows` - The amount of rows the pseudo console has.
+
+    # Errors
+    * [`ArgumentError`]: Returned if `columns` or `rows` is zero or larger than `i16::MAX`.
+    * [`IoError`]: Returned if an OS error occurs, such as if the program cannot be found.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn spawn(command_line: &str, columns: u16, rows: u16) -> WinResult<PseudoConsole> {
+        let size = coord(columns, rows)?;
+        // The pseudo console reads from `input_read` and writes to `output_write`.
+        let (input_read, input_write) = create_pipe()?;
+        let (output_read, output_write) = create_pipe()?;
+
+        let mut console: HPCON = ptr::null_mut();
+        hresult(unsafe {
+            consoleapi::CreatePseudoConsole(
+                size,
+                input_read.get_raw(),
+                output_write.get_raw(),
+                0,
+                &mut console,
+            )
+        })?;
+        // The pseudo console has its own duplicates of these handles.
+        drop(input_read);
+        drop(output_write);
+
+        let info = match start_process(command_line, console) {
+            Ok(info) => info,
+            Err(err) => {
+                unsafe { consoleapi::ClosePseudoConsole(console) };
+                return Err(err);
+            }
+        };
+        drop(ConsoleHandle::owned(info.hThread));
+
+        let parser = Arc::new(Mutex::new(VtParser::new(columns, rows)));
+        let mut output = unsafe { File::from_raw_handle(output_read.into_raw() as RawHandle) };
+        let shared = parser.clone();
+        let reader = thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            // Reading fails once the pseudo console is closed.
+            loop {
+                match output.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => lock(&shared).feed(&buffer[..read]),
+                }
+            }
+        });
+
+        Ok(PseudoConsole {
+            console,
+            input: unsafe { File::from_raw_handle(input_write.into_raw() as RawHandle) },
+            parser,
+            process: ConsoleHandle::owned(info.hProcess),
+            process_id: info.dwProcessId,
+            reader: Some(reader),
+        })
+    }
+
+    /**
+    Closes the pseudo console, and returns the parser once all of the output has been parsed.
+    The child process is ended if it is still attached to the pseudo console.
+    */
+    pub fn close(mut self) -> VtParser {
+        self.shutdown();
+        self.get_parser().clone()
+    }
+    /**
+    Returns the parser which the output of the child process is fed to.
+    Output is not parsed until the returned guard is dropped.
+    */
+    pub fn get_parser(&self) -> MutexGuard<'_, VtParser> {
+        lock(&self.parser)
+    }
+    /**
+    Returns the ID of the child process.
+    */
+    pub fn get_process_id(&self) -> u32 {
+        self.process_id
+    }
+    /**
+    Changes the size of the pseudo console and of the screen of its parser.
+
+    # Arguments
+    * `columns` - The amount of columns the pseudo console should have.
+    * `rows` - The amount of rows the pseudo console should have.
+
+    # Errors
+    * [`ArgumentError`]: Returned if `columns` or `rows` is zero or larger than `i16::MAX`.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn resize(&self, columns: u16, rows: u16) -> WinResult<()> {
+        let size = coord(columns, rows)?;
+        let mut parser = self.get_parser();
+        hresult(unsafe { consoleapi::ResizePseudoConsole(self.console, size) })?;
+        parser.resize(columns, rows);
+        Ok(())
+    }
+    /**
+    Returns the exit code of the child process if it has exited, or None if it is still running.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn try_wait(&self) -> WinResult<Option<u32>> {
+        if unsafe { synchapi::WaitForSingleObject(self.process.get_raw(), 0) } != WAIT_OBJECT_0 {
+            return Ok(None);
+        }
+        self.exit_code().map(Some)
+    }
+    /**
+    Waits for the child process to exit, and returns its exit code.
+    Output which the process wrote before exiting may still be in the process of being parsed;
+    use [`close`](#method.close) to wait for all of it.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait(&self) -> WinResult<u32> {
+        if unsafe { synchapi::WaitForSingleObject(self.process.get_raw(), INFINITE) }
+            != WAIT_OBJECT_0
+        {
+            return os_err!();
+        }
+        self.exit_code()
+    }
+    /**
+    Writes bytes to the input of the child process. Keys should be encoded as VT sequences.
+
+    # Arguments
+    * `bytes` - The bytes to write.
+
+    # Errors
+    * [`IoError`]: Returned if writing fails.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn write_input(&self, bytes: &[u8]) -> WinResult<()> {
+        let mut input = &self.input;
+        input.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn exit_code(&self) -> WinResult<u32> {
+        let mut code: DWORD = STILL_ACTIVE;
+        os_err!(unsafe {
+            processthreadsapi::GetExitCodeProcess(self.process.get_raw(), &mut code)
+        });
+        Ok(code)
+    }
+    fn shutdown(&mut self) {
+        if !self.console.is_null() {
+            unsafe { consoleapi::ClosePseudoConsole(self.console) };
+            self.console = ptr::null_mut();
+        }
+        if let Some(reader) = self.reader.take() {
+            reader.join().unwrap_or(());
+        }
+    }
+}
+
+impl Drop for PseudoConsole {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn coord(columns: u16, rows: u16) -> WinResult<COORD> {
+    if columns == 0 || columns > i16::MAX as u16 {
+        throw_err!(ArgumentError::new("columns", "must be between 1 and 32767"));
+    }
+    if rows == 0 || rows > i16::MAX as u16 {
+        throw_err!(ArgumentError::new("rows", "must be between 1 and 32767"));
+    }
+    Ok(COORD {
+        X: columns as i16,
+        Y: rows as i16,
+    })
+}
+fn create_pipe() -> WinResult<(ConsoleHandle, ConsoleHandle)> {
+    let mut read: HANDLE = ptr::null_mut();
+    let mut write: HANDLE = ptr::null_mut();
+    os_err!(unsafe { namedpipeapi::CreatePipe(&mut read, &mut write, ptr::null_mut(), 0) });
+    Ok((ConsoleHandle::owned(read), ConsoleHandle::owned(write)))
+}
+fn create_process(
+    command_line: &str,
+    console: HPCON,
+    list: LPPROC_THREAD_ATTRIBUTE_LIST,
+) -> WinResult<PROCESS_INFORMATION> {
+    os_err!(unsafe {
+        processthreadsapi::UpdateProcThreadAttribute(
+            list,
+            0,
+            PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
+            console,
+            mem::size_of::<HPCON>(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    });
+
+    let mut startup: STARTUPINFOEXW = unsafe { mem::zeroed() };
+    startup.StartupInfo.cb = mem::size_of::<STARTUPINFOEXW>() as DWORD;
+    startup.lpAttributeList = list;
+    // CreateProcessW may modify the command line.
+    let mut command_line = utf16::encode_with_nul(command_line);
+    let mut info: PROCESS_INFORMATION = unsafe { mem::zeroed() };
+    os_err!(unsafe {
+        processthreadsapi::CreateProcessW(
+            ptr::null(),
+            command_line.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            FALSE,
+            EXTENDED_STARTUPINFO_PRESENT,
+            ptr::null_mut(),
+            ptr::null(),
+            &mut startup.StartupInfo,
+            &mut info,
+        )
+    });
+    Ok(info)
+}
+fn hresult(result: HRESULT) -> WinResult<()> {
+    if result >= 0 {
+        return Ok(());
+    }
+    // Errors which wrap a Win32 error code are reported as that code.
+    let code = if result as u32 & 0xFFFF_0000 == 0x8007_0000 {
+        result & 0xFFFF
+    } else {
+        result
+    };
+    Err(io::Error::from_raw_os_error(code).into())
+}
+fn lock(parser: &Mutex<VtParser>) -> MutexGuard<'_, VtParser> {
+    match parser.lock() {
+        Ok(parser) => parser,
+        Err(err) => err.into_inner(),
+    }
+}
+fn start_process(command_line: &str, console: HPCON) -> WinResult<PROCESS_INFORMATION> {
+    let mut size: SIZE_T = 0;
+    // The first call fails, and returns the size of the attribute list.
+    unsafe {
+        processthreadsapi::InitializeProcThreadAttributeList(ptr::null_mut(), 1, 0, &mut size)
+    };
+    let mut buffer = vec![0usize; size / mem::size_of::<usize>() + 1];
+    let list = buffer.as_mut_ptr() as LPPROC_THREAD_ATTRIBUTE_LIST;
+    os_err!(unsafe { processthreadsapi::InitializeProcThreadAttributeList(list, 1, 0, &mut size) });
+
+    let result = create_process(command_line, console, list);
+    unsafe { processthreadsapi::DeleteProcThreadAttributeList(list) };
+    result
+}
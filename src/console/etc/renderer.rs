@@ -0,0 +1,209 @@
+use super::{CellBuffer, Rect, Vector2};
+use console;
+use errors::WinResult;
+
+/**
+A double-buffered renderer which draws frames to an area of the console output.
+
+Drawing is done on an off-screen frame, which is written to the console by
+[`present`]. Only the cells which changed since the previously presented frame
+are written, which avoids flickering when the screen is redrawn often.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ConsoleColor, Renderer};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let mut renderer = Renderer::new(20, 2);
+for i in 0..3 {
+    let frame = renderer.get_frame_mut();
+    frame.write_str("Frame:", 0, 0, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str(&i.to_string(), 7, 0, ConsoleColor::Yellow, ConsoleColor::Black);
+    renderer.present().unwrap();
+}
+# }
+```
+
+[`present`]: #method.present
+*/
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    /// The largest amount of unchanged cells between two changed runs on the same row
+    /// which are written together. Writing a few unchanged cells is usually cheaper than
+    /// writing to the console twice.
+    pub merge_distance: u16,
+
+    back: CellBuffer,
+    front: CellBuffer,
+    invalidated: bool,
+    origin: Vector2<u16>,
+}
+
+impl Renderer {
+    /**
+    Creates a new Renderer which draws to the top-left corner of the console output.
+    The first frame presented is written in full.
+
+    # Arguments
+    * `columns` - The amount of columns in a frame.
+    * `rows` - The amount of rows in a frame.
+    */
+    pub fn new(columns: u16, rows: u16) -> Renderer {
+        Renderer {
+            merge_distance: 4,
+            back: CellBuffer::new(columns, rows),
+            front: CellBuffer::new(columns, rows),
+            invalidated: true,
+            origin: Vector2::new(0, 0),
+        }
+    }
+
+    /**
+    Returns the runs of cells which will be written by the next call to [`present`],
+    relative to the top-left corner of the frame. This does not interact with the console.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{ConsoleColor, Rect, Renderer};
+    # fn main() {
+    let mut renderer = Renderer::new(10, 2);
+    renderer.mark_presented();
+
+    let frame = renderer.get_frame_mut();
+    frame.write_str("a", 1, 1, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str("b", 3, 1, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str("c", 9, 1, ConsoleColor::Gray, ConsoleColor::Black);
+    assert_eq!(
+        renderer.get_changes(),
+        vec![Rect::new(1, 1, 3, 1), Rect::new(1, 9, 9, 1)]
+    );
+    # }
+    ```
+
+    [`present`]: #method.present
+    */
+    pub fn get_changes(&self) -> Vec<Rect> {
+        let size = self.back.get_size();
+        if size.x == 0 || size.y == 0 {
+            return Vec::new();
+        }
+        if self.invalidated {
+            return vec![Rect::new(0, 0, size.x - 1, size.y - 1)];
+        }
+
+        let mut ret: Vec<Rect> = Vec::new();
+        for run in self.back.compare(&self.front) {
+            if let Some(last) = ret.last_mut() {
+                if last.top == run.top && run.left - last.right - 1 <= self.merge_distance {
+                    last.right = run.right;
+                    continue;
+                }
+            }
+            ret.push(run);
+        }
+        ret
+    }
+    /**
+    Returns the frame which is being drawn.
+    */
+    pub fn get_frame(&self) -> &CellBuffer {
+        &self.back
+    }
+    /**
+    Returns a mutable reference to the frame which is being drawn.
+    */
+    pub fn get_frame_mut(&mut self) -> &mut CellBuffer {
+        &mut self.back
+    }
+    /**
+    Returns the position of the top-left corner of the frame in the console output.
+    */
+    pub fn get_origin(&self) -> Vector2<u16> {
+        self.origin
+    }
+    /**
+    Causes the next call to [`present`] to write the entire frame, such as after the
+    console output was changed by other means.
+
+    [`present`]: #method.present
+    */
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+    /**
+    Marks the current frame as presented without writing it to the console.
+    This is useful if the console output is known to already match the frame.
+    */
+    pub fn mark_presented(&mut self) {
+        self.front = self.back.clone();
+        self.invalidated = false;
+    }
+    /**
+    Writes the cells which changed since the previous frame to the console output, and
+    returns the number of cells written. Cells which lie outside of the output buffer
+    are not written.
+
+    The presented frame is kept as the starting point for the next frame.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn present(&mut self) -> WinResult<u32> {
+        let buffer_size = console::get_buffer_size()?;
+        let mut written = 0;
+        for run in self.get_changes() {
+            let top = u32::from(self.origin.y) + u32::from(run.top);
+            let left = u32::from(self.origin.x) + u32::from(run.left);
+            if top >= u32::from(buffer_size.y) || left >= u32::from(buffer_size.x) {
+                continue;
+            }
+
+            let right =
+                (u32::from(self.origin.x) + u32::from(run.right)).min(u32::from(buffer_size.x) - 1);
+            let bottom = (u32::from(self.origin.y) + u32::from(run.bottom))
+                .min(u32::from(buffer_size.y) - 1);
+            let rect = Rect::new(top as u16, left as u16, right as u16, bottom as u16);
+            console::write_region(rect, &self.back.slice(run))?;
+            written += (right - left + 1) * (bottom - top + 1);
+        }
+
+        self.mark_presented();
+        Ok(written)
+    }
+    /**
+    Changes the size of frames. The frame being drawn is cleared, and the next call to
+    [`present`] writes the entire frame.
+
+    # Arguments
+    * `columns` - The amount of columns in a frame.
+    * `rows` - The amount of rows in a frame.
+
+    [`present`]: #method.present
+    */
+    pub fn resize(&mut self, columns: u16, rows: u16) {
+        self.back = CellBuffer::new(columns, rows);
+        self.front = CellBuffer::new(columns, rows);
+        self.invalidated = true;
+    }
+    /**
+    Sets the position of the top-left corner of the frame in the console output.
+    The next call to [`present`] writes the entire frame.
+
+    # Arguments
+    * `column` - The column of the top-left corner.
+    * `row` - The row of the top-left corner.
+
+    [`present`]: #method.present
+    */
+    pub fn set_origin(&mut self, column: u16, row: u16) {
+        self.origin = Vector2::new(column, row);
+        self.invalidated = true;
+    }
+}
@@ -0,0 +1,106 @@
+use super::{ConsoleHandle, ConsoleOutput, ScreenBufferGuard};
+use errors::WinResult;
+use std::ops::Deref;
+use std::ptr;
+use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+use winapi::um::wincon::{self, CONSOLE_TEXTMODE_BUFFER};
+use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, GENERIC_READ, GENERIC_WRITE};
+
+/**
+A console screen buffer which is separate from the standard output.
+
+The methods of [`ConsoleOutput`] are available on a ScreenBuffer, and operate on
+the screen buffer rather than on the standard output. A screen buffer is only displayed
+while it is active; see [`activate`]. Output written through `print!` and similar macros
+still goes to the standard output.
+
+The screen buffer is closed when the ScreenBuffer is dropped.
+
+# Examples
+Displays a message in an alternate screen buffer, and returns to the original buffer
+when a key is pressed.
+
+```no_run
+# extern crate winconsole;
+# use winconsole::console::{self, ScreenBuffer};
+# fn main() {
+let buffer = ScreenBuffer::new().unwrap();
+let guard = buffer.activate().unwrap();
+buffer.write_output("Press any key to exit.", 0, 0).unwrap();
+console::getch(true).unwrap();
+drop(guard);
+# }
+```
+
+[`activate`]: #method.activate
+[`ConsoleOutput`]: struct.ConsoleOutput.html
+*/
+#[derive(Debug)]
+pub struct ScreenBuffer {
+    output: ConsoleOutput,
+}
+
+impl ScreenBuffer {
+    /**
+    Creates a new screen buffer with the default size and attributes.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new() -> WinResult<ScreenBuffer> {
+        let handle = unsafe {
+            wincon::CreateConsoleScreenBuffer(
+                GENERIC_READ | GENERIC_WRITE,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                ptr::null(),
+                CONSOLE_TEXTMODE_BUFFER,
+                ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return os_err!();
+        }
+        Ok(ScreenBuffer {
+            output: ConsoleOutput::new(ConsoleHandle::owned(handle)),
+        })
+    }
+
+    /**
+    Makes this the active screen buffer, and returns a guard which makes the previously
+    active screen buffer active again when it is dropped.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if the active screen buffer cannot be retrieved.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn activate(&self) -> WinResult<ScreenBufferGuard<'_>> {
+        ScreenBufferGuard::new(self)
+    }
+    /**
+    Makes this the active screen buffer without restoring the previous buffer.
+    If this screen buffer is dropped while it is active, the console does not display
+    any buffer until another one is activated.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn set_active(&self) -> WinResult<()> {
+        os_err!(unsafe { wincon::SetConsoleActiveScreenBuffer(self.get_handle()) });
+        Ok(())
+    }
+}
+
+impl Deref for ScreenBuffer {
+    type Target = ConsoleOutput;
+
+    fn deref(&self) -> &ConsoleOutput {
+        &self.output
+    }
+}
@@ -0,0 +1,35 @@
+use super::{ConsoleHandle, ScreenBuffer};
+use errors::WinResult;
+use std::marker::PhantomData;
+use winapi::um::wincon;
+
+/**
+Keeps a screen buffer active, and makes the previously active screen buffer active again
+when it is dropped. Returned by [`ScreenBuffer::activate`].
+
+[`ScreenBuffer::activate`]: struct.ScreenBuffer.html#method.activate
+*/
+#[derive(Debug)]
+pub struct ScreenBufferGuard<'a> {
+    previous: ConsoleHandle,
+    phantom: PhantomData<&'a ScreenBuffer>,
+}
+
+impl<'a> ScreenBufferGuard<'a> {
+    pub(crate) fn new(buffer: &'a ScreenBuffer) -> WinResult<ScreenBufferGuard<'a>> {
+        let previous = ConsoleHandle::open("CONOUT$")?;
+        os_err!(unsafe { wincon::SetConsoleActiveScreenBuffer(buffer.get_handle()) });
+        Ok(ScreenBufferGuard {
+            previous,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a> Drop for ScreenBufferGuard<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            wincon::SetConsoleActiveScreenBuffer(self.previous.get_raw());
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use super::{Rect, Vector2};
+
+/// Information about a console screen buffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScreenBufferInfo {
+    /// The current text attributes of the buffer.
+    pub attributes: u16,
+    /// The size of the buffer, in character cells.
+    pub buffer_size: Vector2<u16>,
+    /// The position of the cursor within the buffer.
+    pub cursor_position: Vector2<u16>,
+    /// The maximum size of the window given the buffer size, font and display size.
+    pub maximum_window_size: Vector2<u16>,
+    /// The area of the buffer which is displayed in the window.
+    pub window: Rect,
+}
+
+impl Default for ScreenBufferInfo {
+    /**
+    Returns an empty ScreenBufferInfo object.
+    */
+    fn default() -> Self {
+        Self {
+            attributes: 0,
+            buffer_size: Vector2::new(0, 0),
+            cursor_position: Vector2::new(0, 0),
+            maximum_window_size: Vector2::new(0, 0),
+            window: Rect::new(0, 0, 0, 0),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+/// The standard streams of a process.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StandardStream {
+    /// The standard input.
+    Input,
+    /// The standard output.
+    Output,
+    /// The standard error.
+    Error,
+}
@@ -0,0 +1,93 @@
+use super::ConsoleState;
+use console;
+use errors::WinResult;
+
+/**
+Saves the state of the console, and restores it when it is dropped, including while
+unwinding from a panic. See [`get_state`] and [`set_state`].
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ConsoleColor, StateGuard};
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+{
+    let _guard = StateGuard::new(false, false).unwrap();
+    console::set_foreground_color(ConsoleColor::Green).unwrap();
+    console::set_title("Something else").unwrap();
+}
+println!("The color and title are back to normal.");
+# }
+```
+
+[`get_state`]: fn.get_state.html
+[`set_state`]: fn.set_state.html
+*/
+#[derive(Debug)]
+pub struct StateGuard {
+    copy_output: bool,
+    state: Option<ConsoleState>,
+}
+
+impl StateGuard {
+    /**
+    Saves the state of the console, and returns a guard which restores it.
+
+    # Arguments
+    * `copy_output` - Should the output buffer be saved, and written back when the state is restored?
+    * `copy_all` - Should the entire output buffer be saved, rather than only the output before the cursor?
+
+    # Errors
+    * [`FromUtf8Error`]: Returned if an error occurs while converting to a string.
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`FromUtf8Error`]: ../errors/enum.WinError.html#FromUtf8.v
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(copy_output: bool, copy_all: bool) -> WinResult<StateGuard> {
+        Ok(StateGuard {
+            copy_output,
+            state: Some(console::get_state(copy_output, copy_all)?),
+        })
+    }
+
+    /**
+    Returns the saved state.
+    */
+    pub fn get_state(&self) -> Option<&ConsoleState> {
+        self.state.as_ref()
+    }
+    /**
+    Restores the saved state, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`ArgumentError`]: Returned if data in the state is invalid.
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input/output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        self.restore_state()
+    }
+
+    fn restore_state(&mut self) -> WinResult<()> {
+        match self.state.take() {
+            Some(state) => console::set_state(&state, self.copy_output, self.copy_output),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for StateGuard {
+    fn drop(&mut self) {
+        self.restore_state().unwrap_or(())
+    }
+}
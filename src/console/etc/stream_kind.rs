@@ -0,0 +1,28 @@
+/// Describes what a standard stream is attached to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StreamKind {
+    /// The stream is attached to a console. Console functions which operate on the
+    /// stream are available.
+    Console,
+    /// The stream is attached to an anonymous or named pipe which is not a pty.
+    Pipe,
+    /// The stream is attached to a file on disk.
+    File,
+    /// The stream is attached to the pty of a terminal emulator such as mintty, which uses
+    /// Cygwin or MSYS pipes, or to a terminal on platforms other than Windows. Console
+    /// functions are not available, but virtual terminal sequences are generally interpreted.
+    Pty,
+    /// The stream is invalid, or is attached to something else, such as the `NUL` device.
+    Unknown,
+}
+
+impl StreamKind {
+    /**
+    Returns whether or not the stream is attached to a terminal which displays output to the user,
+    i.e. a console or a pty.
+    */
+    pub fn is_terminal(self) -> bool {
+        self == StreamKind::Console || self == StreamKind::Pty
+    }
+}
@@ -0,0 +1,27 @@
+use super::TextStyle;
+
+/// A run of text which is displayed with a single style.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StyledSpan {
+    /// The text of the span.
+    pub text: String,
+    /// The style the text is displayed with.
+    pub style: TextStyle,
+}
+
+impl StyledSpan {
+    /**
+    Creates a new StyledSpan.
+
+    # Arguments
+    * `text` - The text of the span.
+    * `style` - The style the text is displayed with.
+    */
+    pub fn new(text: impl Into<String>, style: TextStyle) -> StyledSpan {
+        StyledSpan {
+            text: text.into(),
+            style,
+        }
+    }
+}
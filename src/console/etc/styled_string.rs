@@ -0,0 +1,379 @@
+use super::{ColorGuard, ConsoleColor, StyledSpan, TextAttributes, TextStyle};
+use console;
+use errors::{ArgumentError, WinError, WinResult};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+use std::mem;
+
+/**
+Text made up of spans, each of which is displayed with its own colors and attribute flags.
+
+A StyledString can be built span by span, or parsed from markup using
+[`parse`](#method.parse) or the [`cformat!`](../macro.cformat.html) macro.
+Formatting a StyledString with `{}` produces its text without any styling.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ConsoleColor, StyledString, TextStyle};
+# fn main() {
+let mut error = TextStyle::new();
+error.foreground = Some(ConsoleColor::Red);
+
+let mut string = StyledString::new();
+string.push("error", error);
+string.push(": file not found", TextStyle::new());
+assert_eq!(string.to_string(), "error: file not found");
+assert_eq!(string.get_spans().len(), 2);
+# }
+```
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StyledString {
+    spans: Vec<StyledSpan>,
+}
+
+impl StyledString {
+    /**
+    Creates a new, empty StyledString.
+    */
+    pub fn new() -> StyledString {
+        StyledString::default()
+    }
+    /**
+    Parses a StyledString from markup.
+
+    Text inside the markup is copied as-is, except for tags enclosed in braces:
+    * `{}` is replaced by the next item of `args`.
+    * `{fg:<color>}` and `{bg:<color>}` set the foreground and background colors of the
+      text which follows, where `<color>` is the name of a [`ConsoleColor`]. Names are not
+      case-sensitive, and words may be separated by `_` or `-` (e.g. `dark_blue`).
+    * `{underscore}` and `{reverse}` set the `Underscore` and `ReverseVideo` attribute flags.
+    * Several of the above may be combined in one tag, separated by commas,
+      such as `{fg:white,bg:red,underscore}`.
+    * `{/}` closes the most recently opened style tag. Styles may be nested; a nested
+      tag inherits any colors and flags it does not set itself.
+    * `{{` and `}}` are replaced by literal braces.
+
+    Colors which are not set by any open tag are inherited from the console when the
+    string is written.
+
+    # Arguments
+    * `markup` - The markup to parse.
+    * `args` - The values which replace `{}` placeholders, in order.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{ConsoleColor, StyledString};
+    # fn main() {
+    let file = "main.rs";
+    let string = StyledString::parse("{fg:red}error{/} in {bg:blue}{}{/}", &[&file]).unwrap();
+    assert_eq!(string.to_string(), "error in main.rs");
+
+    let spans = string.get_spans();
+    assert_eq!(spans[0].style.foreground, Some(ConsoleColor::Red));
+    assert_eq!(spans[2].text, "main.rs");
+    assert_eq!(spans[2].style.background, Some(ConsoleColor::Blue));
+
+    let err = StyledString::parse("{fg:redd}error{/}", &[]).unwrap_err();
+    assert!(err.to_string().contains("unknown color \"redd\""));
+    # }
+    ```
+
+    # Errors
+    * [`ArgumentError`]: Returned if the markup contains an unknown tag or color, a tag which
+      is not terminated, a `{/}` which does not close a tag or a style tag which is never closed,
+      or if the number of placeholders does not match the length of `args`.
+      The message includes the byte position of the offending tag.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`ConsoleColor`]: enum.ConsoleColor.html
+    */
+    pub fn parse(markup: &str, args: &[&dyn Display]) -> WinResult<StyledString> {
+        let mut result = StyledString::new();
+        let mut stack: Vec<(usize, TextStyle)> = Vec::new();
+        let mut text = String::new();
+        let mut next_arg = 0;
+        let mut chars = markup.char_indices().peekable();
+
+        while let Some((position, chr)) = chars.next() {
+            let escaped = chars.peek().map(|&(_, next)| next) == Some(chr);
+            match chr {
+                '{' | '}' if escaped => {
+                    chars.next();
+                    text.push(chr);
+                }
+                '{' => {
+                    let end = match markup[position..].find('}') {
+                        Some(offset) => position + offset,
+                        None => {
+                            return Err(markup_error(format!(
+                                "tag at position {} is not terminated with `}}`",
+                                position
+                            )));
+                        }
+                    };
+                    for (i, _) in chars.by_ref() {
+                        if i == end {
+                            break;
+                        }
+                    }
+
+                    let tag = markup[position + 1..end].trim();
+                    let current = stack.last().map(|&(_, style)| style).unwrap_or_default();
+                    if tag.is_empty() {
+                        let arg = match args.get(next_arg) {
+                            Some(arg) => arg,
+                            None => {
+                                return Err(markup_error(format!(
+                                    "placeholder at position {} refers to argument {}, \
+                                     but only {} were given",
+                                    position,
+                                    next_arg + 1,
+                                    args.len()
+                                )));
+                            }
+                        };
+                        text.push_str(&arg.to_string());
+                        next_arg += 1;
+                    } else if tag == "/" {
+                        result.push(mem::take(&mut text), current);
+                        if stack.pop().is_none() {
+                            return Err(markup_error(format!(
+                                "`{{/}}` at position {} does not close any tag",
+                                position
+                            )));
+                        }
+                    } else {
+                        let style = parse_style(tag, position)?;
+                        result.push(mem::take(&mut text), current);
+                        stack.push((position, inherit(style, current)));
+                    }
+                }
+                '}' => {
+                    return Err(markup_error(format!(
+                        "unmatched `}}` at position {}; use `}}}}` for a literal brace",
+                        position
+                    )));
+                }
+                _ => text.push(chr),
+            }
+        }
+
+        if let Some(&(position, _)) = stack.last() {
+            return Err(markup_error(format!(
+                "tag at position {} is never closed with `{{/}}`",
+                position
+            )));
+        }
+        if next_arg < args.len() {
+            return Err(markup_error(format!(
+                "{} arguments were given, but the markup only has {} placeholders",
+                args.len(),
+                next_arg
+            )));
+        }
+        result.push(text, TextStyle::new());
+        Ok(result)
+    }
+
+    /**
+    Returns the spans which make up the string.
+    */
+    pub fn get_spans(&self) -> &[StyledSpan] {
+        &self.spans
+    }
+    /**
+    Appends text with a style to the end of the string. If the last span has the same style,
+    the text is appended to it; otherwise, a new span is added. Empty text is ignored.
+
+    # Arguments
+    * `text` - The text to append.
+    * `style` - The style to display the text with.
+    */
+    pub fn push(&mut self, text: impl Into<String>, style: TextStyle) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+
+        if let Some(last) = self.spans.last_mut() {
+            if last.style == style {
+                last.text.push_str(&text);
+                return;
+            }
+        }
+        self.spans.push(StyledSpan::new(text, style));
+    }
+    /**
+    Writes the string to the standard output at the cursor position, changing the text
+    attributes for each span. The text attributes are restored after each span,
+    even if writing fails.
+    This has a side effect of flushing the console output.
+
+    # Examples
+    ```
+    # #[macro_use] extern crate winconsole;
+    # fn main() {
+    # #[cfg(not(windows))]
+    # winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+    cformat!("{fg:green}ok{/}: {} tests passed\n", 12).unwrap().write().unwrap();
+    # }
+    ```
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn write(&self) -> WinResult<()> {
+        let base = console::get_text_attributes()?;
+        let out = io::stdout();
+        let mut out = out.lock();
+        for span in &self.spans {
+            let _guard = ColorGuard::with_attributes(span.style.apply(base))?;
+            out.write_all(span.text.as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+    /**
+    Writes the string directly to the output buffer at a specified position along with the
+    attributes of each span, and returns the number of cells which were written to.
+    Neither the cursor position nor the current text attributes are changed.
+    See [`write_output`](fn.write_output.html).
+
+    # Arguments
+    * `column` - The column at which writing will begin.
+    * `row` - The row at which writing will begin.
+
+    # Examples
+    ```
+    # #[macro_use] extern crate winconsole;
+    # fn main() {
+    # #[cfg(not(windows))]
+    # winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+    let status = cformat!("[{fg:yellow}busy{/}]").unwrap();
+    status.write_at(0, 0).unwrap();
+    # }
+    ```
+
+    # Errors
+    * [`ArgumentError`]: Returned if `column` or `row` is not within the output buffer.
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console output is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn write_at(&self, column: u16, row: u16) -> WinResult<u32> {
+        let base = console::get_text_attributes()?;
+        let written = console::write_output(&self.to_string(), column, row)?;
+
+        let attributes: Vec<TextAttributes> = self
+            .spans
+            .iter()
+            .flat_map(|span| {
+                let attributes = span.style.apply(base);
+                span.text.chars().map(move |_| attributes)
+            })
+            .take(written as usize)
+            .collect();
+        console::write_output_attributes(&attributes, column, row)?;
+        Ok(written)
+    }
+}
+
+impl Display for StyledString {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for span in &self.spans {
+            f.write_str(&span.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a str> for StyledString {
+    fn from(text: &'a str) -> StyledString {
+        StyledString::from(String::from(text))
+    }
+}
+
+impl From<String> for StyledString {
+    fn from(text: String) -> StyledString {
+        let mut string = StyledString::new();
+        string.push(text, TextStyle::new());
+        string
+    }
+}
+
+fn inherit(style: TextStyle, outer: TextStyle) -> TextStyle {
+    let flags: u16 = style.flags.into();
+    let outer_flags: u16 = outer.flags.into();
+    TextStyle {
+        foreground: style.foreground.or(outer.foreground),
+        background: style.background.or(outer.background),
+        flags: (flags | outer_flags).into(),
+    }
+}
+
+fn markup_error(message: String) -> WinError {
+    WinError::from(ArgumentError::new("markup", message))
+}
+
+fn parse_color(name: &str, position: usize) -> WinResult<ConsoleColor> {
+    let normalized: String = name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    for value in 0..16u16 {
+        let color = ConsoleColor::from(value);
+        let color_name = color.to_string();
+        let color_name = color_name.trim_start_matches("ConsoleColor::");
+        if color_name.to_lowercase() == normalized {
+            return Ok(color);
+        }
+    }
+    Err(markup_error(format!(
+        "unknown color \"{}\" in tag at position {}; \
+         expected the name of a ConsoleColor, such as red or dark_blue",
+        name, position
+    )))
+}
+
+fn parse_style(tag: &str, position: usize) -> WinResult<TextStyle> {
+    let mut style = TextStyle::new();
+    for item in tag.split(',') {
+        let item = item.trim();
+        let (name, value) = match item.find(':') {
+            Some(i) => (item[..i].trim(), Some(item[i + 1..].trim())),
+            None => (item, None),
+        };
+
+        match (name.to_lowercase().as_str(), value) {
+            ("fg", Some(color)) | ("foreground", Some(color)) => {
+                style.foreground = Some(parse_color(color, position)?);
+            }
+            ("bg", Some(color)) | ("background", Some(color)) => {
+                style.background = Some(parse_color(color, position)?);
+            }
+            ("underscore", None) | ("underline", None) => style.flags.Underscore = true,
+            ("reverse", None) => style.flags.ReverseVideo = true,
+            _ => {
+                return Err(markup_error(format!(
+                    "unknown style \"{}\" in tag at position {}; \
+                     expected fg:<color>, bg:<color>, underscore or reverse",
+                    item, position
+                )));
+            }
+        }
+    }
+    Ok(style)
+}
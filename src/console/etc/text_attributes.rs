@@ -0,0 +1,90 @@
+use super::{AttributeFlags, ConsoleColor};
+
+/**
+The attributes of a character cell: its colors, and the attribute flags which are not
+related to color.
+
+TextAttributes can be converted to and from the attribute word used by the Windows API,
+without losing any bits.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ConsoleColor, TextAttributes};
+# fn main() {
+let mut attributes = TextAttributes::new(ConsoleColor::Red, ConsoleColor::DarkBlue);
+attributes.flags.Underscore = true;
+assert_eq!(u16::from(attributes), 0x801C);
+assert_eq!(TextAttributes::from(0x801C), attributes);
+# }
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextAttributes {
+    /// The foreground color.
+    pub foreground: ConsoleColor,
+    /// The background color.
+    pub background: ConsoleColor,
+    /// Attribute flags which are not related to color.
+    pub flags: AttributeFlags,
+}
+
+impl TextAttributes {
+    /**
+    Creates a new TextAttributes object with no attribute flags set.
+
+    # Arguments
+    * `foreground` - The foreground color.
+    * `background` - The background color.
+    */
+    pub fn new(foreground: ConsoleColor, background: ConsoleColor) -> TextAttributes {
+        TextAttributes {
+            foreground,
+            background,
+            flags: AttributeFlags::default(),
+        }
+    }
+
+    /**
+    Returns the foreground and background colors as a tuple.
+    */
+    pub fn get_colors(&self) -> (ConsoleColor, ConsoleColor) {
+        (self.foreground, self.background)
+    }
+}
+
+impl Default for TextAttributes {
+    /**
+    Returns a TextAttributes object with a gray foreground, a black background
+    and no attribute flags set.
+    */
+    fn default() -> TextAttributes {
+        TextAttributes::new(ConsoleColor::Gray, ConsoleColor::Black)
+    }
+}
+
+impl From<(ConsoleColor, ConsoleColor)> for TextAttributes {
+    fn from(colors: (ConsoleColor, ConsoleColor)) -> TextAttributes {
+        TextAttributes::new(colors.0, colors.1)
+    }
+}
+
+impl From<u16> for TextAttributes {
+    fn from(value: u16) -> TextAttributes {
+        TextAttributes {
+            foreground: ConsoleColor::from(value & 0xF),
+            background: ConsoleColor::from((value & 0xF0) >> 4),
+            flags: AttributeFlags::from(value),
+        }
+    }
+}
+
+impl From<TextAttributes> for u16 {
+    fn from(attributes: TextAttributes) -> u16 {
+        let flags: u16 = attributes.flags.into();
+        let foreground: u16 = attributes.foreground.into();
+        let background: u16 = attributes.background.into();
+        flags | foreground | (background << 4)
+    }
+}
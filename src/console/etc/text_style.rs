@@ -0,0 +1,56 @@
+use super::{AttributeFlags, ConsoleColor, TextAttributes};
+
+/// The style of a span of text. Colors which are None are inherited from the attributes the
+/// style is applied to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextStyle {
+    /// The foreground color of the text.
+    pub foreground: Option<ConsoleColor>,
+    /// The background color of the text.
+    pub background: Option<ConsoleColor>,
+    /// Attribute flags which are set in addition to those of the inherited attributes.
+    pub flags: AttributeFlags,
+}
+
+impl TextStyle {
+    /**
+    Creates a new TextStyle which inherits everything.
+    */
+    pub fn new() -> TextStyle {
+        TextStyle::default()
+    }
+
+    /**
+    Returns the attributes which result from applying this style to other attributes.
+
+    # Arguments
+    * `base` - The attributes to apply the style to.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::console::{ConsoleColor, TextAttributes, TextStyle};
+    # fn main() {
+    let mut style = TextStyle::new();
+    style.foreground = Some(ConsoleColor::Red);
+    style.flags.Underscore = true;
+
+    let base = TextAttributes::new(ConsoleColor::Gray, ConsoleColor::Blue);
+    let attributes = style.apply(base);
+    assert_eq!(attributes.foreground, ConsoleColor::Red);
+    assert_eq!(attributes.background, ConsoleColor::Blue);
+    assert!(attributes.flags.Underscore);
+    # }
+    ```
+    */
+    pub fn apply(&self, base: TextAttributes) -> TextAttributes {
+        let base_flags: u16 = base.flags.into();
+        let flags: u16 = self.flags.into();
+        TextAttributes {
+            foreground: self.foreground.unwrap_or(base.foreground),
+            background: self.background.unwrap_or(base.background),
+            flags: AttributeFlags::from(base_flags | flags),
+        }
+    }
+}
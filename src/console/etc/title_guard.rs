@@ -0,0 +1,67 @@
+use console;
+use errors::WinResult;
+
+/**
+Changes the title of the console window, and restores the previous value when it is dropped,
+including while unwinding from a panic.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::TitleGuard;
+# fn main() {
+# #[cfg(not(windows))]
+# winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+let _guard = TitleGuard::new("Downloading...").unwrap();
+# }
+```
+*/
+#[derive(Debug)]
+pub struct TitleGuard {
+    previous: Option<String>,
+}
+
+impl TitleGuard {
+    /**
+    Changes the title of the console window, and returns a guard which restores the previous value.
+
+    # Arguments
+    * `title` - The title to apply.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn new(title: &str) -> WinResult<TitleGuard> {
+        let previous = console::get_title()?;
+        console::set_title(title)?;
+        Ok(TitleGuard {
+            previous: Some(previous),
+        })
+    }
+
+    /**
+    Restores the previous value, returning any error which occurs.
+    Dropping the guard also restores it, but ignores errors.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn restore(mut self) -> WinResult<()> {
+        match self.previous.take() {
+            Some(previous) => console::set_title(&previous),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for TitleGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            console::set_title(&previous).unwrap_or(())
+        }
+    }
+}
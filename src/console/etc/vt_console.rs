@@ -0,0 +1,411 @@
+use super::{
+    ansi_color, CodePage, Color, ColorMetric, ConsoleBackend, ConsoleFont, HistoryInfo,
+    InputSettings, MemoryConsole, OutputSettings, Rect, ScreenBufferInfo, SelectionInfo,
+    StandardStream, StreamKind, Vector2,
+};
+use errors::WinResult;
+use rgb::RGB8;
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const REVERSE_VIDEO: u16 = 0x4000;
+const UNDERSCORE: u16 = 0x8000;
+
+/**
+A console backend which emits virtual terminal sequences to a writer.
+
+Changes to colors, the cursor, the title and the contents of the screen buffer are written
+to the output as [VT sequences], so the `console` functions can drive any terminal which
+understands them (including Windows consoles with
+`OutputSettings::VirtualTerminalProcessing` enabled). Since a terminal cannot be queried,
+the state of the console is kept in a [`MemoryConsole`], which getter functions read from.
+The output is treated as displaying the window of that screen buffer.
+
+Colors set with `console::set_foreground` and `console::set_background` are written to the
+output exactly, but cells which are redrawn from the screen buffer use the nearest palette
+color, as the screen buffer only holds palette colors.
+
+Text written to the output by other means (such as `print!`) is not tracked. Clones of a
+`VtConsole` share the same output and state.
+
+# Examples
+Captures the sequences generated by console functions.
+
+```
+# extern crate winconsole;
+# use winconsole::console::{self, ConsoleColor, VtConsole};
+# fn main() {
+let vt = VtConsole::new(Vec::new(), 80, 25);
+console::set_backend(vt.clone());
+
+console::set_foreground_color(ConsoleColor::Red).unwrap();
+console::set_cursor_position(4, 2).unwrap();
+assert_eq!(&vt.get_output()[..], &b"\x1b[0;91;40m\x1b[3;5H"[..]);
+
+console::reset_backend();
+# }
+```
+
+[VT sequences]: https://docs.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences
+[`MemoryConsole`]: struct.MemoryConsole.html
+*/
+pub struct VtConsole<W: Write> {
+    output: Arc<Mutex<W>>,
+    screen: MemoryConsole,
+}
+
+impl<W: Write> VtConsole<W> {
+    /**
+    Creates a new VtConsole which writes to `output`.
+    The output is assumed to be a blank terminal of the given size.
+
+    # Arguments
+    * `output` - The writer which sequences are written to.
+    * `columns` - The amount of columns the terminal has.
+    * `rows` - The amount of rows the terminal has.
+
+    # Panics
+    Panics if `columns` or `rows` is zero.
+    */
+    pub fn new(output: W, columns: u16, rows: u16) -> VtConsole<W> {
+        VtConsole {
+            output: Arc::new(Mutex::new(output)),
+            screen: MemoryConsole::new(columns, rows),
+        }
+    }
+
+    /**
+    Returns the writer which sequences are written to.
+    The writer is locked until the returned guard is dropped.
+    */
+    pub fn get_output(&self) -> MutexGuard<'_, W> {
+        match self.output.lock() {
+            Ok(output) => output,
+            Err(err) => err.into_inner(),
+        }
+    }
+    /**
+    Returns the in-memory screen buffer which mirrors the terminal.
+    The returned MemoryConsole shares its state with this VtConsole.
+    */
+    pub fn get_screen(&self) -> MemoryConsole {
+        self.screen.clone()
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> WinResult<()> {
+        let mut output = self.get_output();
+        output.write_all(bytes)?;
+        output.flush()?;
+        Ok(())
+    }
+    fn render_range(&mut self, position: Vector2<u16>, length: u32) -> WinResult<()> {
+        let width = u32::from(self.screen.get_screen_buffer_info()?.buffer_size.x);
+        let start = u32::from(position.y) * width + u32::from(position.x);
+        let end = start + length;
+        if start == end {
+            return Ok(());
+        }
+
+        let top = (start / width) as u16;
+        let bottom = ((end - 1) / width) as u16;
+        let mut rows = Vec::new();
+        for y in top..=bottom {
+            let row_start = u32::from(y) * width;
+            let left = start.max(row_start) - row_start;
+            let right = end.min(row_start + width) - row_start - 1;
+            rows.push((y, left as u16, right as u16));
+        }
+        self.render_rows(&rows)
+    }
+    fn render_rect(&mut self, rect: Rect) -> WinResult<()> {
+        let rows: Vec<(u16, u16, u16)> = (rect.top..=rect.bottom)
+            .map(|y| (y, rect.left, rect.right))
+            .collect();
+        self.render_rows(&rows)
+    }
+    fn render_rows(&mut self, rows: &[(u16, u16, u16)]) -> WinResult<()> {
+        let window = self.screen.get_screen_buffer_info()?.window;
+        let width = window.right - window.left + 1;
+
+        let mut cells = Vec::new();
+        let mut covered = 0;
+        for &(y, left, right) in rows {
+            if y < window.top || y > window.bottom {
+                continue;
+            }
+            let left = left.max(window.left);
+            let right = right.min(window.right);
+            if left > right {
+                continue;
+            }
+
+            let position = Vector2::new(left, y);
+            let length = u32::from(right - left + 1);
+            let chars = self.screen.read_characters(position, length)?;
+            let attrs = self.screen.read_attributes(position, length)?;
+            if right - left + 1 == width {
+                covered += 1;
+            }
+            cells.push((position, chars, attrs));
+        }
+
+        let mut bytes = b"\x1b7".to_vec();
+        let rows = window.bottom - window.top + 1;
+        let blank = cells
+            .iter()
+            .all(|(_, chars, _)| chars.chars().all(|c| c == ' '));
+        let attributes = cells
+            .first()
+            .and_then(|(_, _, attrs)| attrs.first().cloned());
+        let uniform = cells
+            .iter()
+            .all(|(_, _, attrs)| attrs.iter().all(|a| Some(*a) == attributes));
+
+        match attributes {
+            None => return Ok(()),
+            Some(attributes) if covered == rows && blank && uniform => {
+                write_sgr(&mut bytes, attributes);
+                bytes.extend_from_slice(b"\x1b[2J");
+            }
+            Some(_) => {
+                let mut current = None;
+                for (position, chars, attrs) in cells {
+                    let column = position.x - window.left + 1;
+                    let row = position.y - window.top + 1;
+                    bytes.extend_from_slice(format!("\x1b[{};{}H", row, column).as_bytes());
+
+                    for (chr, attrs) in chars.chars().zip(attrs) {
+                        if current != Some(attrs) {
+                            write_sgr(&mut bytes, attrs);
+                            current = Some(attrs);
+                        }
+                        let mut buffer = [0; 4];
+                        bytes.extend_from_slice(chr.encode_utf8(&mut buffer).as_bytes());
+                    }
+                }
+            }
+        }
+
+        bytes.extend_from_slice(b"\x1b8");
+        self.emit(&bytes)
+    }
+    fn render_window(&mut self) -> WinResult<()> {
+        let window = self.screen.get_screen_buffer_info()?.window;
+        self.render_rect(window)
+    }
+}
+
+impl<W: Write> Clone for VtConsole<W> {
+    fn clone(&self) -> VtConsole<W> {
+        VtConsole {
+            output: self.output.clone(),
+            screen: self.screen.clone(),
+        }
+    }
+}
+
+impl<W: Write> ConsoleBackend for VtConsole<W> {
+    fn fill_attributes(
+        &mut self,
+        attributes: u16,
+        length: u32,
+        position: Vector2<u16>,
+    ) -> WinResult<u32> {
+        let num = self.screen.fill_attributes(attributes, length, position)?;
+        self.render_range(position, num)?;
+        Ok(num)
+    }
+    fn fill_character(&mut self, chr: char, length: u32, position: Vector2<u16>) -> WinResult<u32> {
+        let num = self.screen.fill_character(chr, length, position)?;
+        self.render_range(position, num)?;
+        Ok(num)
+    }
+    fn flush_input(&mut self) -> WinResult<()> {
+        self.screen.flush_input()
+    }
+    fn get_color_mapping(&mut self) -> WinResult<[RGB8; 16]> {
+        self.screen.get_color_mapping()
+    }
+    fn get_cursor_size(&mut self) -> WinResult<u8> {
+        self.screen.get_cursor_size()
+    }
+    fn get_font(&mut self) -> WinResult<ConsoleFont> {
+        self.screen.get_font()
+    }
+    fn get_history_info(&mut self) -> WinResult<HistoryInfo> {
+        self.screen.get_history_info()
+    }
+    fn get_input_code_page(&mut self) -> WinResult<CodePage> {
+        self.screen.get_input_code_page()
+    }
+    fn get_input_mode(&mut self) -> WinResult<InputSettings> {
+        self.screen.get_input_mode()
+    }
+    fn get_largest_window_size(&mut self) -> WinResult<Vector2<u16>> {
+        self.screen.get_largest_window_size()
+    }
+    fn get_original_title(&mut self) -> WinResult<String> {
+        self.screen.get_original_title()
+    }
+    fn get_output_code_page(&mut self) -> WinResult<CodePage> {
+        self.screen.get_output_code_page()
+    }
+    fn get_output_mode(&mut self) -> WinResult<OutputSettings> {
+        self.screen.get_output_mode()
+    }
+    fn get_screen_buffer_info(&mut self) -> WinResult<ScreenBufferInfo> {
+        self.screen.get_screen_buffer_info()
+    }
+    fn get_selection_info(&mut self) -> WinResult<SelectionInfo> {
+        self.screen.get_selection_info()
+    }
+    fn get_stream_kind(&mut self, stream: StandardStream) -> StreamKind {
+        self.screen.get_stream_kind(stream)
+    }
+    fn get_title(&mut self) -> WinResult<String> {
+        self.screen.get_title()
+    }
+    fn is_cursor_visible(&mut self) -> WinResult<bool> {
+        self.screen.is_cursor_visible()
+    }
+    fn read_attributes(&mut self, position: Vector2<u16>, length: u32) -> WinResult<Vec<u16>> {
+        self.screen.read_attributes(position, length)
+    }
+    fn read_characters(&mut self, position: Vector2<u16>, length: u32) -> WinResult<String> {
+        self.screen.read_characters(position, length)
+    }
+    fn scroll_contents(
+        &mut self,
+        scroll: Rect,
+        clip: Option<Rect>,
+        dest: Vector2<i16>,
+        fill_char: char,
+        fill_attributes: u16,
+    ) -> WinResult<()> {
+        self.screen
+            .scroll_contents(scroll, clip, dest, fill_char, fill_attributes)?;
+
+        let size = self.screen.get_screen_buffer_info()?.buffer_size;
+        let height = i32::from(scroll.bottom - scroll.top);
+        let top = i32::from(scroll.top).min(i32::from(dest.y)).max(0);
+        let bottom = i32::from(scroll.bottom)
+            .max(i32::from(dest.y) + height)
+            .min(i32::from(size.y) - 1);
+        if top <= bottom {
+            self.render_rect(Rect::new(top as u16, 0, size.x - 1, bottom as u16))?;
+        }
+        Ok(())
+    }
+    fn set_buffer_size(&mut self, size: Vector2<u16>) -> WinResult<()> {
+        self.screen.set_buffer_size(size)
+    }
+    fn set_color(&mut self, color: Color, background: bool, metric: ColorMetric) -> WinResult<()> {
+        self.screen.set_color(color, background, metric)?;
+        self.emit(color.get_vt_sequence(background).as_bytes())
+    }
+    fn set_color_mapping(&mut self, mapping: &[RGB8; 16]) -> WinResult<()> {
+        let old = self.screen.get_color_mapping()?;
+        self.screen.set_color_mapping(mapping)?;
+
+        let mut bytes = Vec::new();
+        for (i, (old, new)) in old.iter().zip(mapping.iter()).enumerate() {
+            if old != new {
+                let index = ansi_color(i as u16);
+                let osc = format!(
+                    "\x1b]4;{};rgb:{:02x}/{:02x}/{:02x}\x07",
+                    index, new.r, new.g, new.b
+                );
+                bytes.extend_from_slice(osc.as_bytes());
+            }
+        }
+        self.emit(&bytes)
+    }
+    fn set_cursor_position(&mut self, position: Vector2<u16>) -> WinResult<()> {
+        let old_window = self.screen.get_screen_buffer_info()?.window;
+        self.screen.set_cursor_position(position)?;
+
+        let window = self.screen.get_screen_buffer_info()?.window;
+        if window != old_window {
+            self.render_window()?;
+        }
+
+        let row = position.y - window.top + 1;
+        let column = position.x - window.left + 1;
+        self.emit(format!("\x1b[{};{}H", row, column).as_bytes())
+    }
+    fn set_cursor_size(&mut self, size: u8) -> WinResult<()> {
+        self.screen.set_cursor_size(size)
+    }
+    fn set_cursor_visible(&mut self, visible: bool) -> WinResult<()> {
+        self.screen.set_cursor_visible(visible)?;
+        if visible {
+            self.emit(b"\x1b[?25h")
+        } else {
+            self.emit(b"\x1b[?25l")
+        }
+    }
+    fn set_font(&mut self, font: &ConsoleFont) -> WinResult<()> {
+        self.screen.set_font(font)
+    }
+    fn set_history_info(&mut self, history: HistoryInfo) -> WinResult<()> {
+        self.screen.set_history_info(history)
+    }
+    fn set_input_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        self.screen.set_input_code_page(page)
+    }
+    fn set_input_mode(&mut self, settings: InputSettings) -> WinResult<()> {
+        self.screen.set_input_mode(settings)
+    }
+    fn set_output_code_page(&mut self, page: CodePage) -> WinResult<()> {
+        self.screen.set_output_code_page(page)
+    }
+    fn set_output_mode(&mut self, settings: OutputSettings) -> WinResult<()> {
+        self.screen.set_output_mode(settings)
+    }
+    fn set_text_attributes(&mut self, attributes: u16) -> WinResult<()> {
+        self.screen.set_text_attributes(attributes)?;
+        let mut bytes = Vec::new();
+        write_sgr(&mut bytes, attributes);
+        self.emit(&bytes)
+    }
+    fn set_title(&mut self, title: &str) -> WinResult<()> {
+        self.screen.set_title(title)?;
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        self.emit(format!("\x1b]2;{}\x07", title).as_bytes())
+    }
+    fn set_window_rect(&mut self, rect: Rect) -> WinResult<()> {
+        let old_window = self.screen.get_screen_buffer_info()?.window;
+        self.screen.set_window_rect(rect)?;
+        if rect != old_window {
+            self.render_window()?;
+        }
+        Ok(())
+    }
+    fn write_attributes(&mut self, attributes: &[u16], position: Vector2<u16>) -> WinResult<u32> {
+        let num = self.screen.write_attributes(attributes, position)?;
+        self.render_range(position, num)?;
+        Ok(num)
+    }
+    fn write_characters(&mut self, string: &str, position: Vector2<u16>) -> WinResult<u32> {
+        let num = self.screen.write_characters(string, position)?;
+        self.render_range(position, num)?;
+        Ok(num)
+    }
+}
+
+fn write_sgr(bytes: &mut Vec<u8>, attributes: u16) {
+    let fg = ansi_color(attributes & 0xF);
+    let bg = ansi_color((attributes & 0xF0) >> 4);
+    let fg = if fg < 8 { 30 + fg } else { 82 + fg };
+    let bg = if bg < 8 { 40 + bg } else { 92 + bg };
+
+    let mut sgr = format!("\x1b[0;{};{}", fg, bg);
+    if attributes & UNDERSCORE != 0 {
+        sgr.push_str(";4");
+    }
+    if attributes & REVERSE_VIDEO != 0 {
+        sgr.push_str(";7");
+    }
+    sgr.push('m');
+    bytes.extend_from_slice(sgr.as_bytes());
+}
@@ -0,0 +1,673 @@
+use super::{
+    ansi_color, default_color_mapping, Cell, CellBuffer, Color, ColorMetric, ConsoleColor, Rect,
+    TextAttributes, Vector2,
+};
+use rgb::RGB8;
+use std::io::{self, Write};
+use std::str;
+
+// Limits the memory used by malformed or hostile sequences.
+const MAX_OSC_LENGTH: usize = 4096;
+const MAX_PARAMS: usize = 32;
+const TAB_WIDTH: u16 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Ground,
+    Escape,
+    // An escape sequence with intermediate bytes, such as a character set designation.
+    EscapeIntermediate,
+    Csi,
+    Osc,
+    OscEscape,
+    // A device control string or another string which is ignored.
+    Ignored,
+    IgnoredEscape,
+}
+
+/**
+Parses the output of a program which uses [VT sequences], and maintains the grid of cells
+which a terminal would display.
+
+Bytes can be fed in chunks of any size, as sequences and UTF-8 characters which are split
+between chunks are buffered. Cursor movement, erasing, inserting and deleting characters and
+lines, scroll regions, the window title and SGR colors are supported; 256-color and RGB colors
+are mapped to the nearest palette color. Unsupported sequences are ignored. Every character is
+assumed to occupy a single cell.
+
+The parser is written in pure Rust, so recorded output can be replayed on any platform.
+A VtParser also implements `io::Write`, so output can be copied into it.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::console::{ConsoleColor, Vector2, VtParser};
+# fn main() {
+let mut parser = VtParser::new(20, 3);
+parser.feed(b"\x1b]0;build\x07Compiling...\r\n\x1b[91merror\x1b[0m: failed");
+
+assert_eq!(parser.lines(), vec!["Compiling...", "error: failed", ""]);
+assert_eq!(parser.get_title(), "build");
+assert_eq!(parser.get_cursor_position(), Vector2::new(13, 1));
+let cell = parser.get_screen().get(0, 1).unwrap();
+assert_eq!(cell.foreground, ConsoleColor::Red);
+# }
+```
+
+[VT sequences]: https://docs.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences
+*/
+#[derive(Clone, Debug)]
+pub struct VtParser {
+    attributes: TextAttributes,
+    bold: bool,
+    color_mapping: [RGB8; 16],
+    cursor: Vector2<u16>,
+    cursor_visible: bool,
+    intermediate: Option<u8>,
+    osc: Vec<u8>,
+    param: Option<u16>,
+    params: Vec<u16>,
+    pending_wrap: bool,
+    prefix: Option<u8>,
+    saved: (Vector2<u16>, TextAttributes, bool),
+    screen: CellBuffer,
+    scroll_region: (u16, u16),
+    state: State,
+    title: String,
+    utf8: Vec<u8>,
+}
+
+impl VtParser {
+    /**
+    Creates a new VtParser with a blank screen.
+
+    # Arguments
+    * `columns` - The amount of columns the screen has.
+    * `rows` - The amount of rows the screen has.
+
+    # Panics
+    Panics if `columns` or `rows` is zero.
+    */
+    pub fn new(columns: u16, rows: u16) -> VtParser {
+        assert!(columns > 0 && rows > 0, "screen size must be non-zero");
+        VtParser {
+            attributes: TextAttributes::default(),
+            bold: false,
+            color_mapping: default_color_mapping(),
+            cursor: Vector2::new(0, 0),
+            cursor_visible: true,
+            intermediate: None,
+            osc: Vec::new(),
+            param: None,
+            params: Vec::new(),
+            pending_wrap: false,
+            prefix: None,
+            saved: (Vector2::new(0, 0), TextAttributes::default(), false),
+            screen: CellBuffer::new(columns, rows),
+            scroll_region: (0, rows - 1),
+            state: State::Ground,
+            title: String::new(),
+            utf8: Vec::new(),
+        }
+    }
+
+    /**
+    Parses bytes of output, updating the screen.
+
+    # Arguments
+    * `bytes` - The bytes to parse.
+    */
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.advance(byte);
+        }
+    }
+    /**
+    Returns the color mapping used to find the nearest palette color to 256-color and RGB colors.
+    */
+    pub fn get_color_mapping(&self) -> [RGB8; 16] {
+        self.color_mapping
+    }
+    /**
+    Returns the position of the cursor.
+    */
+    pub fn get_cursor_position(&self) -> Vector2<u16> {
+        self.cursor
+    }
+    /**
+    Returns the cells of the screen.
+    */
+    pub fn get_screen(&self) -> &CellBuffer {
+        &self.screen
+    }
+    /**
+    Returns the text attributes which are applied to subsequently printed characters.
+    */
+    pub fn get_text_attributes(&self) -> TextAttributes {
+        let mut attributes = self.attributes;
+        if self.bold {
+            attributes.foreground = intense(attributes.foreground);
+        }
+        attributes
+    }
+    /**
+    Returns the window title which was last set by the output.
+    */
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+    /**
+    Returns whether or not the cursor is visible.
+    */
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+    /**
+    Returns the characters of each row of the screen, with trailing whitespace removed.
+    */
+    pub fn lines(&self) -> Vec<String> {
+        self.screen.lines()
+    }
+    /**
+    Resets the parser to its initial state, keeping the screen size and color mapping.
+    */
+    pub fn reset(&mut self) {
+        let size = self.screen.get_size();
+        let mapping = self.color_mapping;
+        *self = VtParser::new(size.x, size.y);
+        self.color_mapping = mapping;
+    }
+    /**
+    Changes the size of the screen. Cells which are within both the old and new sizes are kept,
+    and the scroll region is reset.
+
+    # Arguments
+    * `columns` - The amount of columns the screen should have.
+    * `rows` - The amount of rows the screen should have.
+
+    # Panics
+    Panics if `columns` or `rows` is zero.
+    */
+    pub fn resize(&mut self, columns: u16, rows: u16) {
+        assert!(columns > 0 && rows > 0, "screen size must be non-zero");
+        let old = self.screen.get_size();
+        let mut screen = CellBuffer::new(columns, rows);
+        screen.blit(
+            &self.screen,
+            Rect::new(0, 0, old.x - 1, old.y - 1),
+            Vector2::new(0, 0),
+        );
+
+        self.screen = screen;
+        self.scroll_region = (0, rows - 1);
+        self.cursor = Vector2::new(self.cursor.x.min(columns - 1), self.cursor.y.min(rows - 1));
+        self.pending_wrap = false;
+    }
+    /**
+    Sets the color mapping used to find the nearest palette color to 256-color and RGB colors.
+
+    # Arguments
+    * `mapping` - The RGB values of the 16 palette colors.
+    */
+    pub fn set_color_mapping(&mut self, mapping: &[RGB8; 16]) {
+        self.color_mapping = *mapping;
+    }
+
+    fn advance(&mut self, byte: u8) {
+        match self.state {
+            State::Ground => self.ground(byte),
+            State::Escape => self.escape(byte),
+            State::EscapeIntermediate => {
+                // The final byte of the sequence ends it; nothing is applied.
+                if (0x30..=0x7E).contains(&byte) {
+                    self.state = State::Ground;
+                } else if byte == 0x1B {
+                    self.state = State::Escape;
+                }
+            }
+            State::Csi => self.csi(byte),
+            State::Osc => match byte {
+                0x07 => self.dispatch_osc(),
+                0x1B => self.state = State::OscEscape,
+                _ if self.osc.len() < MAX_OSC_LENGTH => self.osc.push(byte),
+                _ => {}
+            },
+            State::OscEscape => {
+                self.dispatch_osc();
+                if byte != b'\\' {
+                    self.escape(byte);
+                }
+            }
+            State::Ignored => match byte {
+                0x07 => self.state = State::Ground,
+                0x1B => self.state = State::IgnoredEscape,
+                _ => {}
+            },
+            State::IgnoredEscape => {
+                self.state = State::Ground;
+                if byte != b'\\' {
+                    self.escape(byte);
+                }
+            }
+        }
+    }
+    fn blank(&self) -> Cell {
+        let attributes =
+            TextAttributes::new(self.attributes.foreground, self.attributes.background);
+        Cell::with_text_attributes(' ', attributes)
+    }
+    fn csi(&mut self, byte: u8) {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = u16::from(byte - b'0');
+                let value = self.param.unwrap_or(0);
+                self.param = Some(value.saturating_mul(10).saturating_add(digit));
+            }
+            b';' | b':' => {
+                let value = self.param.take().unwrap_or(0);
+                if self.params.len() < MAX_PARAMS {
+                    self.params.push(value);
+                }
+            }
+            0x3C..=0x3F => self.prefix = Some(byte),
+            0x20..=0x2F => self.intermediate = Some(byte),
+            0x40..=0x7E => {
+                if let Some(value) = self.param.take() {
+                    if self.params.len() < MAX_PARAMS {
+                        self.params.push(value);
+                    }
+                }
+                self.state = State::Ground;
+                self.dispatch_csi(byte);
+            }
+            0x1B => self.state = State::Escape,
+            0x18 | 0x1A => self.state = State::Ground,
+            0x00..=0x1F => self.execute(byte),
+            _ => {}
+        }
+    }
+    fn dispatch_csi(&mut self, action: u8) {
+        if self.intermediate.is_some() {
+            return;
+        }
+        if self.prefix.is_some() {
+            let private = self.prefix == Some(b'?') && (action == b'h' || action == b'l');
+            if private && self.params.contains(&25) {
+                self.cursor_visible = action == b'h';
+            }
+            return;
+        }
+
+        let size = self.screen.get_size();
+        let (top, bottom) = self.scroll_region;
+        let count = self.param(0, 1);
+        self.pending_wrap = false;
+        match action {
+            b'A' => self.cursor.y = self.cursor.y.saturating_sub(count),
+            b'B' | b'e' => self.cursor.y = (self.cursor.y.saturating_add(count)).min(size.y - 1),
+            b'C' | b'a' => self.cursor.x = (self.cursor.x.saturating_add(count)).min(size.x - 1),
+            b'D' => self.cursor.x = self.cursor.x.saturating_sub(count),
+            b'E' => {
+                self.cursor.y = (self.cursor.y.saturating_add(count)).min(size.y - 1);
+                self.cursor.x = 0;
+            }
+            b'F' => {
+                self.cursor.y = self.cursor.y.saturating_sub(count);
+                self.cursor.x = 0;
+            }
+            b'G' | b'`' => self.cursor.x = (count - 1).min(size.x - 1),
+            b'd' => self.cursor.y = (count - 1).min(size.y - 1),
+            b'H' | b'f' => {
+                self.cursor.y = (count - 1).min(size.y - 1);
+                self.cursor.x = (self.param(1, 1) - 1).min(size.x - 1);
+            }
+            b'J' => {
+                let (x, y) = (self.cursor.x, self.cursor.y);
+                let last = size.x - 1;
+                match self.param(0, 0) {
+                    0 => {
+                        self.erase(Rect::new(y, x, last, y));
+                        if y + 1 < size.y {
+                            self.erase(Rect::new(y + 1, 0, last, size.y - 1));
+                        }
+                    }
+                    1 => {
+                        if y > 0 {
+                            self.erase(Rect::new(0, 0, last, y - 1));
+                        }
+                        self.erase(Rect::new(y, 0, x, y));
+                    }
+                    _ => self.erase(Rect::new(0, 0, last, size.y - 1)),
+                }
+            }
+            b'K' => {
+                let (x, y) = (self.cursor.x, self.cursor.y);
+                match self.param(0, 0) {
+                    0 => self.erase(Rect::new(y, x, size.x - 1, y)),
+                    1 => self.erase(Rect::new(y, 0, x, y)),
+                    _ => self.erase(Rect::new(y, 0, size.x - 1, y)),
+                }
+            }
+            b'X' => {
+                let (x, y) = (self.cursor.x, self.cursor.y);
+                let right = (x.saturating_add(count) - 1).min(size.x - 1);
+                self.erase(Rect::new(y, x, right, y));
+            }
+            b'@' => self.shift_row(count, true),
+            b'P' => self.shift_row(count, false),
+            b'L' if self.cursor.y >= top && self.cursor.y <= bottom => {
+                let y = self.cursor.y;
+                self.scroll(y, bottom, count, false);
+                self.cursor.x = 0;
+            }
+            b'M' if self.cursor.y >= top && self.cursor.y <= bottom => {
+                let y = self.cursor.y;
+                self.scroll(y, bottom, count, true);
+                self.cursor.x = 0;
+            }
+            b'S' => self.scroll(top, bottom, count, true),
+            b'T' => self.scroll(top, bottom, count, false),
+            b'm' => self.select_graphic_rendition(),
+            b'r' => {
+                let new_top = self.param(0, 1) - 1;
+                let new_bottom = (self.param(1, size.y) - 1).min(size.y - 1);
+                if new_top < new_bottom {
+                    self.scroll_region = (new_top, new_bottom);
+                    self.cursor = Vector2::new(0, 0);
+                }
+            }
+            b's' => self.saved = (self.cursor, self.attributes, self.bold),
+            b'u' => self.restore_cursor(),
+            _ => {}
+        }
+    }
+    fn dispatch_osc(&mut self) {
+        self.state = State::Ground;
+        let osc = String::from_utf8_lossy(&self.osc).into_owned();
+        self.osc.clear();
+
+        let mut parts = osc.splitn(2, ';');
+        if let (Some(command), Some(text)) = (parts.next(), parts.next()) {
+            if command == "0" || command == "2" {
+                self.title = text.to_string();
+            }
+        }
+    }
+    fn erase(&mut self, rect: Rect) {
+        let blank = self.blank();
+        self.screen.fill(rect, blank);
+    }
+    fn escape(&mut self, byte: u8) {
+        self.state = State::Ground;
+        match byte {
+            b'[' => {
+                self.intermediate = None;
+                self.param = None;
+                self.params.clear();
+                self.prefix = None;
+                self.state = State::Csi;
+            }
+            b']' => {
+                self.osc.clear();
+                self.state = State::Osc;
+            }
+            b'P' | b'X' | b'^' | b'_' => self.state = State::Ignored,
+            0x20..=0x2F => self.state = State::EscapeIntermediate,
+            0x1B => self.state = State::Escape,
+            b'7' => self.saved = (self.cursor, self.attributes, self.bold),
+            b'8' => self.restore_cursor(),
+            b'D' => self.line_feed(),
+            b'E' => {
+                self.cursor.x = 0;
+                self.line_feed();
+            }
+            b'M' => self.reverse_index(),
+            b'c' => self.reset(),
+            _ => {}
+        }
+    }
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x08 => {
+                self.cursor.x = self.cursor.x.saturating_sub(1);
+                self.pending_wrap = false;
+            }
+            0x09 => {
+                let last = self.screen.get_size().x - 1;
+                let next = (self.cursor.x / TAB_WIDTH + 1).saturating_mul(TAB_WIDTH);
+                self.cursor.x = next.min(last);
+            }
+            0x0A..=0x0C => self.line_feed(),
+            0x0D => {
+                self.cursor.x = 0;
+                self.pending_wrap = false;
+            }
+            0x1B => self.state = State::Escape,
+            _ => {}
+        }
+    }
+    fn ground(&mut self, byte: u8) {
+        if byte < 0x80 {
+            if !self.utf8.is_empty() {
+                self.utf8.clear();
+                self.print(char::REPLACEMENT_CHARACTER);
+            }
+            match byte {
+                0x00..=0x1F | 0x7F => self.execute(byte),
+                _ => self.print(char::from(byte)),
+            }
+            return;
+        }
+
+        if byte & 0xC0 == 0x80 {
+            if self.utf8.is_empty() {
+                self.print(char::REPLACEMENT_CHARACTER);
+                return;
+            }
+            self.utf8.push(byte);
+        } else {
+            if !self.utf8.is_empty() {
+                self.utf8.clear();
+                self.print(char::REPLACEMENT_CHARACTER);
+            }
+            if !(0xC2..=0xF4).contains(&byte) {
+                self.print(char::REPLACEMENT_CHARACTER);
+                return;
+            }
+            self.utf8.push(byte);
+        }
+
+        let expected = match self.utf8[0] {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => 4,
+        };
+        if self.utf8.len() == expected {
+            let chr = str::from_utf8(&self.utf8)
+                .ok()
+                .and_then(|s| s.chars().next())
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            self.utf8.clear();
+            self.print(chr);
+        }
+    }
+    fn line_feed(&mut self) {
+        self.pending_wrap = false;
+        let (top, bottom) = self.scroll_region;
+        if self.cursor.y == bottom {
+            self.scroll(top, bottom, 1, true);
+        } else if self.cursor.y + 1 < self.screen.get_size().y {
+            self.cursor.y += 1;
+        }
+    }
+    fn param(&self, index: usize, default: u16) -> u16 {
+        match self.params.get(index) {
+            Some(&value) if value != 0 => value,
+            _ => default,
+        }
+    }
+    fn print(&mut self, chr: char) {
+        if self.pending_wrap {
+            self.cursor.x = 0;
+            self.line_feed();
+        }
+
+        let cell = Cell::with_text_attributes(chr, self.get_text_attributes());
+        if let Some(value) = self.screen.get_mut(self.cursor.x, self.cursor.y) {
+            *value = cell;
+        }
+        if self.cursor.x + 1 >= self.screen.get_size().x {
+            self.pending_wrap = true;
+        } else {
+            self.cursor.x += 1;
+        }
+    }
+    fn restore_cursor(&mut self) {
+        let size = self.screen.get_size();
+        let (cursor, attributes, bold) = self.saved;
+        self.cursor = Vector2::new(cursor.x.min(size.x - 1), cursor.y.min(size.y - 1));
+        self.attributes = attributes;
+        self.bold = bold;
+        self.pending_wrap = false;
+    }
+    fn reverse_index(&mut self) {
+        self.pending_wrap = false;
+        let (top, bottom) = self.scroll_region;
+        if self.cursor.y == top {
+            self.scroll(top, bottom, 1, false);
+        } else if self.cursor.y > 0 {
+            self.cursor.y -= 1;
+        }
+    }
+    // Moves the rows from `top` to `bottom` up (or down) by `count`, blanking the rows left behind.
+    fn scroll(&mut self, top: u16, bottom: u16, count: u16, up: bool) {
+        let last = self.screen.get_size().x - 1;
+        let count = count.min(bottom - top + 1);
+        if count <= bottom - top {
+            let (source, dest) = if up {
+                (
+                    Rect::new(top + count, 0, last, bottom),
+                    Vector2::new(0, top),
+                )
+            } else {
+                (
+                    Rect::new(top, 0, last, bottom - count),
+                    Vector2::new(0, top + count),
+                )
+            };
+            let rows = self.screen.slice(source);
+            let rect = Rect::new(0, 0, last, bottom - top - count);
+            self.screen.blit(&rows, rect, dest);
+        }
+
+        if up {
+            self.erase(Rect::new(bottom + 1 - count, 0, last, bottom));
+        } else {
+            self.erase(Rect::new(top, 0, last, top + count - 1));
+        }
+    }
+    fn select_graphic_rendition(&mut self) {
+        if self.params.is_empty() {
+            self.params.push(0);
+        }
+
+        let mut i = 0;
+        while i < self.params.len() {
+            let value = self.params[i];
+            match value {
+                0 => {
+                    self.attributes = TextAttributes::default();
+                    self.bold = false;
+                }
+                // Bold is kept apart from the foreground, so that it also applies to
+                // colors which are set after it.
+                1 => self.bold = true,
+                22 => self.bold = false,
+                4 => self.attributes.flags.Underscore = true,
+                24 => self.attributes.flags.Underscore = false,
+                7 => self.attributes.flags.ReverseVideo = true,
+                27 => self.attributes.flags.ReverseVideo = false,
+                30..=37 => self.attributes.foreground = palette_color(value - 30),
+                90..=97 => self.attributes.foreground = palette_color(value - 90 + 8),
+                39 => self.attributes.foreground = TextAttributes::default().foreground,
+                40..=47 => self.attributes.background = palette_color(value - 40),
+                100..=107 => self.attributes.background = palette_color(value - 100 + 8),
+                49 => self.attributes.background = TextAttributes::default().background,
+                38 | 48 => {
+                    let color = match self.params.get(i + 1) {
+                        Some(&5) => {
+                            let index = self.params.get(i + 2).copied();
+                            i += 2;
+                            index.map(|index| Color::Indexed(index.min(255) as u8))
+                        }
+                        Some(&2) => {
+                            let component = |offset: usize| {
+                                self.params.get(i + offset).map(|&c| c.min(255) as u8)
+                            };
+                            let rgb = match (component(2), component(3), component(4)) {
+                                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(RGB8 { r, g, b })),
+                                _ => None,
+                            };
+                            i += 4;
+                            rgb
+                        }
+                        _ => None,
+                    };
+                    if let Some(color) = color {
+                        let color =
+                            color.to_console_color(&self.color_mapping, ColorMetric::default());
+                        if value == 38 {
+                            self.attributes.foreground = color;
+                        } else {
+                            self.attributes.background = color;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+    // Inserts (or deletes) blank cells at the cursor, shifting the rest of the row.
+    fn shift_row(&mut self, count: u16, insert: bool) {
+        let width = self.screen.get_size().x;
+        let (x, y) = (self.cursor.x, self.cursor.y);
+        let count = count.min(width - x);
+        let last = width - 1;
+        if count < width - x {
+            let (source, dest) = if insert {
+                (Rect::new(y, x, last - count, y), Vector2::new(x + count, y))
+            } else {
+                (Rect::new(y, x + count, last, y), Vector2::new(x, y))
+            };
+            let cells = self.screen.slice(source);
+            let size = cells.get_size();
+            self.screen
+                .blit(&cells, Rect::new(0, 0, size.x - 1, size.y - 1), dest);
+        }
+
+        if insert {
+            self.erase(Rect::new(y, x, x + count - 1, y));
+        } else {
+            self.erase(Rect::new(y, width - count, last, y));
+        }
+    }
+}
+
+impl Write for VtParser {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.feed(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Returns the bright variant of a dark color, which is how bold text is shown.
+fn intense(color: ConsoleColor) -> ConsoleColor {
+    ConsoleColor::from(u16::from(color.get_value() | 0x8))
+}
+fn palette_color(index: u16) -> ConsoleColor {
+    ConsoleColor::from(ansi_color(index))
+}
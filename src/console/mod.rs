@@ -1,29 +1,46 @@
 use rgb::RGB8;
 
-use std::{io, mem, ptr};
+use std::io;
+#[cfg(windows)]
+use std::{mem, ptr};
 
-use winapi::ctypes::c_void as VOID;
-use winapi::shared::minwindef::{DWORD, MAX_PATH, UINT, WORD};
-use winapi::um::winbase::{STD_INPUT_HANDLE as STDIN, STD_OUTPUT_HANDLE as STDOUT};
+#[cfg(windows)]
+use winapi::shared::minwindef::{BOOL, DWORD, MAX_PATH, UINT, WORD};
+#[cfg(windows)]
+use winapi::um::winbase::{
+    CREATE_NEW_PROCESS_GROUP, STD_ERROR_HANDLE as STDERR, STD_INPUT_HANDLE as STDIN,
+    STD_OUTPUT_HANDLE as STDOUT,
+};
+#[cfg(windows)]
 use winapi::um::wincon::{
-    CHAR_INFO_Char, CHAR_INFO, CONSOLE_CURSOR_INFO, CONSOLE_FONT_INFOEX, CONSOLE_HISTORY_INFO,
-    CONSOLE_READCONSOLE_CONTROL, CONSOLE_SCREEN_BUFFER_INFO, CONSOLE_SCREEN_BUFFER_INFOEX,
+    ATTACH_PARENT_PROCESS, CHAR_INFO, CONSOLE_CURSOR_INFO, CONSOLE_FONT_INFOEX,
+    CONSOLE_HISTORY_INFO, CONSOLE_SCREEN_BUFFER_INFO, CONSOLE_SCREEN_BUFFER_INFOEX,
     CONSOLE_SELECTION_INFO, COORD, SMALL_RECT,
 };
-use winapi::um::winnls::CPINFOEXA;
-use winapi::um::winnt::{CHAR, WCHAR};
+#[cfg(windows)]
+use winapi::um::winnls::CPINFOEXW;
+#[cfg(windows)]
+use winapi::um::winnt::{HANDLE, WCHAR};
+#[cfg(windows)]
 use winapi::um::{consoleapi, processenv, utilapiset, wincon, winnls};
 
 use super::errors::*;
+#[cfg(windows)]
+use super::utf16;
 
+#[cfg(windows)]
 type HandlerRoutine = unsafe extern "system" fn(_: u32) -> i32;
 
-#[cfg(feature = "input")]
+#[cfg(all(windows, feature = "input"))]
 mod console_input;
 mod console_main;
+#[cfg(windows)]
+mod console_winapi;
 mod etc;
 
-#[cfg(feature = "input")]
+#[cfg(all(windows, feature = "input"))]
 pub(crate) use self::console_input::*;
 pub use self::console_main::*;
+#[cfg(windows)]
+use self::console_winapi::WinApiConsole;
 pub use self::etc::*;
@@ -19,6 +19,7 @@ macro_rules! win_errs {
 		}
 
 		impl Error for WinError {
+			#[allow(deprecated)]
 			fn description(&self) -> &str {
 				match *self {
 					$(
@@ -1,5 +1,10 @@
 use super::*;
 use input;
+use std::time::{Duration, Instant};
+use utf16::Utf16Decoder;
+use winapi::um::synchapi;
+use winapi::um::winbase::{INFINITE, WAIT_FAILED};
+use winapi::um::winnt::HANDLE;
 
 /// Used to obtain input events.
 pub struct InputContext {
@@ -10,9 +15,12 @@ pub struct InputContext {
 
     pub(crate) button_status: [bool; 5],
     pub(crate) held_keys: Vec<KeyCode>,
+    pub(crate) key_decoders: [Utf16Decoder; 2],
+    pub(crate) vt_parser: Option<VtInputParser>,
 
     filter: InputFilter,
     filter_value: u16,
+    input: ConsoleInput,
     original_mode: InputSettings,
     queue: Vec<InputEvent>,
 }
@@ -83,6 +91,13 @@ impl InputContext {
         self.filter
     }
     /**
+    Returns whether or not input is read as VT sequences, which are decoded into input events.
+    See [`set_vt_input`](#method.set_vt_input).
+    */
+    pub fn is_vt_input(&self) -> bool {
+        self.vt_parser.is_some()
+    }
+    /**
     Reads data from the input queue without discarding it.
 
     # Arguments
@@ -154,6 +169,39 @@ impl InputContext {
         Ok(self.queue.remove(0))
     }
     /**
+    Returns a future which resolves to the next input event, without blocking the thread.
+    The future is cancel safe, so it can be used as a branch of `tokio::select!` in a loop.
+    For a stream of events which works with `tokio_stream`, see [`stream`](#method.stream).
+
+    # Examples
+    ```no_run,edition2018
+    # extern crate tokio;
+    # extern crate winconsole;
+    # use std::time::Duration;
+    # use winconsole::input;
+    # fn main() {
+    # let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    # runtime.block_on(async {
+    let mut ctx = input::start().unwrap();
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            event = ctx.recv() => println!("{}", event.unwrap()),
+            _ = interval.tick() => println!("Tick."),
+        }
+    }
+    # });
+    # }
+    ```
+
+    # Errors
+    The future resolves to an error in the same cases as [`poll`](#method.poll).
+    */
+    #[cfg(feature = "tokio")]
+    pub fn recv(&mut self) -> RecvEvent<'_> {
+        RecvEvent::new(self)
+    }
+    /**
     Resets the internal state of the context, clearing data about which keys and buttons are
     currently held along with the event queue.
 
@@ -173,9 +221,11 @@ impl InputContext {
     pub fn reset(&mut self) {
         self.held_keys.clear();
         self.queue.clear();
-        for i in 0..5 {
-            self.button_status[i] = console::get_key_state(BUTTON_VIRTUAL[i] as u32);
+        self.key_decoders = [Utf16Decoder::new(); 2];
+        if self.vt_parser.is_some() {
+            self.vt_parser = Some(VtInputParser::new());
         }
+        self.button_status = input::get_button_status();
     }
     /**
     Sets InputEvent types which should not be returned from methods.
@@ -209,6 +259,50 @@ impl InputContext {
             .collect();
     }
     /**
+    Sets whether or not user input is converted to [VT sequences] by the console, and decoded
+    into input events by a [`VtInputParser`]. Decoding is enabled when the context is created
+    if [`InputSettings::VirtualTerminalInput`] is set.
+
+    Terminals don't report key releases, so only KeyDown events are produced for keys while
+    VT input is enabled.
+
+    # Arguments
+    * `enabled` - Should VT input be enabled?
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::input;
+    # fn main() {
+    let mut ctx = input::start().unwrap();
+    ctx.set_vt_input(true).unwrap();
+    assert!(ctx.is_vt_input());
+    # }
+    ```
+
+    # Errors
+     * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+     * [`IoError`]: Returned if an OS error occurs.
+
+     [VT sequences]: https://docs.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences#input-sequences
+     [`InputSettings::VirtualTerminalInput`]: ../console/struct.InputSettings.html#structfield.VirtualTerminalInput
+     [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+     [`IoError`]: ../errors/enum.WinError.html#Io.v
+     [`VtInputParser`]: struct.VtInputParser.html
+    */
+    pub fn set_vt_input(&mut self, enabled: bool) -> WinResult<()> {
+        let mut mode = self.input.get_input_mode()?;
+        mode.VirtualTerminalInput = enabled;
+        self.input.set_input_mode(mode)?;
+
+        if !enabled {
+            self.vt_parser = None;
+        } else if self.vt_parser.is_none() {
+            self.vt_parser = Some(VtInputParser::new());
+        }
+        Ok(())
+    }
+    /**
     Adds an input event to the input queue.
 
     # Arguments
@@ -234,6 +328,29 @@ impl InputContext {
         self.push(event.into());
     }
     /**
+    Returns an asynchronous stream of input events, which waits for input without blocking
+    the thread. This allows input to be awaited alongside timers, network I/O and
+    [Ctrl events](../console/fn.ctrl_event_stream.html) in any async runtime.
+
+    # Examples
+    ```no_run
+    # extern crate futures;
+    # extern crate winconsole;
+    # use futures::executor;
+    # use winconsole::input;
+    # fn main() {
+    let mut ctx = input::start().unwrap();
+    for event in executor::block_on_stream(ctx.stream()) {
+        println!("{}", event.unwrap());
+    }
+    # }
+    ```
+    */
+    #[cfg(feature = "async")]
+    pub fn stream(&mut self) -> InputStream<'_> {
+        InputStream::new(self)
+    }
+    /**
     Waits until an input event is available and returns it.
 
     # Examples
@@ -256,6 +373,12 @@ impl InputContext {
     */
     pub fn wait(&mut self) -> WinResult<InputEvent> {
         if self.queue.len() == 0 {
+            // Buffered bytes of an incomplete VT sequence are flushed if no more input arrives.
+            while let Some(deadline) = self.get_vt_deadline() {
+                if let Some(event) = self.wait_until(deadline)? {
+                    return Ok(event);
+                }
+            }
             self.collect(true, false, 1000)?;
             if self.queue.len() == 0 {
                 return Ok(InputEvent::None);
@@ -264,33 +387,151 @@ impl InputContext {
 
         Ok(self.queue.remove(0))
     }
+    /**
+    Waits until an input event is available or the timeout elapses, and returns the event
+    if there is one.
+
+    # Arguments
+    * `timeout` - The maximum amount of time to wait.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::input;
+    # use std::time::Duration;
+    # fn main() {
+    let mut ctx = input::start().unwrap();
+    match ctx.wait_timeout(Duration::from_millis(16)).unwrap() {
+        Some(event) => println!("{}", event),
+        None => println!("No input this frame."),
+    }
+    # }
+    ```
+
+    # Errors
+     * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+     * [`IoError`]: Returned if an OS error occurs.
+
+     [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+     [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait_timeout(&mut self, timeout: Duration) -> WinResult<Option<InputEvent>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_until(deadline),
+            None => self.wait().map(Some),
+        }
+    }
+    /**
+    Waits until an input event is available or the deadline is reached, and returns the event
+    if there is one.
+
+    # Arguments
+    * `deadline` - The instant at which to stop waiting.
 
-    pub(crate) fn new(original_mode: InputSettings) -> InputContext {
+    # Examples
+    Gives the user five seconds to answer a prompt.
+
+    ```no_run
+    # extern crate winconsole;
+    # use winconsole::input::{self, InputEvent};
+    # use std::time::{Duration, Instant};
+    # fn main() {
+    let mut ctx = input::start().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    println!("Continue? [y/n]");
+    while let Some(event) = ctx.wait_until(deadline).unwrap() {
+        if let InputEvent::KeyDown(key) = event {
+            println!("You pressed {}.", key.character);
+            break;
+        }
+    }
+    # }
+    ```
+
+    # Errors
+     * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+     * [`IoError`]: Returned if an OS error occurs.
+
+     [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+     [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait_until(&mut self, deadline: Instant) -> WinResult<Option<InputEvent>> {
+        loop {
+            match self.poll()? {
+                InputEvent::None => {}
+                event => return Ok(Some(event)),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            let wake = match self.get_vt_deadline() {
+                Some(vt_deadline) => vt_deadline.min(deadline),
+                None => deadline,
+            };
+            let millis = wait_millis(wake, now);
+            let handle = self.input.get_handle();
+            if unsafe { synchapi::WaitForSingleObject(handle, millis) } == WAIT_FAILED {
+                return os_err!();
+            }
+        }
+    }
+
+    pub(crate) fn new(input: ConsoleInput, original_mode: InputSettings) -> InputContext {
+        let vt_parser = if original_mode.VirtualTerminalInput {
+            Some(VtInputParser::new())
+        } else {
+            None
+        };
         InputContext {
+            input,
             original_mode,
             button_status: [false; 5],
             repeat_enabled: true,
             restore_on_drop: true,
             held_keys: Vec::new(),
+            key_decoders: [Utf16Decoder::new(); 2],
             queue: Vec::new(),
+            vt_parser,
             filter: InputFilter::from(1),
             filter_value: 1,
         }
     }
 
-    fn collect(&mut self, wait: bool, peek: bool, max_length: u32) -> WinResult<Vec<InputEvent>> {
-        if !wait && console::num_input_events()? == 0 {
-            return Ok(Vec::new());
-        }
+    pub(crate) fn get_handle(&self) -> HANDLE {
+        self.input.get_handle()
+    }
+    pub(crate) fn get_vt_deadline(&self) -> Option<Instant> {
+        self.vt_parser.as_ref().and_then(VtInputParser::get_deadline)
+    }
 
-        let records = if peek {
-            console::peek_input(max_length as usize)?
+    fn collect(&mut self, wait: bool, peek: bool, max_length: u32) -> WinResult<Vec<InputEvent>> {
+        let handle = self.input.get_handle();
+        let records = if !wait && console::num_input_events(handle)? == 0 {
+            if self.get_vt_deadline().is_none() {
+                return Ok(Vec::new());
+            }
+            Vec::new()
+        } else if peek {
+            console::peek_input(handle, max_length as usize)?
         } else {
-            console::read_input(max_length as usize)?
+            console::read_input(handle, max_length as usize)?
         };
 
-        let events = input::convert_events(&records, self);
-        if peek {
+        // Peeking must leave the decoders' buffered input for the next read.
+        let decoders = if peek {
+            Some((self.key_decoders, self.vt_parser.clone()))
+        } else {
+            None
+        };
+        let mut events = input::convert_events(&records, self);
+        if let Some(ref mut parser) = self.vt_parser {
+            events.extend(parser.expire(Instant::now()));
+        }
+        if let Some((key_decoders, vt_parser)) = decoders {
+            self.key_decoders = key_decoders;
+            self.vt_parser = vt_parser;
             return Ok(events);
         }
 
@@ -309,7 +550,14 @@ impl InputContext {
 impl Drop for InputContext {
     fn drop(&mut self) {
         if self.restore_on_drop {
-            console::set_input_mode(self.original_mode).unwrap_or(())
+            self.input.set_input_mode(self.original_mode).unwrap_or(())
         }
     }
 }
+
+// Returns the amount of milliseconds to wait for the deadline, for WaitForSingleObject.
+pub(crate) fn wait_millis(deadline: Instant, now: Instant) -> u32 {
+    // Rounding up ensures the deadline has passed when the wait times out.
+    let remaining = deadline.saturating_duration_since(now).as_nanos().div_ceil(1_000_000);
+    remaining.min(u128::from(INFINITE - 1)) as u32
+}
@@ -29,6 +29,7 @@ pub enum InputEvent {
 }
 
 impl InputEvent {
+    #[cfg(windows)]
     pub(crate) fn get_type(&self) -> u16 {
         match *self {
             InputEvent::None => 0x1,
@@ -0,0 +1,191 @@
+use super::*;
+use std::ptr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use winapi::shared::minwindef::FALSE;
+use winapi::um::synchapi;
+use winapi::um::winbase::{INFINITE, WAIT_OBJECT_0};
+
+/**
+Reads input events on a dedicated thread, and sends them to a channel.
+
+The reader takes ownership of an [`InputContext`], so the context's filter and other settings
+apply to the events which are sent. Reading stops when the reader is stopped or dropped,
+which wakes the thread if it is waiting for input, or when the receiving end of the channel
+is dropped. The context is then dropped, restoring the original input mode if its
+`restore_on_drop` field is set.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::input::{self, InputEvent, InputReader};
+# fn main() {
+let (reader, events) = InputReader::start(input::start().unwrap()).unwrap();
+for event in events.iter() {
+    if let InputEvent::KeyDown(_) = event {
+        break;
+    }
+}
+reader.stop().unwrap();
+# }
+```
+
+Events can be sent to an existing channel, along with messages from other threads.
+
+```no_run
+# extern crate winconsole;
+# use winconsole::input::{self, InputEvent, InputReader};
+# use std::sync::mpsc;
+# fn main() {
+enum Message {
+    Input(InputEvent),
+    Quit,
+}
+impl From<InputEvent> for Message {
+    fn from(event: InputEvent) -> Message {
+        Message::Input(event)
+    }
+}
+
+let (sender, messages) = mpsc::channel();
+let reader = InputReader::with_sender(input::start().unwrap(), sender.clone()).unwrap();
+sender.send(Message::Quit).unwrap();
+for message in messages.iter() {
+    match message {
+        Message::Input(event) => println!("{}", event),
+        Message::Quit => break,
+    }
+}
+reader.stop().unwrap();
+# }
+```
+
+[`InputContext`]: struct.InputContext.html
+*/
+#[derive(Debug)]
+pub struct InputReader {
+    stop_event: Arc<ConsoleHandle>,
+    thread: Option<JoinHandle<WinResult<()>>>,
+}
+
+impl InputReader {
+    /**
+    Starts reading input events from a context on a new thread, and returns the reader along
+    with the receiving end of a channel which the events are sent to.
+
+    # Arguments
+    * `context` - The context to read input events from.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn start(context: InputContext) -> WinResult<(InputReader, Receiver<InputEvent>)> {
+        let (sender, receiver) = mpsc::channel();
+        let reader = InputReader::with_sender(context, sender)?;
+        Ok((reader, receiver))
+    }
+    /**
+    Starts reading input events from a context on a new thread, and sends them to an
+    existing channel.
+
+    # Arguments
+    * `context` - The context to read input events from.
+    * `sender` - The sending end of the channel. Events are converted to the type of its messages.
+
+    # Errors
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn with_sender<T>(context: InputContext, sender: Sender<T>) -> WinResult<InputReader>
+    where
+        T: From<InputEvent> + Send + 'static,
+    {
+        // A manual-reset event, so that the thread sees it however often it waits.
+        let event = unsafe { synchapi::CreateEventW(ptr::null_mut(), 1, FALSE, ptr::null()) };
+        if event.is_null() {
+            return os_err!();
+        }
+
+        let stop_event = Arc::new(ConsoleHandle::owned(event));
+        let thread_event = stop_event.clone();
+        let thread = thread::spawn(move || read(context, &sender, &thread_event));
+        Ok(InputReader {
+            stop_event,
+            thread: Some(thread),
+        })
+    }
+
+    /**
+    Returns whether or not the thread is still reading input. Reading stops when the receiving
+    end of the channel is dropped, or if an error occurs.
+    */
+    pub fn is_running(&self) -> bool {
+        match self.thread {
+            Some(ref thread) => !thread.is_finished(),
+            None => false,
+        }
+    }
+    /**
+    Stops reading input, and waits for the thread to exit.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input was used
+      while reading.
+    * [`IoError`]: Returned if an OS error occurred while reading.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn stop(mut self) -> WinResult<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> WinResult<()> {
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        unsafe { synchapi::SetEvent(self.stop_event.get_raw()) };
+        match thread.join() {
+            Ok(result) => result,
+            // The thread panicked, and the context was dropped while unwinding.
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+impl Drop for InputReader {
+    fn drop(&mut self) {
+        self.shutdown().unwrap_or(());
+    }
+}
+
+fn read<T: From<InputEvent>>(
+    mut context: InputContext,
+    sender: &Sender<T>,
+    stop_event: &ConsoleHandle,
+) -> WinResult<()> {
+    let handles = [stop_event.get_raw(), context.get_handle()];
+    loop {
+        loop {
+            match context.poll()? {
+                InputEvent::None => break,
+                event => {
+                    if sender.send(T::from(event)).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        match unsafe { synchapi::WaitForMultipleObjects(2, handles.as_ptr(), FALSE, INFINITE) } {
+            WAIT_OBJECT_0 => return Ok(()),
+            result if result == WAIT_OBJECT_0 + 1 => {}
+            _ => return os_err!(),
+        }
+    }
+}
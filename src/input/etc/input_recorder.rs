@@ -0,0 +1,192 @@
+use super::*;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/**
+Wraps an [`InputContext`], and writes every input event which is read through it to a log
+along with the time at which it was read.
+
+The log can be read with [`RecordedEvent::read_all`] and replayed with an [`InputReplayer`],
+in order to reproduce a user's input. Events which are read directly from the context
+are not recorded.
+
+# Examples
+```no_run
+# extern crate winconsole;
+# use winconsole::input::{self, InputEvent, InputRecorder, KeyCode, LogFormat};
+# use std::fs::File;
+# use std::io::BufWriter;
+# fn main() {
+let log = BufWriter::new(File::create("input.jsonl").unwrap());
+let mut recorder = InputRecorder::new(input::start().unwrap(), log, LogFormat::JsonLines);
+loop {
+    if let InputEvent::KeyDown(key) = recorder.wait().unwrap() {
+        if key.key_code == KeyCode::Escape {
+            break;
+        }
+    }
+}
+# }
+```
+
+[`InputContext`]: struct.InputContext.html
+[`InputReplayer`]: struct.InputReplayer.html
+[`RecordedEvent::read_all`]: struct.RecordedEvent.html#method.read_all
+*/
+pub struct InputRecorder<W: Write> {
+    context: InputContext,
+    format: LogFormat,
+    start: Instant,
+    writer: W,
+}
+
+impl<W: Write> InputRecorder<W> {
+    /**
+    Creates a new InputRecorder. Times are recorded relative to its creation.
+
+    # Arguments
+    * `context` - The context to read input events from.
+    * `writer` - The writer to write the log to.
+    * `format` - The format of the log.
+    */
+    pub fn new(context: InputContext, writer: W, format: LogFormat) -> InputRecorder<W> {
+        InputRecorder {
+            context,
+            format,
+            start: Instant::now(),
+            writer,
+        }
+    }
+
+    /**
+    Returns and records all of the input events which are currently in the queue.
+    See [`InputContext::get`](struct.InputContext.html#method.get).
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing to the log fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn get(&mut self) -> WinResult<Vec<InputEvent>> {
+        let events = self.context.get()?;
+        for event in events.iter() {
+            self.record(*event)?;
+        }
+        Ok(events)
+    }
+    /**
+    Returns a reference to the context.
+    */
+    pub fn get_context(&self) -> &InputContext {
+        &self.context
+    }
+    /**
+    Returns a mutable reference to the context.
+    Events which are read directly from it are not recorded.
+    */
+    pub fn get_context_mut(&mut self) -> &mut InputContext {
+        &mut self.context
+    }
+    /**
+    Consumes the InputRecorder, returning the context and the writer.
+    */
+    pub fn into_inner(self) -> (InputContext, W) {
+        (self.context, self.writer)
+    }
+    /**
+    Returns and records a single input event, or InputEvent::None if none are available.
+    See [`InputContext::poll`](struct.InputContext.html#method.poll).
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing to the log fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn poll(&mut self) -> WinResult<InputEvent> {
+        let event = self.context.poll()?;
+        self.record(event)?;
+        Ok(event)
+    }
+    /**
+    Writes an event to the log with the current time. InputEvent::None is not recorded.
+
+    # Arguments
+    * `event` - The event to record.
+
+    # Errors
+    * [`IoError`]: Returned if writing to the log fails.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn record(&mut self, event: impl Into<InputEvent>) -> WinResult<()> {
+        let event = event.into();
+        if event == InputEvent::None {
+            return Ok(());
+        }
+        RecordedEvent::new(self.start.elapsed(), event).write(&mut self.writer, self.format)
+    }
+    /**
+    Waits until an input event is available, and returns and records it.
+    See [`InputContext::wait`](struct.InputContext.html#method.wait).
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing to the log fails.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait(&mut self) -> WinResult<InputEvent> {
+        let event = self.context.wait()?;
+        self.record(event)?;
+        Ok(event)
+    }
+    /**
+    Waits until an input event is available or the timeout elapses, and returns and records
+    the event if there is one. See [`InputContext::wait_timeout`].
+
+    # Arguments
+    * `timeout` - The maximum amount of time to wait.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing to the log fails.
+
+    [`InputContext::wait_timeout`]: struct.InputContext.html#method.wait_timeout
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait_timeout(&mut self, timeout: Duration) -> WinResult<Option<InputEvent>> {
+        let event = self.context.wait_timeout(timeout)?;
+        if let Some(event) = event {
+            self.record(event)?;
+        }
+        Ok(event)
+    }
+    /**
+    Waits until an input event is available or the deadline is reached, and returns and records
+    the event if there is one. See [`InputContext::wait_until`].
+
+    # Arguments
+    * `deadline` - The instant at which to stop waiting.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs, or if writing to the log fails.
+
+    [`InputContext::wait_until`]: struct.InputContext.html#method.wait_until
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn wait_until(&mut self, deadline: Instant) -> WinResult<Option<InputEvent>> {
+        let event = self.context.wait_until(deadline)?;
+        if let Some(event) = event {
+            self.record(event)?;
+        }
+        Ok(event)
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+use errors::WinResult;
+#[cfg(windows)]
+use input;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/**
+Replays recorded input events, such as those which were read from a log written by an
+[`InputRecorder`](struct.InputRecorder.html).
+
+Events are replayed in order, with delays between them according to a [`ReplayTiming`].
+Delays are measured from the start of the replay, so time spent handling events does not
+accumulate.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{FocusEvent, InputReplayer, RecordedEvent, ReplayTiming};
+# use std::time::Duration;
+# fn main() {
+let events = vec![
+    RecordedEvent::new(Duration::from_millis(10), FocusEvent::new()),
+    RecordedEvent::new(Duration::from_secs(60), FocusEvent::new()),
+];
+let timing = ReplayTiming::Compressed(Duration::from_millis(5));
+let replayer = InputReplayer::new(events, timing);
+assert_eq!(
+    replayer.get_delays(),
+    vec![Duration::from_millis(5), Duration::from_millis(5)]
+);
+
+let mut replayed = Vec::new();
+replayer
+    .replay(|event| {
+        replayed.push(event);
+        Ok(())
+    })
+    .unwrap();
+assert_eq!(replayed.len(), 2);
+# }
+```
+
+[`ReplayTiming`]: enum.ReplayTiming.html
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputReplayer {
+    events: Vec<RecordedEvent>,
+    timing: ReplayTiming,
+}
+
+impl InputReplayer {
+    /**
+    Creates a new InputReplayer.
+
+    # Arguments
+    * `events` - The events to replay, in the order in which they were recorded.
+    * `timing` - The method by which events are spaced out.
+    */
+    pub fn new(events: Vec<RecordedEvent>, timing: ReplayTiming) -> InputReplayer {
+        InputReplayer { events, timing }
+    }
+
+    /**
+    Returns the delay which is waited before each event is replayed. The first delay is
+    measured from the start of the replay.
+    */
+    pub fn get_delays(&self) -> Vec<Duration> {
+        let mut previous = Duration::from_secs(0);
+        self.events
+            .iter()
+            .map(|recorded| {
+                let gap = recorded.time.checked_sub(previous).unwrap_or_default();
+                previous = recorded.time;
+                self.timing.get_delay(gap)
+            })
+            .collect()
+    }
+    /**
+    Returns the events which are replayed.
+    */
+    pub fn get_events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+    /**
+    Returns the method by which events are spaced out.
+    */
+    pub fn get_timing(&self) -> ReplayTiming {
+        self.timing
+    }
+    /**
+    Replays the events by passing each of them to a function, blocking the thread until all
+    of them have been replayed.
+
+    # Arguments
+    * `handler` - The function to call with each event.
+
+    # Errors
+    Returns the first error which is returned by `handler`, which stops the replay.
+    */
+    pub fn replay(&self, mut handler: impl FnMut(InputEvent) -> WinResult<()>) -> WinResult<()> {
+        let start = Instant::now();
+        let mut offset = Duration::from_secs(0);
+        for (recorded, delay) in self.events.iter().zip(self.get_delays()) {
+            offset += delay;
+            let elapsed = start.elapsed();
+            if offset > elapsed {
+                thread::sleep(offset - elapsed);
+            }
+            handler(recorded.event)?;
+        }
+        Ok(())
+    }
+    /**
+    Adds all of the events to the queue of an input context at once with
+    [`InputContext::simulate`](struct.InputContext.html#method.simulate).
+
+    The timing is ignored, since the context can't be read while it is borrowed. To replay
+    events into a context with their timing, call [`replay`](#method.replay) with a handler
+    which simulates each event and then reads the context.
+
+    # Arguments
+    * `context` - The context to add the events to.
+    */
+    #[cfg(windows)]
+    pub fn replay_into(&self, context: &mut InputContext) {
+        for recorded in &self.events {
+            context.simulate(recorded.event);
+        }
+    }
+    /**
+    Replays the events by writing them to the console input buffer with
+    [`input::write`](fn.write.html), so that they can be read by any input context.
+    This blocks the thread until all of the events have been replayed.
+
+    # Errors
+    * [`InvalidHandleError`]: Returned if an invalid handle to the console input is retrieved or used.
+    * [`IoError`]: Returned if an OS error occurs.
+
+    [`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    #[cfg(windows)]
+    pub fn replay_to_console(&self) -> WinResult<()> {
+        self.replay(|event| input::write(event, None))
+    }
+    /**
+    Sets the method by which events are spaced out.
+
+    # Arguments
+    * `timing` - The method to use.
+    */
+    pub fn set_timing(&mut self, timing: ReplayTiming) {
+        self.timing = timing;
+    }
+}
@@ -0,0 +1,131 @@
+use super::input_context::wait_millis;
+use super::*;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Instant;
+use winapi::shared::ntdef::{BOOLEAN, PVOID};
+use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+use winapi::um::threadpoollegacyapiset;
+use winapi::um::winbase::{self, INFINITE};
+use winapi::um::winnt::{HANDLE, WT_EXECUTEONLYONCE};
+
+/**
+An asynchronous stream of the input events of an [`InputContext`], which is returned from
+[`InputContext::stream`].
+
+While no events are available, the stream waits on the console input handle using the
+system thread pool rather than blocking or polling, so it can be used with any async runtime.
+The stream never ends.
+
+With the `tokio` feature, the stream can be used with `tokio_stream::StreamExt`, and
+[`InputContext::recv`] returns a future of the next event for use with `tokio::select!`.
+
+[`InputContext::recv`]: struct.InputContext.html#method.recv
+
+[`InputContext`]: struct.InputContext.html
+[`InputContext::stream`]: struct.InputContext.html#method.stream
+*/
+pub struct InputStream<'a> {
+    context: &'a mut InputContext,
+    state: Arc<WaitState>,
+    wait: HANDLE,
+}
+
+#[derive(Default)]
+struct WaitState {
+    signaled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+// The wait handle is only used by the stream, and may be unregistered from any thread.
+unsafe impl<'a> Send for InputStream<'a> {}
+
+impl<'a> InputStream<'a> {
+    pub(crate) fn new(context: &'a mut InputContext) -> InputStream<'a> {
+        InputStream {
+            context,
+            state: Arc::new(WaitState::default()),
+            wait: ptr::null_mut(),
+        }
+    }
+
+    fn register(&mut self, waker: &Waker) -> WinResult<()> {
+        match self.state.waker.lock() {
+            Ok(mut current) => *current = Some(waker.clone()),
+            Err(err) => *err.into_inner() = Some(waker.clone()),
+        }
+        if !self.wait.is_null() {
+            if !self.state.signaled.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            self.unregister();
+        }
+
+        // The wait completes immediately if input is already available. It also times out when
+        // buffered bytes of an incomplete VT sequence should be flushed.
+        let millis = match self.context.get_vt_deadline() {
+            Some(deadline) => wait_millis(deadline, Instant::now()),
+            None => INFINITE,
+        };
+        self.state.signaled.store(false, Ordering::SeqCst);
+        let context = Arc::as_ptr(&self.state) as PVOID;
+        os_err!(unsafe {
+            winbase::RegisterWaitForSingleObject(
+                &mut self.wait,
+                self.context.get_handle(),
+                Some(wake),
+                context,
+                millis,
+                WT_EXECUTEONLYONCE,
+            )
+        });
+        Ok(())
+    }
+    fn unregister(&mut self) {
+        if !self.wait.is_null() {
+            // This waits for the callback to finish, so the state is not used after being freed.
+            unsafe { threadpoollegacyapiset::UnregisterWaitEx(self.wait, INVALID_HANDLE_VALUE) };
+            self.wait = ptr::null_mut();
+        }
+    }
+}
+
+impl<'a> Drop for InputStream<'a> {
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
+
+impl<'a> Stream for InputStream<'a> {
+    type Item = WinResult<InputEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+        match stream.context.poll() {
+            Ok(InputEvent::None) => {}
+            Ok(event) => return Poll::Ready(Some(Ok(event))),
+            Err(err) => return Poll::Ready(Some(Err(err))),
+        }
+
+        match stream.register(cx.waker()) {
+            Ok(()) => Poll::Pending,
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+unsafe extern "system" fn wake(context: PVOID, _timed_out: BOOLEAN) {
+    let state = &*(context as *const WaitState);
+    state.signaled.store(true, Ordering::SeqCst);
+    let waker = match state.waker.lock() {
+        Ok(mut waker) => waker.take(),
+        Err(err) => err.into_inner().take(),
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
@@ -0,0 +1,301 @@
+use super::*;
+use errors::ArgumentError;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, Serializer};
+
+// Keys which are written as the character they produce on a US keyboard layout.
+const CHARACTER_KEYS: [(KeyCode, &str); 11] = [
+    (KeyCode::Oem1, ";"),
+    (KeyCode::Plus, "="),
+    (KeyCode::Comma, ","),
+    (KeyCode::Minus, "-"),
+    (KeyCode::Period, "."),
+    (KeyCode::Oem2, "/"),
+    (KeyCode::Oem3, "`"),
+    (KeyCode::Oem4, "["),
+    (KeyCode::Oem5, "\\"),
+    (KeyCode::Oem6, "]"),
+    (KeyCode::Oem7, "'"),
+];
+const KEY_ALIASES: [(KeyCode, &str); 6] = [
+    (KeyCode::Delete, "Del"),
+    (KeyCode::Escape, "Esc"),
+    (KeyCode::Insert, "Ins"),
+    (KeyCode::Plus, "+"),
+    (KeyCode::Return, "Enter"),
+    (KeyCode::Return, "Return"),
+];
+
+/**
+A key along with the modifier keys which are held while it is pressed, such as `Ctrl+Shift+F5`.
+
+Chords can be parsed from and formatted as strings, which makes them suitable for
+keybindings in configuration files. Modifiers are separated from the key by `+`, and are
+named `Ctrl`, `Alt` and `Shift`, or `LeftCtrl`, `RightCtrl`, `LeftAlt` and `RightAlt` to require
+a specific key of the pair. Keys are named after their [`KeyCode`], except that letters, digits
+and punctuation are written as characters, and `Enter`, `Esc`, `Del` and `Ins` are accepted as
+well. Names are not case sensitive.
+
+A chord made up of several keys pressed one after another is a [`KeySequence`].
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{KeyChord, KeyCode, KeyEvent, KeyModifiers};
+# fn main() {
+let chord: KeyChord = "ctrl+shift+f5".parse().unwrap();
+assert_eq!(chord.key_code, KeyCode::F5);
+assert!(chord.modifiers.Ctrl && chord.modifiers.Shift);
+assert_eq!(chord.to_string(), "Ctrl+Shift+F5");
+
+let mut event = KeyEvent::new();
+event.key_code = KeyCode::F5;
+event.modifiers.RightCtrlPressed = true;
+event.modifiers.ShiftPressed = true;
+assert!(chord.matches(&event));
+
+let left: KeyChord = "LeftCtrl+Shift+F5".parse().unwrap();
+assert!(!left.matches(&event));
+# }
+```
+
+[`KeyCode`]: enum.KeyCode.html
+[`KeySequence`]: struct.KeySequence.html
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyChord {
+    /// The KeyCode of the key which is pressed.
+    pub key_code: KeyCode,
+    /// The modifier keys which must be held.
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /**
+    Creates a new KeyChord. Sided modifiers are cleared when Alt or Ctrl is set, as either key
+    of the pair is accepted.
+
+    # Arguments
+    * `key_code` - The KeyCode of the key which is pressed.
+    * `modifiers` - The modifier keys which must be held.
+    */
+    pub fn new(key_code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        let mut modifiers = modifiers;
+        if modifiers.Alt {
+            modifiers.LeftAlt = false;
+            modifiers.RightAlt = false;
+        }
+        if modifiers.Ctrl {
+            modifiers.LeftCtrl = false;
+            modifiers.RightCtrl = false;
+        }
+        KeyChord {
+            key_code,
+            modifiers,
+        }
+    }
+
+    /**
+    Returns whether or not a key event is for this chord. The event's key must be the chord's
+    key, and exactly the chord's modifiers must be held. Whether the key is pressed or
+    released is not checked.
+
+    # Arguments
+    * `event` - The event to check.
+    */
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let state = event.modifiers;
+        let modifiers = self.modifiers;
+        let pair_matches = |any: bool, left: bool, right: bool, pressed: (bool, bool)| {
+            if any {
+                pressed.0 || pressed.1
+            } else {
+                pressed == (left, right)
+            }
+        };
+
+        event.key_code == self.key_code
+            && state.ShiftPressed == modifiers.Shift
+            && pair_matches(
+                modifiers.Alt,
+                modifiers.LeftAlt,
+                modifiers.RightAlt,
+                (state.LeftAltPressed, state.RightAltPressed),
+            )
+            && pair_matches(
+                modifiers.Ctrl,
+                modifiers.LeftCtrl,
+                modifiers.RightCtrl,
+                (state.LeftCtrlPressed, state.RightCtrlPressed),
+            )
+    }
+}
+
+impl Default for KeyChord {
+    /**
+    Returns a KeyChord for KeyCode::None without any modifiers.
+    */
+    fn default() -> KeyChord {
+        KeyChord::new(KeyCode::None, KeyModifiers::default())
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let chord = KeyChord::new(self.key_code, self.modifiers);
+        let modifiers = chord.modifiers;
+        let names = [
+            (modifiers.Ctrl, "Ctrl"),
+            (modifiers.LeftCtrl, "LeftCtrl"),
+            (modifiers.RightCtrl, "RightCtrl"),
+            (modifiers.Alt, "Alt"),
+            (modifiers.LeftAlt, "LeftAlt"),
+            (modifiers.RightAlt, "RightAlt"),
+            (modifiers.Shift, "Shift"),
+        ];
+        for &(_, name) in names.iter().filter(|&&(held, _)| held) {
+            write!(f, "{}+", name)?;
+        }
+        write!(f, "{}", key_name(chord.key_code))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    /**
+    Returns the chord which a key event is for. Alt and Ctrl are set without regard to which
+    key of the pair is pressed.
+    */
+    fn from(event: KeyEvent) -> KeyChord {
+        KeyChord::new(event.key_code, KeyModifiers::from(event.modifiers))
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = ArgumentError;
+
+    fn from_str(s: &str) -> Result<KeyChord, ArgumentError> {
+        let s = s.trim();
+        if s.split_whitespace().count() > 1 {
+            return Err(ArgumentError::new(
+                "s",
+                "a chord must be a single key; use a KeySequence for several keys",
+            ));
+        }
+
+        // The key is after the last separator, unless it's the `+` key itself.
+        let (modifier_names, name) = if s == "+" {
+            ("", s)
+        } else if let Some(modifier_names) = s.strip_suffix("++") {
+            (modifier_names, "+")
+        } else {
+            match s.rfind('+') {
+                Some(index) => (&s[..index], &s[index + 1..]),
+                None => ("", s),
+            }
+        };
+
+        let mut modifiers = KeyModifiers::default();
+        if !modifier_names.is_empty() {
+            for modifier in modifier_names.split('+') {
+                let flag = match modifier.to_lowercase().as_str() {
+                    "alt" => &mut modifiers.Alt,
+                    "ctrl" | "control" => &mut modifiers.Ctrl,
+                    "leftalt" | "lalt" => &mut modifiers.LeftAlt,
+                    "leftctrl" | "lctrl" => &mut modifiers.LeftCtrl,
+                    "rightalt" | "ralt" => &mut modifiers.RightAlt,
+                    "rightctrl" | "rctrl" => &mut modifiers.RightCtrl,
+                    "shift" => &mut modifiers.Shift,
+                    _ => {
+                        let message = format!("unknown modifier `{}`", modifier);
+                        return Err(ArgumentError::new("s", message));
+                    }
+                };
+                *flag = true;
+            }
+        }
+
+        match key_code(name) {
+            Some(key_code) => Ok(KeyChord::new(key_code, modifiers)),
+            None => Err(ArgumentError::new("s", format!("unknown key `{}`", name))),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for KeyChord {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<KeyChord, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ChordVisitor;
+
+        impl<'de> Visitor<'de> for ChordVisitor {
+            type Value = KeyChord;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a key chord such as \"Ctrl+Shift+F5\"")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<KeyChord, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ChordVisitor)
+    }
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    let mut characters = name.chars();
+    if let (Some(character), None) = (characters.next(), characters.next()) {
+        match character.to_ascii_uppercase() {
+            letter @ 'A'..='Z' => return Some(KeyCode::from(letter as u8)),
+            digit @ '0'..='9' => return Some(KeyCode::from(digit as u8)),
+            _ => {}
+        }
+    }
+
+    let aliases = CHARACTER_KEYS.iter().chain(KEY_ALIASES.iter());
+    for &(key_code, alias) in aliases {
+        if alias.eq_ignore_ascii_case(name) {
+            return Some(key_code);
+        }
+    }
+    (0..=255u8)
+        .map(KeyCode::from)
+        .find(|key_code| variant_name(*key_code).eq_ignore_ascii_case(name))
+}
+
+fn key_name(key_code: KeyCode) -> String {
+    let value: u8 = key_code.into();
+    match key_code {
+        KeyCode::Escape => String::from("Esc"),
+        KeyCode::Return => String::from("Enter"),
+        _ if value.is_ascii_digit() || value.is_ascii_uppercase() => (value as char).to_string(),
+        _ => match CHARACTER_KEYS.iter().find(|&&(code, _)| code == key_code) {
+            Some(&(_, character)) => String::from(character),
+            None => variant_name(key_code),
+        },
+    }
+}
+
+fn variant_name(key_code: KeyCode) -> String {
+    key_code.to_string().replacen("KeyCode::", "", 1)
+}
@@ -0,0 +1,16 @@
+use super::*;
+
+/// The result of passing an input event to a [`Keymap`](struct.Keymap.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyMatch<A> {
+    /// The event completed a binding, and its action should be performed.
+    Matched(A),
+    /// The event continued a binding which needs more keys to complete.
+    Pending,
+    /// The event didn't complete or continue a binding, either after the keys which were
+    /// pending or on its own. Contains every key of the attempted sequence, including keys
+    /// which were pending.
+    Unmatched(KeySequence),
+    /// The event isn't a key press, or is a press of a modifier key.
+    Ignored,
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+flags! {
+    /**
+     Modifier keys which must be held for a [`KeyChord`](struct.KeyChord.html) to match.
+
+     Alt and Ctrl are satisfied by either key of the pair, while LeftAlt, LeftCtrl, RightAlt
+     and RightCtrl require a specific key.
+     */
+    KeyModifiers<u8> {
+        /// Must either alt key be pressed?
+        Alt = 0x1,
+        /// Must either ctrl key be pressed?
+        Ctrl = 0x2,
+        /// Must left alt be pressed?
+        LeftAlt = 0x4,
+        /// Must left ctrl be pressed?
+        LeftCtrl = 0x8,
+        /// Must right alt be pressed?
+        RightAlt = 0x10,
+        /// Must right ctrl be pressed?
+        RightCtrl = 0x20,
+        /// Must the shift key be pressed?
+        Shift = 0x40,
+    }
+}
+
+impl From<ControlKeyState> for KeyModifiers {
+    /**
+    Returns the modifiers which are held in a ControlKeyState. Alt and Ctrl are set without
+    regard to which key of the pair is pressed.
+    */
+    fn from(state: ControlKeyState) -> KeyModifiers {
+        KeyModifiers {
+            Alt: state.LeftAltPressed || state.RightAltPressed,
+            Ctrl: state.LeftCtrlPressed || state.RightCtrlPressed,
+            Shift: state.ShiftPressed,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+use errors::ArgumentError;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, Serializer};
+
+/**
+A sequence of [`KeyChord`]s which are pressed one after another, such as `Ctrl+K Ctrl+C`.
+
+Sequences are parsed from and formatted as chords separated by whitespace. A sequence may
+contain a single chord, so every keybinding in a configuration file can be read as one.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{KeyChord, KeyCode, KeySequence};
+# fn main() {
+let sequence: KeySequence = "Ctrl+K  ctrl+c".parse().unwrap();
+assert_eq!(sequence.chords.len(), 2);
+assert_eq!(sequence.chords[1].key_code, KeyCode::C);
+assert_eq!(sequence.to_string(), "Ctrl+K Ctrl+C");
+
+let chord: KeyChord = "Alt+Enter".parse().unwrap();
+assert_eq!(KeySequence::from(chord), "Alt+Enter".parse().unwrap());
+# }
+```
+
+[`KeyChord`]: struct.KeyChord.html
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeySequence {
+    /// The chords which are pressed, in order.
+    pub chords: Vec<KeyChord>,
+}
+
+impl KeySequence {
+    /**
+    Creates a new KeySequence.
+
+    # Arguments
+    * `chords` - The chords which are pressed, in order.
+    */
+    pub fn new(chords: Vec<KeyChord>) -> KeySequence {
+        KeySequence { chords }
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, chord) in self.chords.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", chord)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<KeyChord> for KeySequence {
+    fn from(chord: KeyChord) -> KeySequence {
+        KeySequence::new(vec![chord])
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ArgumentError;
+
+    fn from_str(s: &str) -> Result<KeySequence, ArgumentError> {
+        let chords = s
+            .split_whitespace()
+            .map(KeyChord::from_str)
+            .collect::<Result<Vec<KeyChord>, ArgumentError>>()?;
+        if chords.is_empty() {
+            return Err(ArgumentError::new("s", "a sequence must contain a chord"));
+        }
+        Ok(KeySequence::new(chords))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for KeySequence {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D>(deserializer: D) -> Result<KeySequence, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SequenceVisitor;
+
+        impl<'de> Visitor<'de> for SequenceVisitor {
+            type Value = KeySequence;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a key sequence such as \"Ctrl+K Ctrl+C\"")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<KeySequence, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(SequenceVisitor)
+    }
+}
@@ -0,0 +1,411 @@
+use super::*;
+use errors::{ArgumentError, WinResult};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/**
+Maps key chords and sequences to actions, replacing a `match` on the KeyCode of every key
+event.
+
+Bindings belong to modes, like the modes of vim, and only the bindings of the current mode
+are used. Bindings can have several keys which are pressed one after another, such as
+`Ctrl+K Ctrl+C`, in which case the keys must follow each other within a timeout. A binding
+can't be a prefix of another binding in the same mode, as it would be ambiguous which one
+was meant, so such conflicts are rejected when bindings are added.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{InputEvent, KeyChord, KeyCode, KeyEvent, KeyMatch, Keymap};
+# fn main() {
+#[derive(Clone, Debug, PartialEq)]
+enum Action {
+    Comment,
+    Insert,
+    Quit,
+}
+
+let mut keymap = Keymap::new("normal");
+keymap
+    .load(
+        "normal",
+        vec![("Ctrl+K Ctrl+C", Action::Comment), ("I", Action::Insert), ("Ctrl+Q", Action::Quit)],
+    )
+    .unwrap();
+assert!(keymap.bind("normal", "Ctrl+K".parse::<KeyChord>().unwrap(), Action::Quit).is_err());
+
+let key = |key_code, ctrl| {
+    let mut kev = KeyEvent::new();
+    kev.key_code = key_code;
+    kev.modifiers.LeftCtrlPressed = ctrl;
+    kev.pressed = true;
+    InputEvent::KeyDown(kev)
+};
+assert_eq!(keymap.handle(&key(KeyCode::K, true)), KeyMatch::Pending);
+assert_eq!(keymap.handle(&key(KeyCode::C, true)), KeyMatch::Matched(Action::Comment));
+assert_eq!(keymap.handle(&key(KeyCode::I, false)), KeyMatch::Matched(Action::Insert));
+
+keymap.set_mode("insert");
+match keymap.handle(&key(KeyCode::I, false)) {
+    KeyMatch::Unmatched(keys) => assert_eq!(keys.to_string(), "I"),
+    _ => panic!(),
+}
+# }
+```
+*/
+#[derive(Clone, Debug)]
+pub struct Keymap<A> {
+    bindings: HashMap<String, Vec<(KeySequence, A)>>,
+    deadline: Option<Instant>,
+    mode: String,
+    pending: Vec<KeyEvent>,
+    timeout: Duration,
+}
+
+impl<A> Keymap<A> {
+    /**
+    Creates a new Keymap without any bindings. The timeout between keys of a sequence is
+    one second.
+
+    # Arguments
+    * `mode` - The initial mode.
+    */
+    pub fn new(mode: impl Into<String>) -> Keymap<A> {
+        Keymap {
+            bindings: HashMap::new(),
+            deadline: None,
+            mode: mode.into(),
+            pending: Vec::new(),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    /**
+    Binds a key sequence to an action in a mode.
+
+    # Arguments
+    * `mode` - The mode which the binding belongs to.
+    * `sequence` - The keys which trigger the action. A [`KeyChord`] can also be passed.
+    * `action` - The action to return when the keys are pressed.
+
+    # Errors
+    * [`ArgumentError`]: Returned if the sequence is empty, or if it conflicts with a binding
+      in the same mode: the sequences are the same, or one of them is a prefix of the other.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    [`KeyChord`]: struct.KeyChord.html
+    */
+    pub fn bind(
+        &mut self,
+        mode: impl Into<String>,
+        sequence: impl Into<KeySequence>,
+        action: A,
+    ) -> WinResult<()> {
+        let mode = mode.into();
+        let sequence = sequence.into();
+        let existing = self.bindings.get(&mode).map_or(&[][..], Vec::as_slice);
+        check_binding(&mode, existing, &sequence)?;
+        self.bindings.entry(mode).or_default().push((sequence, action));
+        Ok(())
+    }
+    /**
+    Removes the pending keys of a sequence if the timeout has elapsed since the last of them
+    was pressed, and returns them. Keys which time out are otherwise discarded when the next
+    key is handled.
+
+    # Arguments
+    * `now` - The current time.
+    */
+    pub fn expire(&mut self, now: Instant) -> Option<KeySequence> {
+        match self.deadline {
+            Some(deadline) if now >= deadline => Some(self.take_pending()),
+            _ => None,
+        }
+    }
+    /**
+    Returns the action which is bound to a key sequence in a mode.
+
+    # Arguments
+    * `mode` - The mode which the binding belongs to.
+    * `sequence` - The keys which trigger the action.
+    */
+    pub fn get_action(&self, mode: &str, sequence: &KeySequence) -> Option<&A> {
+        self.bindings
+            .get(mode)?
+            .iter()
+            .find(|binding| binding.0 == *sequence)
+            .map(|binding| &binding.1)
+    }
+    /**
+    Returns the time at which the pending keys of a sequence expire, or None if no keys are
+    pending. This can be passed to
+    [`InputContext::wait_until`](struct.InputContext.html#method.wait_until).
+    */
+    pub fn get_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+    /**
+    Returns the current mode.
+    */
+    pub fn get_mode(&self) -> &str {
+        &self.mode
+    }
+    /**
+    Returns the keys of a sequence which have been pressed so far.
+    */
+    pub fn get_pending(&self) -> KeySequence {
+        KeySequence::new(
+            self.pending
+                .iter()
+                .map(|kev| KeyChord::from(*kev))
+                .collect(),
+        )
+    }
+    /**
+    Returns the maximum amount of time between the keys of a sequence.
+    */
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+    /**
+    Passes an input event to the keymap, and returns whether it matched a binding.
+    Both KeyDown and KeyHeld events are handled, so holding the keys of a binding repeats it.
+    KeyHeld events are ignored while a sequence is pending, so that holding a key of a
+    sequence doesn't break it.
+
+    If a key breaks a pending sequence, it is tried again as the start of a new sequence, and
+    the keys which were pending are discarded if it matches or continues a binding.
+
+    # Arguments
+    * `event` - The event to handle.
+    */
+    pub fn handle(&mut self, event: &InputEvent) -> KeyMatch<A>
+    where
+        A: Clone,
+    {
+        self.handle_at(event, Instant::now())
+    }
+    /**
+    Passes an input event to the keymap as if it occurred at a specific time, and returns
+    whether it matched a binding. See [`handle`](#method.handle).
+
+    # Arguments
+    * `event` - The event to handle.
+    * `now` - The time at which the event occurred.
+    */
+    pub fn handle_at(&mut self, event: &InputEvent, now: Instant) -> KeyMatch<A>
+    where
+        A: Clone,
+    {
+        let (kev, held) = match *event {
+            InputEvent::KeyDown(kev) => (kev, false),
+            InputEvent::KeyHeld(kev) => (kev, true),
+            _ => return KeyMatch::Ignored,
+        };
+        if is_modifier(kev.key_code) {
+            return KeyMatch::Ignored;
+        }
+        self.expire(now);
+        if held && !self.pending.is_empty() {
+            return KeyMatch::Ignored;
+        }
+
+        self.pending.push(kev);
+        let (mut matched, mut continued) = self.find();
+        let mut unmatched = None;
+        if matched.is_none() && !continued && self.pending.len() > 1 {
+            unmatched = Some(self.take_pending());
+            self.pending.push(kev);
+            let (retried, retried_continued) = self.find();
+            matched = retried;
+            continued = retried_continued;
+        }
+
+        if let Some(action) = matched {
+            self.take_pending();
+            KeyMatch::Matched(action)
+        } else if continued {
+            self.deadline = now.checked_add(self.timeout);
+            KeyMatch::Pending
+        } else {
+            let keys = self.take_pending();
+            KeyMatch::Unmatched(unmatched.unwrap_or(keys))
+        }
+    }
+    /**
+    Binds key sequences which are parsed from strings, such as those loaded from a
+    configuration file, to actions in a mode. Either all of the bindings are added, or none
+    of them are.
+
+    # Arguments
+    * `mode` - The mode which the bindings belong to.
+    * `bindings` - The key sequences and the actions which they trigger.
+      See [`KeySequence`](struct.KeySequence.html) for the format of sequences.
+
+    # Errors
+    * [`ArgumentError`]: Returned if a sequence can't be parsed, or if it conflicts with
+      another binding in the same mode.
+
+    [`ArgumentError`]: ../errors/enum.WinError.html#Argument.v
+    */
+    pub fn load<S: AsRef<str>>(
+        &mut self,
+        mode: impl Into<String>,
+        bindings: impl IntoIterator<Item = (S, A)>,
+    ) -> WinResult<()> {
+        let mode = mode.into();
+        let mut loaded = Vec::new();
+        for (text, action) in bindings {
+            let sequence: KeySequence = text.as_ref().parse()?;
+            if let Some(existing) = self.bindings.get(&mode) {
+                check_binding(&mode, existing, &sequence)?;
+            }
+            check_binding(&mode, &loaded, &sequence)?;
+            loaded.push((sequence, action));
+        }
+
+        self.bindings.entry(mode).or_default().extend(loaded);
+        Ok(())
+    }
+    /**
+    Discards the pending keys of a sequence.
+    */
+    pub fn reset(&mut self) {
+        self.take_pending();
+    }
+    /**
+    Sets the current mode, discarding the pending keys of a sequence.
+
+    # Arguments
+    * `mode` - The mode to switch to. It doesn't need to have any bindings.
+    */
+    pub fn set_mode(&mut self, mode: impl Into<String>) {
+        self.mode = mode.into();
+        self.reset();
+    }
+    /**
+    Sets the maximum amount of time between the keys of a sequence.
+
+    # Arguments
+    * `timeout` - The timeout to use.
+    */
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+    /**
+    Removes the binding of a key sequence in a mode, and returns its action.
+
+    # Arguments
+    * `mode` - The mode which the binding belongs to.
+    * `sequence` - The keys which trigger the action.
+    */
+    pub fn unbind(&mut self, mode: &str, sequence: &KeySequence) -> Option<A> {
+        let bindings = self.bindings.get_mut(mode)?;
+        let index = bindings.iter().position(|binding| binding.0 == *sequence)?;
+        Some(bindings.remove(index).1)
+    }
+
+    // Returns the action of the binding which the pending keys complete, and whether or not
+    // they are the prefix of a longer binding.
+    fn find(&self) -> (Option<A>, bool)
+    where
+        A: Clone,
+    {
+        let mut continued = false;
+        if let Some(bindings) = self.bindings.get(&self.mode) {
+            for (sequence, action) in bindings.iter() {
+                let chords = &sequence.chords;
+                if chords.len() < self.pending.len() {
+                    continue;
+                }
+                let mut prefix = chords.iter().zip(self.pending.iter());
+                if !prefix.all(|(chord, kev)| chord.matches(kev)) {
+                    continue;
+                }
+                if chords.len() == self.pending.len() {
+                    return (Some(action.clone()), false);
+                }
+                continued = true;
+            }
+        }
+        (None, continued)
+    }
+    fn take_pending(&mut self) -> KeySequence {
+        let sequence = self.get_pending();
+        self.pending.clear();
+        self.deadline = None;
+        sequence
+    }
+}
+
+fn check_binding<A>(
+    mode: &str,
+    bindings: &[(KeySequence, A)],
+    sequence: &KeySequence,
+) -> WinResult<()> {
+    if sequence.chords.is_empty() {
+        throw_err!(ArgumentError::new(
+            "sequence",
+            "a binding must contain a key"
+        ));
+    }
+    for (existing, _) in bindings {
+        let overlapping = existing
+            .chords
+            .iter()
+            .zip(sequence.chords.iter())
+            .all(|(a, b)| overlaps(a, b));
+        if overlapping {
+            let message = format!(
+                "`{}` conflicts with `{}` in mode `{}`",
+                sequence, existing, mode
+            );
+            throw_err!(ArgumentError::new("sequence", message));
+        }
+    }
+    Ok(())
+}
+
+fn is_modifier(key_code: KeyCode) -> bool {
+    matches!(
+        key_code,
+        KeyCode::Shift
+            | KeyCode::Control
+            | KeyCode::Menu
+            | KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LControl
+            | KeyCode::RControl
+            | KeyCode::LMenu
+            | KeyCode::RMenu
+            | KeyCode::Capital
+            | KeyCode::NumLock
+            | KeyCode::Scroll
+    )
+}
+
+// Returns whether or not a key event could match both chords.
+fn overlaps(a: &KeyChord, b: &KeyChord) -> bool {
+    let (x, y) = (a.modifiers, b.modifiers);
+    // Each chord's sides are given as (left, right).
+    let pair_overlaps = |any: (bool, bool), x_sides: (bool, bool), y_sides: (bool, bool)| match any
+    {
+        (true, true) => true,
+        (true, false) => y_sides.0 || y_sides.1,
+        (false, true) => x_sides.0 || x_sides.1,
+        (false, false) => x_sides == y_sides,
+    };
+
+    a.key_code == b.key_code
+        && x.Shift == y.Shift
+        && pair_overlaps(
+            (x.Alt, y.Alt),
+            (x.LeftAlt, x.RightAlt),
+            (y.LeftAlt, y.RightAlt),
+        )
+        && pair_overlaps(
+            (x.Ctrl, y.Ctrl),
+            (x.LeftCtrl, x.RightCtrl),
+            (y.LeftCtrl, y.RightCtrl),
+        )
+}
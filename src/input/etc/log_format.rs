@@ -0,0 +1,9 @@
+/// Formats of the input logs which are written by an [`InputRecorder`](struct.InputRecorder.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LogFormat {
+    /// Each event is written as a line of JSON, which can be read and edited by hand.
+    JsonLines,
+    /// Events are written as a sequence of compact CBOR values.
+    Binary,
+}
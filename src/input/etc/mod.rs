@@ -2,24 +2,70 @@ use super::*;
 
 mod control_key_state;
 mod focus_event;
+#[cfg(windows)]
 mod input_context;
 mod input_event;
 mod input_filter;
+#[cfg(all(windows, feature = "recording"))]
+mod input_recorder;
+#[cfg(windows)]
+mod input_reader;
+#[cfg(feature = "recording")]
+mod input_replayer;
+#[cfg(all(windows, feature = "async"))]
+mod input_stream;
+mod key_chord;
 mod key_code;
 mod key_event;
+mod key_match;
+mod key_modifiers;
+mod key_sequence;
+mod keymap;
+#[cfg(feature = "recording")]
+mod log_format;
 mod mouse_event;
 mod mouse_move_event;
 mod mouse_wheel_event;
+#[cfg(feature = "recording")]
+mod recorded_event;
+#[cfg(all(windows, feature = "tokio"))]
+mod recv_event;
+#[cfg(feature = "recording")]
+mod replay_timing;
 mod resize_event;
+mod vt_input_parser;
 
 pub use self::control_key_state::ControlKeyState;
 pub use self::focus_event::FocusEvent;
+#[cfg(windows)]
 pub use self::input_context::InputContext;
 pub use self::input_event::InputEvent;
 pub use self::input_filter::InputFilter;
+#[cfg(all(windows, feature = "recording"))]
+pub use self::input_recorder::InputRecorder;
+#[cfg(windows)]
+pub use self::input_reader::InputReader;
+#[cfg(feature = "recording")]
+pub use self::input_replayer::InputReplayer;
+#[cfg(all(windows, feature = "async"))]
+pub use self::input_stream::InputStream;
+pub use self::key_chord::KeyChord;
 pub use self::key_code::KeyCode;
 pub use self::key_event::KeyEvent;
+pub use self::key_match::KeyMatch;
+pub use self::key_modifiers::KeyModifiers;
+pub use self::key_sequence::KeySequence;
+pub use self::keymap::Keymap;
+#[cfg(feature = "recording")]
+pub use self::log_format::LogFormat;
 pub use self::mouse_event::MouseEvent;
 pub use self::mouse_move_event::MouseMoveEvent;
 pub use self::mouse_wheel_event::MouseWheelEvent;
+#[cfg(feature = "recording")]
+pub use self::recorded_event::RecordedEvent;
+#[cfg(all(windows, feature = "tokio"))]
+pub use self::recv_event::RecvEvent;
+#[cfg(feature = "recording")]
+pub use self::replay_timing::ReplayTiming;
 pub use self::resize_event::ResizeEvent;
+pub use self::vt_input_parser::VtInputParser;
@@ -0,0 +1,117 @@
+use super::*;
+use errors::WinResult;
+use ciborium;
+use serde_json;
+use std::error::Error;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::time::Duration;
+
+/**
+An input event along with the time at which it occurred, relative to the start of a recording.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{FocusEvent, LogFormat, RecordedEvent};
+# use std::time::Duration;
+# fn main() {
+let event = RecordedEvent::new(Duration::from_millis(250), FocusEvent::new());
+let mut log = Vec::new();
+event.write(&mut log, LogFormat::JsonLines).unwrap();
+event.write(&mut log, LogFormat::JsonLines).unwrap();
+
+let events = RecordedEvent::read_all(&log[..], LogFormat::JsonLines).unwrap();
+assert_eq!(events, vec![event, event]);
+# }
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The time at which the event occurred.
+    pub time: Duration,
+    /// The event which occurred.
+    pub event: InputEvent,
+}
+
+impl RecordedEvent {
+    /**
+    Creates a new RecordedEvent.
+
+    # Arguments
+    * `time` - The time at which the event occurred.
+    * `event` - The event which occurred.
+    */
+    pub fn new(time: Duration, event: impl Into<InputEvent>) -> RecordedEvent {
+        RecordedEvent {
+            time,
+            event: event.into(),
+        }
+    }
+
+    /**
+    Reads all of the events in a log.
+
+    # Arguments
+    * `reader` - The reader to read the log from.
+    * `format` - The format of the log.
+
+    # Errors
+    * [`IoError`]: Returned if reading fails, or if the log is malformed. Errors caused by
+      a malformed log have the `InvalidData` kind.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn read_all(reader: impl Read, format: LogFormat) -> WinResult<Vec<RecordedEvent>> {
+        let mut events = Vec::new();
+        match format {
+            LogFormat::JsonLines => {
+                for line in BufReader::new(reader).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        events.push(serde_json::from_str(&line).map_err(invalid_data)?);
+                    }
+                }
+            }
+            LogFormat::Binary => {
+                let mut reader = BufReader::new(reader);
+                while !reader.fill_buf()?.is_empty() {
+                    let event = ciborium::de::from_reader(&mut reader).map_err(invalid_data)?;
+                    events.push(event);
+                }
+            }
+        }
+        Ok(events)
+    }
+    /**
+    Writes the event to a log.
+
+    # Arguments
+    * `writer` - The writer to write the log to.
+    * `format` - The format of the log.
+
+    # Errors
+    * [`IoError`]: Returned if writing fails.
+
+    [`IoError`]: ../errors/enum.WinError.html#Io.v
+    */
+    pub fn write(&self, mut writer: impl Write, format: LogFormat) -> WinResult<()> {
+        let bytes = match format {
+            LogFormat::JsonLines => {
+                let mut line = serde_json::to_vec(self).map_err(invalid_data)?;
+                line.push(b'\n');
+                line
+            }
+            LogFormat::Binary => {
+                let mut bytes = Vec::new();
+                ciborium::ser::into_writer(self, &mut bytes).map_err(invalid_data)?;
+                bytes
+            }
+        };
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+fn invalid_data(err: impl Into<Box<dyn Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,40 @@
+use super::*;
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/**
+A future which resolves to the next input event of an [`InputContext`], which is returned from
+[`InputContext::recv`].
+
+The future is cancel safe: an event is only taken from the context when the future resolves,
+so dropping it, such as when another branch of `tokio::select!` completes first, doesn't lose
+any input.
+
+[`InputContext`]: struct.InputContext.html
+[`InputContext::recv`]: struct.InputContext.html#method.recv
+*/
+pub struct RecvEvent<'a> {
+    stream: InputStream<'a>,
+}
+
+impl<'a> RecvEvent<'a> {
+    pub(crate) fn new(context: &'a mut InputContext) -> RecvEvent<'a> {
+        RecvEvent {
+            stream: InputStream::new(context),
+        }
+    }
+}
+
+impl<'a> Future for RecvEvent<'a> {
+    type Output = WinResult<InputEvent>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The input stream never ends.
+        match Pin::new(&mut self.get_mut().stream).poll_next(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            _ => Poll::Pending,
+        }
+    }
+}
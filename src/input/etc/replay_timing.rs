@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// Methods by which an [`InputReplayer`](struct.InputReplayer.html) spaces out events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReplayTiming {
+    /// Events are replayed with the delays between them which were recorded.
+    Real,
+    /// Events are replayed with the recorded delays, but no delay is longer than the given one.
+    Compressed(Duration),
+    /// Events are replayed without delays.
+    Immediate,
+}
+
+impl ReplayTiming {
+    /**
+    Returns the delay which is waited before replaying an event.
+
+    # Arguments
+    * `gap` - The time between the event and the previous one when they were recorded.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::input::ReplayTiming;
+    # use std::time::Duration;
+    # fn main() {
+    let timing = ReplayTiming::Compressed(Duration::from_millis(100));
+    assert_eq!(timing.get_delay(Duration::from_secs(5)), Duration::from_millis(100));
+    assert_eq!(timing.get_delay(Duration::from_millis(20)), Duration::from_millis(20));
+    # }
+    ```
+    */
+    pub fn get_delay(&self, gap: Duration) -> Duration {
+        match *self {
+            ReplayTiming::Real => gap,
+            ReplayTiming::Compressed(max) => gap.min(max),
+            ReplayTiming::Immediate => Duration::from_secs(0),
+        }
+    }
+}
@@ -0,0 +1,556 @@
+use super::*;
+use std::char::REPLACEMENT_CHARACTER;
+use std::mem;
+use std::str;
+use std::time::{Duration, Instant};
+
+// How long an incomplete sequence waits for the rest of its bytes. Terminals send a sequence
+// at once, so a lone escape byte which isn't followed by more input soon is the Escape key.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+// Limits the memory used by malformed or hostile sequences.
+const MAX_SEQUENCE_LENGTH: usize = 64;
+const PASTE_END: &[u8] = b"\x1b[201~";
+const WHEEL_DELTA: i16 = 120;
+
+/**
+Decodes the [VT sequences] which a terminal sends as input into input events.
+
+When [`InputSettings::VirtualTerminalInput`] is enabled, or when reading from a terminal on
+another platform, keys and mouse actions arrive as characters and escape sequences rather than
+as input records. The parser supports:
+
+* Printable characters, control characters and Alt combinations sent as an escape prefix.
+* Cursor, editing and function keys, including their modifiers (`ESC [ 1 ; 5 C`).
+* SGR mouse reporting (`ESC [ < 0 ; 10 ; 5 M`) and legacy X10 mouse reporting.
+* Focus reporting, and window size reports (`ESC [ 8 ; rows ; columns t`).
+* Bracketed paste. Pasted text is decoded as a KeyDown event for every character, without
+  interpreting any escape sequences it contains.
+
+Terminals do not report key releases, so only KeyDown events are produced. Key codes of
+printable characters assume a US keyboard layout, while the `character` of an event is
+always the character which was sent. Unsupported sequences are ignored.
+
+Bytes can be fed in chunks of any size, as sequences and UTF-8 characters which are split
+between chunks are buffered. An escape byte on its own may be the Escape key or the start of a
+sequence, so it is held until more input arrives or the parser is flushed. Incomplete
+sequences which don't receive more input within 50 milliseconds can be flushed with
+[`expire`](#method.expire).
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::input::{InputEvent, KeyCode, VtInputParser};
+# fn main() {
+let mut parser = VtInputParser::new();
+let events = parser.feed(b"a\x1b[1;5C\x1b[<0;10;5M");
+assert_eq!(events.len(), 3);
+match events[1] {
+    InputEvent::KeyDown(key) => {
+        assert_eq!(key.key_code, KeyCode::Right);
+        assert!(key.modifiers.LeftCtrlPressed);
+    }
+    _ => panic!(),
+}
+match events[2] {
+    InputEvent::MouseDown(mouse) => assert_eq!(mouse.position.x, 9),
+    _ => panic!(),
+}
+
+assert!(parser.feed(b"\x1b").is_empty());
+assert!(parser.is_pending());
+let events = parser.flush();
+match events[0] {
+    InputEvent::KeyDown(key) => assert_eq!(key.key_code, KeyCode::Escape),
+    _ => panic!(),
+}
+# }
+```
+
+[VT sequences]: https://docs.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences#input-sequences
+[`InputSettings::VirtualTerminalInput`]: ../console/struct.InputSettings.html#structfield.VirtualTerminalInput
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VtInputParser {
+    buffer: Vec<u8>,
+    deadline: Option<Instant>,
+    held_button: Option<(u8, KeyCode)>,
+    pasting: bool,
+}
+
+impl VtInputParser {
+    /**
+    Creates a new VtInputParser.
+    */
+    pub fn new() -> VtInputParser {
+        VtInputParser {
+            buffer: Vec::new(),
+            deadline: None,
+            held_button: None,
+            pasting: false,
+        }
+    }
+
+    /**
+    Parses bytes of input, returning the events which they complete.
+
+    # Arguments
+    * `bytes` - The bytes to parse.
+    */
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<InputEvent> {
+        self.feed_at(bytes, Instant::now())
+    }
+    /**
+    Parses bytes of input which arrived at the given instant, returning the events which they
+    complete. See [`feed`](#method.feed).
+
+    # Arguments
+    * `bytes` - The bytes to parse.
+    * `now` - The instant at which the bytes arrived.
+    */
+    pub fn feed_at(&mut self, bytes: &[u8], now: Instant) -> Vec<InputEvent> {
+        self.buffer.extend_from_slice(bytes);
+        let buffer = mem::take(&mut self.buffer);
+        let mut events = Vec::new();
+        let mut start = 0;
+        while start < buffer.len() {
+            let parsed = if self.pasting {
+                self.parse_paste(&buffer[start..])
+            } else {
+                self.parse(&buffer[start..])
+            };
+            match parsed {
+                Some((length, event)) => {
+                    start += length;
+                    events.extend(event);
+                }
+                None => break,
+            }
+        }
+        self.buffer = buffer[start..].to_vec();
+        self.deadline = if self.buffer.is_empty() {
+            None
+        } else {
+            now.checked_add(ESCAPE_TIMEOUT)
+        };
+        events
+    }
+    /**
+    Flushes the buffered bytes if the deadline of the incomplete sequence has been reached,
+    and returns their events. See [`flush`](#method.flush).
+
+    # Arguments
+    * `now` - The current instant.
+    */
+    pub fn expire(&mut self, now: Instant) -> Vec<InputEvent> {
+        match self.deadline {
+            Some(deadline) if now >= deadline => self.flush(),
+            _ => Vec::new(),
+        }
+    }
+    /**
+    Returns events for any bytes which are buffered as the start of an incomplete sequence.
+    A buffered escape byte is returned as the Escape key, and the bytes following it are
+    returned as characters.
+
+    This should be called once no more input is immediately available.
+    */
+    pub fn flush(&mut self) -> Vec<InputEvent> {
+        self.deadline = None;
+        let buffer = mem::take(&mut self.buffer);
+        let mut start = 0;
+        let mut events = Vec::new();
+        if !self.pasting && buffer.first() == Some(&0x1b) {
+            events.push(char_event('\x1b', ControlKeyState::default()));
+            start = 1;
+        }
+        for character in String::from_utf8_lossy(&buffer[start..]).chars() {
+            events.push(char_event(character, ControlKeyState::default()));
+        }
+        events
+    }
+    /**
+    Returns the instant at which the buffered bytes of an incomplete sequence are no longer
+    waited on, if any are buffered.
+    */
+    pub fn get_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+    /**
+    Returns whether or not the parser is inside of a bracketed paste.
+    */
+    pub fn is_pasting(&self) -> bool {
+        self.pasting
+    }
+    /**
+    Returns whether or not bytes are buffered, waiting for the rest of a sequence.
+    */
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    // Each parse function returns the amount of bytes used along with the event they form,
+    // or None if more bytes are needed.
+    fn parse(&mut self, bytes: &[u8]) -> Option<(usize, Option<InputEvent>)> {
+        if bytes[0] != 0x1b {
+            let (character, length) = decode_char(bytes)?;
+            return Some((
+                length,
+                Some(char_event(character, ControlKeyState::default())),
+            ));
+        }
+
+        match bytes.get(1) {
+            None => None,
+            Some(b'[') => self
+                .parse_csi(&bytes[2..])
+                .map(|(length, event)| (length + 2, event)),
+            Some(b'O') => {
+                let event = match bytes.get(2) {
+                    None => return None,
+                    Some(&byte) => cursor_key(byte, None),
+                };
+                Some((3, event))
+            }
+            Some(0x1b) => Some((1, Some(char_event('\x1b', ControlKeyState::default())))),
+            Some(_) => {
+                let (character, length) = decode_char(&bytes[1..])?;
+                let modifiers = ControlKeyState {
+                    LeftAltPressed: true,
+                    ..Default::default()
+                };
+                Some((length + 1, Some(char_event(character, modifiers))))
+            }
+        }
+    }
+    fn parse_csi(&mut self, bytes: &[u8]) -> Option<(usize, Option<InputEvent>)> {
+        let prefix = match bytes.first() {
+            Some(&byte @ b'<'..=b'?') => Some(byte),
+            _ => None,
+        };
+        let start = prefix.is_some() as usize;
+
+        let mut end = start;
+        loop {
+            match bytes.get(end) {
+                None if end >= MAX_SEQUENCE_LENGTH => return Some((end, None)),
+                None => return None,
+                Some(0x40..=0x7e) => break,
+                Some(0x20..=0x3f) => end += 1,
+                // A control character cancels the sequence.
+                Some(_) => return Some((end, None)),
+            }
+        }
+        let length = end + 1;
+        let intermediate = bytes[start..end].iter().any(|byte| *byte < 0x30);
+        if intermediate {
+            return Some((length, None));
+        }
+
+        let params: Vec<Option<u16>> = str::from_utf8(&bytes[start..end])
+            .unwrap_or("")
+            .split(';')
+            .map(|param| {
+                param
+                    .parse::<u32>()
+                    .ok()
+                    .map(|value| value.min(0xffff) as u16)
+            })
+            .collect();
+        let param = |index: usize| params.get(index).cloned().unwrap_or(None);
+
+        let event = match (prefix, bytes[end]) {
+            (None, b'M') if end == 0 => {
+                // X10 mouse reporting: three bytes, each offset by 32.
+                if bytes.len() < 4 {
+                    return None;
+                }
+                let values: Vec<u16> = bytes[1..4]
+                    .iter()
+                    .map(|byte| u16::from(byte.saturating_sub(32)))
+                    .collect();
+                let event = self.mouse_event(values[0], values[1], values[2], None);
+                return Some((4, event));
+            }
+            (Some(b'<'), final_byte @ b'M') | (Some(b'<'), final_byte @ b'm') => {
+                match (param(0), param(1), param(2)) {
+                    (Some(code), Some(x), Some(y)) => {
+                        self.mouse_event(code, x, y, Some(final_byte == b'M'))
+                    }
+                    _ => None,
+                }
+            }
+            (None, b'I') | (None, b'O') => {
+                let mut fev = FocusEvent::new();
+                fev.focused = bytes[end] == b'I';
+                Some(if fev.focused {
+                    InputEvent::Focused(fev)
+                } else {
+                    InputEvent::FocusLost(fev)
+                })
+            }
+            (None, b'Z') => {
+                let modifiers = ControlKeyState {
+                    ShiftPressed: true,
+                    ..Default::default()
+                };
+                Some(key_event(KeyCode::Tab, '\t', modifiers))
+            }
+            (None, b't') if param(0) == Some(8) => match (param(1), param(2)) {
+                (Some(rows), Some(columns)) => {
+                    let mut rev = ResizeEvent::new();
+                    rev.size = Vector2::new(columns, rows);
+                    Some(InputEvent::Resize(rev))
+                }
+                _ => None,
+            },
+            (None, b'~') => match param(0) {
+                Some(200) => {
+                    self.pasting = true;
+                    None
+                }
+                Some(code) => tilde_key(code, param(1)),
+                None => None,
+            },
+            (None, final_byte) => cursor_key(final_byte, param(1)),
+            _ => None,
+        };
+        Some((length, event))
+    }
+    fn parse_paste(&mut self, bytes: &[u8]) -> Option<(usize, Option<InputEvent>)> {
+        if bytes.starts_with(PASTE_END) {
+            self.pasting = false;
+            return Some((PASTE_END.len(), None));
+        }
+        if PASTE_END.starts_with(bytes) {
+            return None;
+        }
+
+        let (character, length) = decode_char(bytes)?;
+        Some((
+            length,
+            Some(char_event(character, ControlKeyState::default())),
+        ))
+    }
+
+    fn mouse_event(
+        &mut self,
+        code: u16,
+        x: u16,
+        y: u16,
+        pressed: Option<bool>,
+    ) -> Option<InputEvent> {
+        let modifiers = ControlKeyState {
+            ShiftPressed: code & 4 != 0,
+            LeftAltPressed: code & 8 != 0,
+            LeftCtrlPressed: code & 16 != 0,
+            ..Default::default()
+        };
+        let position = Vector2::new(x.saturating_sub(1), y.saturating_sub(1));
+
+        if code & 64 != 0 && code & 128 == 0 {
+            let mut mwev = MouseWheelEvent::new();
+            mwev.delta = match code & 3 {
+                0 | 3 => WHEEL_DELTA,
+                _ => -WHEEL_DELTA,
+            };
+            mwev.horizontal = code & 2 != 0;
+            mwev.modifiers = modifiers;
+            mwev.position = position;
+            return Some(InputEvent::MouseWheel(mwev));
+        }
+        if code & 32 != 0 {
+            let mut mmev = MouseMoveEvent::new();
+            mmev.modifiers = modifiers;
+            mmev.position = position;
+            return Some(InputEvent::MouseMove(mmev));
+        }
+
+        let button = match (code & 128 != 0, code & 3) {
+            (false, 0) => Some((1, KeyCode::LButton)),
+            (false, 1) => Some((3, KeyCode::MButton)),
+            (false, 2) => Some((2, KeyCode::RButton)),
+            (true, 0) => Some((4, KeyCode::XButton1)),
+            (true, 1) => Some((5, KeyCode::XButton2)),
+            _ => None,
+        };
+        // X10 reporting doesn't say which button was released, so the last press is used.
+        let pressed = pressed.unwrap_or(button.is_some());
+        let (button, key_code) = if pressed {
+            self.held_button = button;
+            button?
+        } else {
+            let released = button.or(self.held_button);
+            self.held_button = None;
+            released?
+        };
+
+        let mut mev = MouseEvent::new();
+        mev.button = button;
+        mev.key_code = key_code;
+        mev.modifiers = modifiers;
+        mev.position = position;
+        mev.pressed = pressed;
+        Some(mev.into())
+    }
+}
+
+fn char_event(character: char, mut modifiers: ControlKeyState) -> InputEvent {
+    let (key_code, character) = match character {
+        '\r' | '\n' => (KeyCode::Return, character),
+        '\t' => (KeyCode::Tab, character),
+        '\x08' | '\x7f' => (KeyCode::Backspace, '\x08'),
+        '\x1b' => (KeyCode::Escape, character),
+        ' ' => (KeyCode::Space, character),
+        '\0' => {
+            modifiers.LeftCtrlPressed = true;
+            (KeyCode::Space, character)
+        }
+        '\x01'..='\x1a' => {
+            modifiers.LeftCtrlPressed = true;
+            (KeyCode::from(character as u8 - 1 + b'A'), character)
+        }
+        '\x1c'..='\x1f' => {
+            modifiers.LeftCtrlPressed = true;
+            modifiers.ShiftPressed = character >= '\x1e';
+            let key_code = match character {
+                '\x1c' => KeyCode::Oem5,
+                '\x1d' => KeyCode::Oem6,
+                '\x1e' => KeyCode::Six,
+                _ => KeyCode::Minus,
+            };
+            (key_code, character)
+        }
+        _ => {
+            let (key_code, shift) = printable_key(character);
+            modifiers.ShiftPressed |= shift;
+            (key_code, character)
+        }
+    };
+    key_event(key_code, character, modifiers)
+}
+
+fn cursor_key(final_byte: u8, modifier: Option<u16>) -> Option<InputEvent> {
+    let key_code = match final_byte {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'F' => KeyCode::End,
+        b'H' => KeyCode::Home,
+        b'P' => KeyCode::F1,
+        b'Q' => KeyCode::F2,
+        b'R' => KeyCode::F3,
+        b'S' => KeyCode::F4,
+        _ => return None,
+    };
+    Some(special_key(key_code, modifier))
+}
+
+fn decode_char(bytes: &[u8]) -> Option<(char, usize)> {
+    let length = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return Some((REPLACEMENT_CHARACTER, 1)),
+    };
+    let available = length.min(bytes.len());
+    if bytes[1..available].iter().any(|byte| byte & 0xc0 != 0x80) {
+        return Some((REPLACEMENT_CHARACTER, 1));
+    }
+    if available < length {
+        return None;
+    }
+
+    match str::from_utf8(&bytes[..length]) {
+        Ok(text) => text.chars().next().map(|character| (character, length)),
+        Err(_) => Some((REPLACEMENT_CHARACTER, 1)),
+    }
+}
+
+// Returns the key code of F1 through F24, starting from zero.
+fn function_key(index: u16) -> KeyCode {
+    let first: u8 = KeyCode::F1.into();
+    KeyCode::from(first + index as u8)
+}
+
+fn key_event(key_code: KeyCode, character: char, modifiers: ControlKeyState) -> InputEvent {
+    let mut kev = KeyEvent::new();
+    kev.character = character;
+    kev.key_code = key_code;
+    kev.modifiers = modifiers;
+    kev.pressed = true;
+    kev.repeat_count = 1;
+    InputEvent::KeyDown(kev)
+}
+
+fn printable_key(character: char) -> (KeyCode, bool) {
+    let shifted = ")!@#$%^&*(";
+    match character {
+        'a'..='z' => (KeyCode::from(character as u8 - b'a' + b'A'), false),
+        'A'..='Z' => (KeyCode::from(character as u8), true),
+        '0'..='9' => (KeyCode::from(character as u8), false),
+        _ if shifted.contains(character) => {
+            let index = shifted.find(character).unwrap() as u8;
+            (KeyCode::from(b'0' + index), true)
+        }
+        ';' => (KeyCode::Oem1, false),
+        ':' => (KeyCode::Oem1, true),
+        '=' => (KeyCode::Plus, false),
+        '+' => (KeyCode::Plus, true),
+        ',' => (KeyCode::Comma, false),
+        '<' => (KeyCode::Comma, true),
+        '-' => (KeyCode::Minus, false),
+        '_' => (KeyCode::Minus, true),
+        '.' => (KeyCode::Period, false),
+        '>' => (KeyCode::Period, true),
+        '/' => (KeyCode::Oem2, false),
+        '?' => (KeyCode::Oem2, true),
+        '`' => (KeyCode::Oem3, false),
+        '~' => (KeyCode::Oem3, true),
+        '[' => (KeyCode::Oem4, false),
+        '{' => (KeyCode::Oem4, true),
+        '\\' => (KeyCode::Oem5, false),
+        '|' => (KeyCode::Oem5, true),
+        ']' => (KeyCode::Oem6, false),
+        '}' => (KeyCode::Oem6, true),
+        '\'' => (KeyCode::Oem7, false),
+        '"' => (KeyCode::Oem7, true),
+        _ => (KeyCode::None, false),
+    }
+}
+
+fn special_key(key_code: KeyCode, modifier: Option<u16>) -> InputEvent {
+    // The modifier parameter is one more than a bit mask of Shift, Alt and Ctrl.
+    let mask = modifier.unwrap_or(1).saturating_sub(1);
+    // Cursor and editing keys are enhanced keys, while function keys are not.
+    let value: u8 = key_code.into();
+    let first: u8 = KeyCode::F1.into();
+    let enhanced = value < first;
+    let modifiers = ControlKeyState {
+        EnhancedKey: enhanced,
+        LeftAltPressed: mask & 2 != 0,
+        LeftCtrlPressed: mask & 4 != 0,
+        ShiftPressed: mask & 1 != 0,
+        ..Default::default()
+    };
+    key_event(key_code, '\0', modifiers)
+}
+
+fn tilde_key(code: u16, modifier: Option<u16>) -> Option<InputEvent> {
+    let key_code = match code {
+        1 | 7 => KeyCode::Home,
+        2 => KeyCode::Insert,
+        3 => KeyCode::Delete,
+        4 | 8 => KeyCode::End,
+        5 => KeyCode::PageUp,
+        6 => KeyCode::PageDown,
+        11..=15 => function_key(code - 11),
+        17..=21 => function_key(code - 12),
+        23..=26 => function_key(code - 13),
+        28 | 29 => function_key(code - 14),
+        31..=34 => function_key(code - 15),
+        _ => return None,
+    };
+    Some(special_key(key_code, modifier))
+}
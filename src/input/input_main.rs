@@ -44,7 +44,7 @@ println!("input events available: {}", num);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_num_input_events() -> WinResult<u32> {
-    console::num_input_events()
+    ConsoleInput::stdin()?.get_num_input_events()
 }
 /**
 Returns the number of mouse buttons available for the console.
@@ -171,10 +171,7 @@ println!("{}", event);
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn start() -> WinResult<InputContext> {
-    let mut ctx = InputContext::new(console::get_input_mode()?);
-    ctx.reset();
-    console::flush_input()?;
-    Ok(ctx)
+    ConsoleInput::stdin()?.start()
 }
 /**
 Adds an input event directly to the input buffer.
@@ -207,23 +204,7 @@ pub fn write(
     event: impl Into<InputEvent>,
     button_status: impl Into<Option<[bool; 5]>>,
 ) -> WinResult<()> {
-    let event = event.into();
-    if event == InputEvent::None {
-        return Ok(());
-    }
-
-    let button_status = match button_status.into() {
-        None => {
-            let mut status = [false; 5];
-            for i in 0..5 {
-                status[i] = console::get_key_state(BUTTON_VIRTUAL[i] as u32);
-            }
-            status
-        }
-        Some(status) => status,
-    };
-
-    console::write_input(vec![self::convert_to_record(event, button_status)])
+    ConsoleInput::stdin()?.write(event, button_status)
 }
 
 pub(crate) fn convert_events(
@@ -233,7 +214,9 @@ pub(crate) fn convert_events(
     let mut ret = Vec::new();
     let button_status = &mut ctx.button_status;
     let held_keys = &mut ctx.held_keys;
+    let key_decoders = &mut ctx.key_decoders;
     let repeat_enabled = ctx.repeat_enabled;
+    let vt_parser = &mut ctx.vt_parser;
     for record in records {
         let ev = record.Event;
 
@@ -288,10 +271,26 @@ pub(crate) fn convert_events(
                 }
 
                 let key_code = KeyCode::from(virt);
-                let character = unsafe { *(ker.uChar.AsciiChar()) };
+                let unit = unsafe { *(ker.uChar.UnicodeChar()) };
                 let status = ker.bKeyDown != 0;
+                let decoder = &mut key_decoders[status as usize];
+                if let Some(ref mut parser) = *vt_parser {
+                    // Sequences are delivered as the characters of KeyDown records.
+                    if status && unit != 0 {
+                        if let Some(character) = decoder.push(unit) {
+                            let mut bytes = [0; 4];
+                            ret.extend(parser.feed(character.encode_utf8(&mut bytes).as_bytes()));
+                        }
+                    }
+                    continue;
+                }
+                // The first half of a surrogate pair is held until the second completes it.
+                let character = match decoder.push(unit) {
+                    Some(character) => character,
+                    None => continue,
+                };
                 let mut kev = KeyEvent::new();
-                kev.character = character as u8 as char;
+                kev.character = character;
                 kev.key_code = key_code;
                 kev.modifiers = ControlKeyState::from(ker.dwControlKeyState as u16);
                 kev.pressed = status;
@@ -341,6 +340,33 @@ pub(crate) fn convert_events(
     ret
 }
 
+pub(crate) fn convert_to_records(event: InputEvent, button_status: [bool; 5]) -> Vec<INPUT_RECORD> {
+    let record = self::convert_to_record(event, button_status);
+    match event {
+        InputEvent::KeyHeld(kev) | InputEvent::KeyDown(kev) | InputEvent::KeyUp(kev) => {
+            let mut units = [0; 2];
+            kev.character
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|unit| {
+                    let mut record = record;
+                    unsafe {
+                        *record.Event.KeyEvent_mut().uChar.UnicodeChar_mut() = *unit;
+                    }
+                    record
+                })
+                .collect()
+        }
+        _ => vec![record],
+    }
+}
+pub(crate) fn get_button_status() -> [bool; 5] {
+    let mut status = [false; 5];
+    for i in 0..5 {
+        status[i] = console::get_key_state(BUTTON_VIRTUAL[i] as u32);
+    }
+    status
+}
 fn convert_to_record(event: InputEvent, button_status: [bool; 5]) -> INPUT_RECORD {
     let mut record: INPUT_RECORD = unsafe { mem::zeroed() };
     let mut ev = record.Event;
@@ -358,15 +384,12 @@ fn convert_to_record(event: InputEvent, button_status: [bool; 5]) -> INPUT_RECOR
             let key_code: u8 = kev.key_code.into();
             let control_key_state: u16 = kev.modifiers.into();
             unsafe {
-                let mut u_char: KEY_EVENT_RECORD_uChar = mem::zeroed();
-                *u_char.AsciiChar_mut() = kev.character as i8;
-
                 *ev.KeyEvent_mut() = KEY_EVENT_RECORD {
                     bKeyDown: bool_to_num!(kev.pressed),
                     wRepeatCount: kev.repeat_count,
                     wVirtualKeyCode: key_code as u16,
                     wVirtualScanCode: kev.scan_code,
-                    uChar: u_char,
+                    uChar: mem::zeroed(),
                     dwControlKeyState: control_key_state as u32,
                 };
             }
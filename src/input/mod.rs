@@ -1,23 +1,30 @@
+#[cfg(windows)]
 use super::console;
 use super::console::*;
+#[cfg(windows)]
 use super::errors::*;
 
+#[cfg(windows)]
+use std::mem;
 use std::{
     fmt,
     fmt::{Display, Formatter},
-    mem,
 };
 
+#[cfg(windows)]
 use winapi::um::wincon::{
-    KEY_EVENT_RECORD_uChar, COORD, FOCUS_EVENT, FOCUS_EVENT_RECORD, INPUT_RECORD, KEY_EVENT,
+    COORD, FOCUS_EVENT, FOCUS_EVENT_RECORD, INPUT_RECORD, KEY_EVENT,
     KEY_EVENT_RECORD, MOUSE_EVENT, MOUSE_EVENT_RECORD, MOUSE_HWHEELED, MOUSE_MOVED, MOUSE_WHEELED,
     WINDOW_BUFFER_SIZE_EVENT, WINDOW_BUFFER_SIZE_RECORD,
 };
 
 mod etc;
+#[cfg(windows)]
 mod input_main;
 
 pub use self::etc::*;
+#[cfg(windows)]
 pub use self::input_main::*;
 
+#[cfg(windows)]
 const BUTTON_VIRTUAL: [u8; 5] = [1, 2, 4, 5, 6];
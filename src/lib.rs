@@ -1,4 +1,3 @@
-#![cfg(windows)]
 #![warn(missing_docs)]
 /*!
 This crate provides a wrapper for console-related functions in the Windows API.
@@ -19,26 +18,45 @@ extern crate winconsole;
 ---
 
 There are a few optional features:
+* `async` - Includes asynchronous streams of input and Ctrl events, which can be used with any runtime.
 * `cgmath` - Includes conversions for the [cgmath](https://github.com/rustgd/cgmath) Vector2 type.
 * `input` - Includes input-related functions.
+* `recording` - Includes recording and replaying of input events. This enables `input` and `serde`.
 * `serde` - Support for [serde](https://serde.rs/).
+* `tokio` - Includes adapters for [tokio](https://tokio.rs/): a cancel-safe future of the next input
+  event for `tokio::select!`. This enables `async`, whose streams also work with `tokio_stream`.
 * `window` - Includes window-related functions.
 
 These features must be added to `Cargo.toml`:
 ```toml
 [dependencies.winconsole]
 version = "0.11"
-features = ["cgmath", "input", "serde", "window"]
+features = ["async", "cgmath", "input", "recording", "serde", "tokio", "window"]
 ```
+
+---
+
+The crate can be built on any platform. Functions which call the Windows API are only
+available on Windows, while structs, enums and their serde implementations are available
+everywhere. On other platforms, functions in the `console` module return an error until a
+backend, such as an in-memory console, is installed with `console::set_backend`.
 */
 #[cfg(feature = "cgmath")]
 extern crate cgmath;
+#[cfg(feature = "recording")]
+extern crate ciborium;
+#[cfg(all(windows, feature = "async"))]
+extern crate futures_core;
+#[cfg(windows)]
 #[macro_use]
 extern crate lazy_static;
 extern crate rgb;
 #[cfg(feature = "serde")]
 #[macro_use]
 extern crate serde;
+#[cfg(feature = "recording")]
+extern crate serde_json;
+#[cfg(windows)]
 extern crate winapi;
 
 #[macro_use]
@@ -51,6 +69,8 @@ pub mod errors;
 /// Contains input-related functions, structs, and enums.
 #[cfg(feature = "input")]
 pub mod input;
+/// Contains functions for converting between strings and UTF-16 buffers.
+pub mod utf16;
 /// Contains window-related functions, structs, and enums.
 #[cfg(feature = "window")]
 pub mod window;
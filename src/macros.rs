@@ -1,3 +1,4 @@
+#[cfg(windows)]
 macro_rules! bool_to_num {
     ($x:expr) => {
         if $x {
@@ -7,12 +8,14 @@ macro_rules! bool_to_num {
         }
     };
 }
+#[cfg(windows)]
 macro_rules! buf {
     ($size:expr) => {{
         let vec = vec![0; $size];
         vec.into_boxed_slice()
     }};
 }
+#[cfg(windows)]
 macro_rules! buf_to_str {
     ($buf:expr) => {{
         let mut vec: Vec<u8> = vec![];
@@ -25,7 +28,7 @@ macro_rules! buf_to_str {
         String::from_utf8(vec)?
     }};
 }
-#[cfg(feature = "input")]
+#[cfg(all(windows, feature = "input"))]
 macro_rules! buf_to_vec {
     ($buf:expr, $len:expr) => {{
         let mut result = Vec::new();
@@ -37,6 +40,44 @@ macro_rules! buf_to_vec {
     }};
 }
 /**
+Creates a [`StyledString`] from markup with inline style tags, such as
+`"{fg:red}error{/} in {bg:blue}{}{/}"`. Each `{}` placeholder is replaced by the next
+argument, which may be any value implementing `Display`.
+See [`StyledString::parse`] for the markup syntax.
+
+Unlike `format!`, the markup is parsed at runtime, so this returns a `WinResult<StyledString>`.
+
+# Examples
+```
+#[macro_use] extern crate winconsole;
+
+fn main() {
+    # #[cfg(not(windows))]
+    # winconsole::console::set_backend(winconsole::console::MemoryConsole::new(80, 25));
+    let count = 3;
+    let message = cformat!("{fg:yellow}warning{/}: {} files were skipped", count).unwrap();
+    assert_eq!(message.to_string(), "warning: 3 files were skipped");
+    message.write().unwrap();
+    println!();
+}
+```
+
+# Errors
+* [`ArgumentError`]: Returned if the markup is invalid, or if the number of placeholders
+  does not match the number of arguments.
+
+[`ArgumentError`]: errors/enum.WinError.html#Argument.v
+[`StyledString`]: console/struct.StyledString.html
+[`StyledString::parse`]: console/struct.StyledString.html#method.parse
+*/
+#[macro_export]
+macro_rules! cformat {
+    ($fmt:expr) => ($crate::console::StyledString::parse($fmt, &[]));
+    ($fmt:expr, $($arg:expr),+ $(,)*) => (
+        $crate::console::StyledString::parse($fmt, &[$(&$arg as &dyn (::std::fmt::Display)),+])
+    );
+}
+/**
 Prints a colored message to the console.
 This has a side effect of flushing the console output.
 
@@ -52,20 +93,20 @@ fn main() {
 }
 ```
 
+If the foreground color cannot be changed, the message is printed without color.
+The previous foreground color is restored even if printing panics.
+
 # Panics
-Panics if foreground color cannot be retrieved/set, flushing console output fails,
-or if printing fails.
+Panics if printing fails.
 */
 #[macro_export]
 macro_rules! cprint {
     ($color:expr, $($arg:tt)*) => {
 		{
 			use $crate::console;
-			let old_color = console::get_foreground_color().unwrap();
-			console::set_foreground_color($color).unwrap();
+			let _guard = console::ColorGuard::foreground($color).ok();
 			print!($($arg)*);
-			console::flush_output().unwrap();
-			console::set_foreground_color(old_color).unwrap();
+			console::flush_output().unwrap_or(());
 		}
 	}
 }
@@ -88,9 +129,11 @@ fn main() {
 }
 ```
 
+If the foreground color cannot be changed, the message is printed without color.
+The previous foreground color is restored even if printing panics.
+
 # Panics
-Panics if foreground color cannot be retrieved/set, flushing console output fails,
-or if printing fails.
+Panics if printing fails.
 */
 #[macro_export]
 macro_rules! cprintln {
@@ -312,6 +355,7 @@ macro_rules! flags {
 		}
 	});
 }
+#[cfg(windows)]
 macro_rules! handle {
     ($x:expr) => {{
         let handle = processenv::GetStdHandle($x);
@@ -321,6 +365,7 @@ macro_rules! handle {
         handle
     }};
 }
+#[cfg(any(windows, feature = "serde"))]
 macro_rules! make_colorref {
     ($x:expr) => {
         u32::from($x.r) | (u32::from($x.g) << 8) | (u32::from($x.b) << 16)
@@ -335,6 +380,7 @@ macro_rules! make_rgb {
         }
     };
 }
+#[cfg(windows)]
 macro_rules! os_err {
     () => {{
         use std::io;
@@ -363,42 +409,18 @@ macro_rules! os_err {
         }
     };
 }
-macro_rules! str_to_buf {
-	(@inner $s:expr, $type:ty) => {
-		{
-			let vec: Vec<$type> = String::from($s)
-				.as_bytes()
-				.iter()
-				.map(|c| *c as $type)
-				.collect();
-			vec.into_boxed_slice()
-		}
-	};
-	(@inner $s:expr, $size:expr, $type:ty) => {
-		{
-			let mut buffer: [$type; $size] = [0; $size];
-			for (chr, val) in $s.as_bytes().iter().zip(buffer.iter_mut()) {
-				*val = *chr as $type;
-			}
-			buffer
-		}
-	};
-
-	($s:expr) => (str_to_buf!(@inner $s, CHAR));
-	($s:expr, $size:expr) => (str_to_buf!(@inner $s, $size, CHAR));
-}
-macro_rules! str_to_buf_w {
-	($s:expr) => (str_to_buf!(@inner $s, WCHAR));
-	($s:expr, $size:expr) => (str_to_buf!(@inner $s, $size, WCHAR));
-}
 macro_rules! throw_err {
     ($err:expr) => {
         Err($crate::errors::WinError::from($err))?;
     };
 }
-#[cfg(feature = "window")]
+#[cfg(all(windows, feature = "window"))]
 macro_rules! window_handle {
-    () => {
-        wincon::GetConsoleWindow()
-    };
+    () => {{
+        let handle = wincon::GetConsoleWindow();
+        if handle.is_null() {
+            throw_err!($crate::errors::InvalidHandleError::default());
+        }
+        handle
+    }};
 }
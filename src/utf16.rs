@@ -0,0 +1,213 @@
+use errors::WinResult;
+use std::char::{self, REPLACEMENT_CHARACTER};
+
+/// Decodes a sequence of UTF-16 code units one unit at a time.
+///
+/// This is used where a character may be split over several buffers, such as a
+/// surrogate pair which is delivered as two separate key events.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Utf16Decoder {
+    high: Option<u16>,
+}
+
+impl Utf16Decoder {
+    /**
+    Returns an empty Utf16Decoder.
+    */
+    pub fn new() -> Utf16Decoder {
+        Utf16Decoder { high: None }
+    }
+
+    /**
+    Returns a boolean representing whether or not the decoder is waiting for
+    the second half of a surrogate pair.
+    */
+    pub fn is_pending(&self) -> bool {
+        self.high.is_some()
+    }
+    /**
+    Pushes a code unit to the decoder, returning the character it completes.
+    None is returned if the unit is the first half of a surrogate pair.
+
+    A high surrogate which is not followed by a low surrogate is discarded, and
+    a low surrogate which is not preceded by a high surrogate is decoded as
+    U+FFFD REPLACEMENT CHARACTER.
+
+    # Arguments
+    * `unit` - The code unit to push.
+
+    # Examples
+    ```
+    # extern crate winconsole;
+    # use winconsole::utf16::Utf16Decoder;
+    # fn main() {
+    let mut decoder = Utf16Decoder::new();
+    assert_eq!(decoder.push(0xD83D), None);
+    assert_eq!(decoder.push(0xDE00), Some('😀'));
+    assert_eq!(decoder.push(0x41), Some('A'));
+    # }
+    ```
+    */
+    pub fn push(&mut self, unit: u16) -> Option<char> {
+        match unit {
+            0xD800..=0xDBFF => {
+                self.high = Some(unit);
+                None
+            }
+            0xDC00..=0xDFFF => match self.high.take() {
+                Some(high) => {
+                    let value =
+                        0x10000 + ((u32::from(high) - 0xD800) << 10) + (u32::from(unit) - 0xDC00);
+                    char::from_u32(value)
+                }
+                None => Some(REPLACEMENT_CHARACTER),
+            },
+            _ => {
+                self.high = None;
+                char::from_u32(u32::from(unit))
+            }
+        }
+    }
+    /**
+    Discards a pending high surrogate.
+    */
+    pub fn reset(&mut self) {
+        self.high = None;
+    }
+}
+
+/**
+Decodes a UTF-16 buffer into a string.
+Decoding stops at the first null code unit, if there is one.
+
+# Arguments
+* `buffer` - The buffer to decode.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::utf16;
+# fn main() {
+let string = utf16::decode(&[0x48, 0x69, 0xD83D, 0xDC4B, 0, 0x21]).unwrap();
+assert_eq!(string, "Hi👋");
+# }
+```
+
+# Errors
+* [`FromUtf16Error`]: Returned if the buffer contains an unpaired surrogate.
+
+[`FromUtf16Error`]: ../errors/enum.WinError.html#FromUtf16.v
+*/
+pub fn decode(buffer: &[u16]) -> WinResult<String> {
+    Ok(String::from_utf16(until_nul(buffer))?)
+}
+/**
+Decodes a UTF-16 buffer into a string, replacing unpaired surrogates with
+U+FFFD REPLACEMENT CHARACTER.
+Decoding stops at the first null code unit, if there is one.
+
+# Arguments
+* `buffer` - The buffer to decode.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::utf16;
+# fn main() {
+let string = utf16::decode_lossy(&[0x61, 0xD83D, 0x62]);
+assert_eq!(string, "a\u{FFFD}b");
+# }
+```
+*/
+pub fn decode_lossy(buffer: &[u16]) -> String {
+    String::from_utf16_lossy(until_nul(buffer))
+}
+/**
+Encodes a string as UTF-16.
+The returned buffer is not null-terminated.
+
+# Arguments
+* `string` - The string to encode.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::utf16;
+# fn main() {
+assert_eq!(utf16::encode("a😀"), vec![0x61, 0xD83D, 0xDE00]);
+# }
+```
+*/
+pub fn encode(string: &str) -> Vec<u16> {
+    string.encode_utf16().collect()
+}
+/**
+Encodes a string as UTF-16 into a fixed-size buffer, followed by a null code unit.
+The string is truncated to fit the buffer, without splitting a surrogate pair.
+Returns the amount of code units written, excluding the null code unit.
+
+# Arguments
+* `string` - The string to encode.
+* `buffer` - The buffer to write to.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::utf16;
+# fn main() {
+let mut buffer = [0xFFFF; 4];
+assert_eq!(utf16::encode_into("a😀b", &mut buffer), 3);
+assert_eq!(buffer, [0x61, 0xD83D, 0xDE00, 0]);
+
+assert_eq!(utf16::encode_into("ab😀", &mut buffer), 2);
+assert_eq!(buffer, [0x61, 0x62, 0, 0]);
+# }
+```
+*/
+pub fn encode_into(string: &str, buffer: &mut [u16]) -> usize {
+    let capacity = match buffer.len() {
+        0 => return 0,
+        len => len - 1,
+    };
+
+    let mut length = 0;
+    let mut units = [0; 2];
+    for chr in string.chars() {
+        let encoded = chr.encode_utf16(&mut units);
+        if length + encoded.len() > capacity {
+            break;
+        }
+        buffer[length..length + encoded.len()].copy_from_slice(encoded);
+        length += encoded.len();
+    }
+
+    for unit in buffer[length..].iter_mut() {
+        *unit = 0;
+    }
+    length
+}
+/**
+Encodes a string as null-terminated UTF-16.
+
+# Arguments
+* `string` - The string to encode.
+
+# Examples
+```
+# extern crate winconsole;
+# use winconsole::utf16;
+# fn main() {
+assert_eq!(utf16::encode_with_nul("ab"), vec![0x61, 0x62, 0]);
+# }
+```
+*/
+pub fn encode_with_nul(string: &str) -> Vec<u16> {
+    string.encode_utf16().chain(Some(0)).collect()
+}
+
+fn until_nul(buffer: &[u16]) -> &[u16] {
+    match buffer.iter().position(|unit| *unit == 0) {
+        Some(index) => &buffer[..index],
+        None => buffer,
+    }
+}
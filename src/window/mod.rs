@@ -1,14 +1,22 @@
+#[cfg(windows)]
 use std::{mem, ptr};
 
+#[cfg(windows)]
 use winapi::shared::windef::{HWND, POINT, RECT};
+#[cfg(windows)]
 use winapi::um::winuser::{FLASHWINFO, WINDOWPLACEMENT};
+#[cfg(windows)]
 use winapi::um::{wincon, winuser};
 
+#[cfg(windows)]
 use super::console::Vector2;
+#[cfg(windows)]
 use super::errors::*;
 
 mod etc;
+#[cfg(windows)]
 mod window_main;
 
 pub use self::etc::*;
+#[cfg(windows)]
 pub use self::window_main::*;
@@ -16,8 +16,10 @@ window::activate(true).unwrap();
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn activate(display: bool) -> WinResult<()> {
@@ -68,7 +70,10 @@ pub fn flash(info: FlashInfo) {
     }
 
     unsafe {
-        let handle = window_handle!();
+        let handle = wincon::GetConsoleWindow();
+        if handle.is_null() {
+            return;
+        }
         let mut info: FLASHWINFO = mem::zeroed();
         info.cbSize = mem::size_of::<FLASHWINFO>() as u32;
         info.dwFlags = flags;
@@ -122,8 +127,10 @@ println!("State: {:?}", window::get_display_state().unwrap());
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_display_state() -> WinResult<DisplayState> {
@@ -147,8 +154,10 @@ println!("Window position: {:?}", position);
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_position() -> WinResult<Vector2<i32>> {
@@ -169,8 +178,10 @@ println!("Window size: {:?}", size);
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn get_size() -> WinResult<Vector2<i32>> {
@@ -208,7 +219,10 @@ println!("Active? {}", window::is_active());
 ```
 */
 pub fn is_active() -> bool {
-    unsafe { winuser::GetForegroundWindow() == window_handle!() }
+    unsafe {
+        let handle = wincon::GetConsoleWindow();
+        !handle.is_null() && winuser::GetForegroundWindow() == handle
+    }
 }
 /**
 Is the window maximized?
@@ -223,8 +237,10 @@ println!("Maximized? {}", window::is_maximized().unwrap());
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn is_maximized() -> WinResult<bool> {
@@ -243,8 +259,10 @@ println!("Minimized? {}", window::is_minimized().unwrap());
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn is_minimized() -> WinResult<bool> {
@@ -266,8 +284,8 @@ println!("Visible? {}", window::is_visible());
 */
 pub fn is_visible() -> bool {
     unsafe {
-        let handle = window_handle!();
-        winuser::IsWindowVisible(handle) != 0
+        let handle = wincon::GetConsoleWindow();
+        !handle.is_null() && winuser::IsWindowVisible(handle) != 0
     }
 }
 /**
@@ -397,8 +415,10 @@ window::set_position(position.x + 1, position.y + 1).unwrap();
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_position(x: i32, y: i32) -> WinResult<()> {
@@ -418,8 +438,10 @@ window::set_size(size.x + 1, size.y + 1).unwrap();
 ```
 
 # Errors
+* [`InvalidHandleError`]: Returned if the process is not attached to a console.
 * [`IoError`]: Returned if an OS error occurs.
 
+[`InvalidHandleError`]: ../errors/enum.WinError.html#InvalidHandle.v
 [`IoError`]: ../errors/enum.WinError.html#Io.v
 */
 pub fn set_size(width: i32, height: i32) -> WinResult<()> {
@@ -505,7 +527,9 @@ fn set_window_info(
 }
 fn set_window_show(value: i32) {
     unsafe {
-        let handle = window_handle!();
-        winuser::ShowWindow(handle, value);
+        let handle = wincon::GetConsoleWindow();
+        if !handle.is_null() {
+            winuser::ShowWindow(handle, value);
+        }
     }
 }
@@ -0,0 +1,67 @@
+#![cfg(test)]
+extern crate winconsole;
+
+use winconsole::console::{Cell, CellBuffer, ConsoleColor, Rect, Vector2};
+
+fn cell(character: char) -> Cell {
+    Cell::new(character, ConsoleColor::Gray, ConsoleColor::Black)
+}
+
+#[test]
+fn attributes() {
+    let mut cell = Cell::new('a', ConsoleColor::Yellow, ConsoleColor::DarkRed);
+    cell.flags.ReverseVideo = true;
+    assert_eq!(cell.get_attributes(), 0x404E);
+    assert_eq!(Cell::from_attributes('a', 0x404E), cell);
+}
+#[test]
+fn blit() {
+    let mut source = CellBuffer::new(3, 3);
+    source.fill(Rect::new(1, 1, 2, 2), cell('s'));
+    let mut buffer = CellBuffer::new(4, 2);
+    buffer.blit(&source, Rect::new(1, 0, 2, 2), Vector2::new(2, 0));
+
+    assert_eq!(buffer.lines(), vec!["   s", "   s"]);
+    buffer.blit(&source, Rect::new(5, 5, 9, 9), Vector2::new(0, 0));
+    assert_eq!(buffer.lines(), vec!["   s", "   s"]);
+}
+#[test]
+fn compare() {
+    let old = CellBuffer::new(4, 2);
+    let mut new = old.clone();
+    assert!(new.compare(&old).is_empty());
+
+    *new.get_mut(3, 0).unwrap() = cell('x');
+    new.fill(Rect::new(1, 0, 1, 1), cell('y'));
+    assert_eq!(
+        new.compare(&old),
+        vec![Rect::new(0, 3, 3, 0), Rect::new(1, 0, 1, 1)]
+    );
+
+    let small = CellBuffer::new(3, 1);
+    assert_eq!(
+        old.compare(&small),
+        vec![Rect::new(0, 3, 3, 0), Rect::new(1, 0, 3, 1)]
+    );
+}
+#[test]
+fn fill() {
+    let mut buffer = CellBuffer::new(3, 2);
+    buffer.fill(Rect::new(1, 1, 10, 10), cell('f'));
+    assert_eq!(buffer.lines(), vec!["", " ff"]);
+    assert_eq!(buffer.get(3, 0), None);
+    assert_eq!(buffer.get_row(1).unwrap()[2], cell('f'));
+}
+#[test]
+fn slice() {
+    let mut buffer = CellBuffer::new(4, 3);
+    buffer.fill(Rect::new(1, 1, 3, 2), cell('s'));
+
+    let slice = buffer.slice(Rect::new(1, 2, 5, 5));
+    assert_eq!(slice.get_size(), Vector2::new(2, 2));
+    assert_eq!(slice.lines(), vec!["ss", "ss"]);
+    assert_eq!(
+        buffer.slice(Rect::new(3, 0, 3, 3)).get_size(),
+        Vector2::new(0, 0)
+    );
+}
@@ -0,0 +1,133 @@
+#![cfg(test)]
+extern crate rgb;
+extern crate winconsole;
+
+use rgb::RGB8;
+use winconsole::console::{self, Color, ColorMetric, ConsoleColor, MemoryConsole};
+
+fn mapping() -> [RGB8; 16] {
+    let mut mapping = [RGB8 { r: 0, g: 0, b: 0 }; 16];
+    for (i, value) in mapping.iter_mut().enumerate() {
+        let level = if i & 0x8 != 0 { 255 } else { 128 };
+        *value = RGB8 {
+            r: if i & 0x4 != 0 { level } else { 0 },
+            g: if i & 0x2 != 0 { level } else { 0 },
+            b: if i & 0x1 != 0 { level } else { 0 },
+        };
+    }
+    mapping[7] = RGB8 {
+        r: 192,
+        g: 192,
+        b: 192,
+    };
+    mapping[8] = RGB8 {
+        r: 128,
+        g: 128,
+        b: 128,
+    };
+    mapping
+}
+
+#[test]
+fn nearest() {
+    let mapping = mapping();
+    let metrics = [
+        ColorMetric::Euclidean,
+        ColorMetric::Redmean,
+        ColorMetric::Cie76,
+    ];
+    for metric in &metrics {
+        let navy = Color::Rgb(RGB8 {
+            r: 10,
+            g: 5,
+            b: 120,
+        });
+        assert_eq!(
+            navy.to_console_color(&mapping, *metric),
+            ConsoleColor::DarkBlue
+        );
+        let silver = Color::Indexed(250);
+        assert_eq!(
+            silver.to_console_color(&mapping, *metric),
+            ConsoleColor::Gray
+        );
+    }
+
+    assert_eq!(
+        Color::Indexed(9).to_console_color(&mapping, ColorMetric::Cie76),
+        ConsoleColor::Red
+    );
+    assert_eq!(
+        Color::Named(ConsoleColor::Teal).to_console_color(&mapping, ColorMetric::Cie76),
+        ConsoleColor::Teal
+    );
+}
+#[test]
+fn palette() {
+    let mapping = mapping();
+    assert_eq!(Color::Indexed(1).to_rgb(&mapping), mapping[4]);
+    assert_eq!(
+        Color::Indexed(16 + 36 * 5 + 6 * 2 + 1).to_rgb(&mapping),
+        RGB8 {
+            r: 255,
+            g: 135,
+            b: 95,
+        }
+    );
+    assert_eq!(
+        Color::Indexed(255).to_rgb(&mapping),
+        RGB8 {
+            r: 238,
+            g: 238,
+            b: 238,
+        }
+    );
+}
+#[test]
+fn set_foreground() {
+    let memory = MemoryConsole::new(10, 3);
+    console::set_backend(memory);
+    console::set_color_mapping(&mapping()).unwrap();
+
+    let mut mode = console::get_output_mode().unwrap();
+    mode.VirtualTerminalProcessing = false;
+    console::set_output_mode(mode).unwrap();
+    console::set_foreground(RGB8 {
+        r: 240,
+        g: 250,
+        b: 10,
+    })
+    .unwrap();
+    assert_eq!(
+        console::get_foreground_color().unwrap(),
+        ConsoleColor::Yellow
+    );
+    console::set_background(Color::Named(ConsoleColor::DarkRed)).unwrap();
+    assert_eq!(
+        console::get_background_color().unwrap(),
+        ConsoleColor::DarkRed
+    );
+
+    console::reset_backend();
+}
+#[test]
+fn vt_sequence() {
+    assert_eq!(
+        Color::Named(ConsoleColor::DarkBlue).get_vt_sequence(false),
+        "\x1b[34m"
+    );
+    assert_eq!(
+        Color::Named(ConsoleColor::White).get_vt_sequence(true),
+        "\x1b[107m"
+    );
+    assert_eq!(Color::Indexed(42).get_vt_sequence(false), "\x1b[38;5;42m");
+    assert_eq!(
+        Color::Rgb(RGB8 {
+            r: 255,
+            g: 128,
+            b: 0,
+        })
+        .get_vt_sequence(true),
+        "\x1b[48;2;255;128;0m"
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(test)]
+extern crate winconsole;
+
+use std::io::Write;
+#[cfg(not(windows))]
+use std::io::{self, IsTerminal};
+use winconsole::console::{
+    self, ColorMode, ColorWriter, ConsoleColor, MemoryConsole, TextAttributes,
+};
+
+#[test]
+fn ansi() {
+    let mut writer = ColorWriter::new(Vec::new(), ColorMode::Ansi).unwrap();
+    assert_eq!(
+        writer.get_colors(),
+        (ConsoleColor::Gray, ConsoleColor::Black)
+    );
+    writer
+        .set_colors(ConsoleColor::Blue, ConsoleColor::DarkRed)
+        .unwrap();
+    write!(writer, "a").unwrap();
+    writer.set_background(ConsoleColor::DarkRed).unwrap();
+    writer.set_foreground(ConsoleColor::Gray).unwrap();
+    write!(writer, "b").unwrap();
+    writer.reset().unwrap();
+    assert_eq!(
+        writer.get_colors(),
+        (ConsoleColor::Gray, ConsoleColor::Black)
+    );
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        "\x1b[94m\x1b[41ma\x1b[37mb\x1b[0m"
+    );
+}
+#[test]
+fn console_mode() {
+    let memory = MemoryConsole::new(10, 2);
+    console::set_backend(memory.clone());
+    let mut initial = TextAttributes::new(ConsoleColor::White, ConsoleColor::Black);
+    initial.flags.Underscore = true;
+    console::set_text_attributes(initial).unwrap();
+
+    let mut writer = ColorWriter::new(Vec::new(), ColorMode::Console).unwrap();
+    assert_eq!(writer.get_colors(), initial.get_colors());
+    writer.set_foreground(ConsoleColor::Green).unwrap();
+    write!(writer, "text").unwrap();
+
+    let current = console::get_text_attributes().unwrap();
+    assert_eq!(
+        current.get_colors(),
+        (ConsoleColor::Green, ConsoleColor::Black)
+    );
+    assert!(current.flags.Underscore);
+    assert_eq!(writer.get_ref(), b"text");
+
+    writer.reset().unwrap();
+    assert_eq!(console::get_text_attributes().unwrap(), initial);
+    console::reset_backend();
+}
+#[cfg(not(windows))]
+#[test]
+fn detect() {
+    // The default backend reports what the standard output is really attached to.
+    let terminal = io::stdout().is_terminal();
+    let expected = if terminal {
+        ColorMode::Ansi
+    } else {
+        ColorMode::Plain
+    };
+    assert_eq!(ColorWriter::stdout().unwrap().get_mode(), expected);
+    assert_eq!(console::get_capabilities().virtual_terminal, terminal);
+}
+#[test]
+fn plain() {
+    let mut writer = ColorWriter::new(Vec::new(), ColorMode::Plain).unwrap();
+    writer.set_foreground(ConsoleColor::Red).unwrap();
+    writeln!(writer, "clean").unwrap();
+    writer.reset().unwrap();
+    assert_eq!(writer.get_mode(), ColorMode::Plain);
+    assert_eq!(writer.into_inner(), b"clean\n");
+}
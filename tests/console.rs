@@ -1,8 +1,20 @@
 #![cfg(test)]
+#![cfg(windows)]
 extern crate winconsole;
 
-use winconsole::console::{self, ConsoleColor, Vector2};
+use std::process::Command;
+use winconsole::console::{
+    self, ConsoleColor, ConsoleInput, ConsoleOutput, CtrlEvent, ProcessGroup, PseudoConsole,
+    ScreenBuffer, Vector2,
+};
 
+#[test]
+fn attached_processes() {
+    assert!(console::is_attached());
+    let processes = console::get_attached_processes().unwrap();
+    assert!(processes.contains(&std::process::id()));
+    assert!(console::alloc().is_err());
+}
 #[test]
 fn beep() {
     console::beep(440, 1000);
@@ -22,6 +34,38 @@ fn color_mapping() {
     console::map_color(ConsoleColor::Black, old_black).unwrap();
 }
 #[test]
+fn console_handles() {
+    let input = ConsoleInput::open().unwrap();
+    assert_eq!(
+        input.get_input_mode().unwrap(),
+        console::get_input_mode().unwrap()
+    );
+
+    let output = ConsoleOutput::open().unwrap();
+    let position = console::get_cursor_position().unwrap();
+    output.set_cursor_position(3, 2).unwrap();
+    assert_eq!(console::get_cursor_position().unwrap(), Vector2::new(3, 2));
+    output.set_cursor_position(position.x, position.y).unwrap();
+    assert_eq!(
+        output.get_buffer_size().unwrap(),
+        console::get_buffer_size().unwrap()
+    );
+}
+#[test]
+fn ctrl_handlers() {
+    let token = console::add_ctrl_handler(|event| event == CtrlEvent::CtrlBreak).unwrap();
+    let (events, channel_token) = console::ctrl_events().unwrap();
+    assert!(events.try_recv().is_err());
+    console::remove_ctrl_handler(channel_token).unwrap();
+    console::remove_ctrl_handler(token).unwrap();
+}
+#[test]
+#[cfg(feature = "async")]
+fn ctrl_event_stream() {
+    let (_events, token) = console::ctrl_event_stream().unwrap();
+    console::remove_ctrl_handler(token).unwrap();
+}
+#[test]
 fn cursor_position() {
     let position = console::get_cursor_position().unwrap();
     console::set_cursor_position(10, 10).unwrap();
@@ -93,6 +137,38 @@ fn input_mode_fail() {
     console::set_input_mode(input_mode).unwrap();
 }
 
+#[test]
+fn process_group() {
+    let mut command = Command::new("cmd.exe");
+    let mut child = console::spawn_process_group(command.args(["/c", "pause>nul"])).unwrap();
+    assert_eq!(ProcessGroup::from(&child), ProcessGroup::Id(child.id()));
+    assert!(console::generate_ctrl_event(CtrlEvent::Close, &child).is_err());
+    console::generate_ctrl_event(CtrlEvent::CtrlBreak, &child).unwrap();
+    assert!(!child.wait().unwrap().success());
+}
+#[test]
+fn pseudo_console() {
+    let pty = PseudoConsole::spawn("cmd.exe /c echo hello", 40, 10).unwrap();
+    assert_eq!(pty.wait().unwrap(), 0);
+    assert_eq!(pty.try_wait().unwrap(), Some(0));
+    assert!(pty.resize(0, 10).is_err());
+    let parser = pty.close();
+    assert_eq!(parser.get_screen().get_size(), Vector2::new(40, 10));
+    assert!(parser.lines().iter().any(|line| line == "hello"));
+}
+#[test]
+fn screen_buffer() {
+    let buffer = ScreenBuffer::new().unwrap();
+    let output = console::read_output(0, 0, 5).unwrap();
+    {
+        let _guard = buffer.activate().unwrap();
+        buffer.write_output("Hello", 0, 0).unwrap();
+        buffer.set_cursor_position(5, 0).unwrap();
+        assert_eq!(buffer.read_output(0, 0, 5).unwrap(), "Hello");
+        assert_eq!(buffer.get_cursor_position().unwrap(), Vector2::new(5, 0));
+    }
+    assert_eq!(console::read_output(0, 0, 5).unwrap(), output);
+}
 #[test]
 fn title() {
     let original_title = console::get_original_title().unwrap();
@@ -1,12 +1,21 @@
 #![cfg(test)]
+#![cfg(windows)]
 #![cfg(feature = "input")]
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "tokio")]
+extern crate tokio_stream;
 extern crate winconsole;
 
 use winconsole::{
     console::{self, Vector2},
-    input::{self, KeyCode, KeyEvent, MouseWheelEvent},
+    input::{self, InputReader, KeyCode, KeyEvent, MouseWheelEvent},
 };
 
+use std::time::{Duration, Instant};
+
 #[test]
 fn drop_restore() {
     let old_mode = console::get_input_mode().unwrap();
@@ -40,6 +49,21 @@ fn drop_no_restore() {
     assert_eq!(console::get_input_mode().unwrap(), old_mode);
 }
 #[test]
+fn reader() {
+    let old_mode = console::get_input_mode().unwrap();
+    let mut ctx = input::start().unwrap();
+    let mut mode = old_mode;
+    mode.WindowInput = !mode.WindowInput;
+    console::set_input_mode(mode).unwrap();
+
+    ctx.simulate(KeyEvent::new());
+    let (reader, events) = InputReader::start(ctx).unwrap();
+    assert_eq!(events.recv().unwrap(), KeyEvent::new().into());
+    assert!(reader.is_running());
+    reader.stop().unwrap();
+    assert_eq!(console::get_input_mode().unwrap(), old_mode);
+}
+#[test]
 fn simulate() {
     let mut ctx = input::start().unwrap();
 
@@ -67,3 +91,79 @@ fn simulate() {
     assert_eq!(events[0], key_event.into());
     assert_eq!(events[1], wheel_event.into());
 }
+#[test]
+#[cfg(feature = "async")]
+fn stream() {
+    use futures::executor;
+    use winconsole::input::FocusEvent;
+
+    let mut ctx = input::start().unwrap();
+    let mut focus_event = FocusEvent::new();
+    focus_event.focused = true;
+    input::flush().unwrap();
+    ctx.flush();
+    ctx.simulate(focus_event);
+
+    let mut events = executor::block_on_stream(ctx.stream());
+    assert_eq!(events.next().unwrap().unwrap(), focus_event.into());
+}
+#[test]
+fn surrogate_pair() {
+    let mut ctx = input::start().unwrap();
+    let mut key_event = KeyEvent::new();
+    key_event.character = '😀';
+    key_event.pressed = true;
+    let mut release = key_event;
+    release.pressed = false;
+
+    input::flush().unwrap();
+    ctx.flush();
+    // Each event is written as two records, one for each half of the pair.
+    input::write(key_event, None).unwrap();
+    input::write(release, None).unwrap();
+    let events = ctx.get().unwrap();
+    assert_eq!(events, vec![key_event.into(), release.into()]);
+}
+#[test]
+#[cfg(feature = "tokio")]
+fn tokio() {
+    use tokio::runtime::Builder;
+    use tokio::time;
+    use tokio_stream::StreamExt;
+    use winconsole::input::FocusEvent;
+
+    let runtime = Builder::new_current_thread().enable_time().build().unwrap();
+    let mut ctx = input::start().unwrap();
+    let mut focus_event = FocusEvent::new();
+    focus_event.focused = true;
+    input::flush().unwrap();
+    ctx.flush();
+
+    let timeout = Duration::from_millis(50);
+    assert!(runtime.block_on(time::timeout(timeout, ctx.recv())).is_err());
+    ctx.simulate(focus_event);
+    let event = runtime.block_on(time::timeout(timeout, ctx.recv()));
+    assert_eq!(event.unwrap().unwrap(), focus_event.into());
+
+    ctx.simulate(focus_event);
+    let mut stream = ctx.stream();
+    let event = runtime.block_on(stream.next()).unwrap();
+    assert_eq!(event.unwrap(), focus_event.into());
+}
+#[test]
+fn wait_timeout() {
+    let mut ctx = input::start().unwrap();
+    let mut key_event = KeyEvent::new();
+    key_event.key_code = KeyCode::Escape;
+    key_event.pressed = true;
+
+    input::flush().unwrap();
+    ctx.flush();
+    ctx.simulate(key_event);
+    let event = ctx.wait_timeout(Duration::from_millis(10)).unwrap();
+    assert_eq!(event, Some(key_event.into()));
+
+    ctx.simulate(key_event);
+    let event = ctx.wait_until(Instant::now()).unwrap();
+    assert_eq!(event, Some(key_event.into()));
+}
@@ -0,0 +1,94 @@
+#![cfg(test)]
+#![cfg(feature = "input")]
+extern crate winconsole;
+
+use winconsole::input::{KeyChord, KeyCode, KeyEvent, KeyModifiers, KeySequence};
+
+fn event(key_code: KeyCode) -> KeyEvent {
+    let mut kev = KeyEvent::new();
+    kev.key_code = key_code;
+    kev.pressed = true;
+    kev
+}
+
+#[test]
+fn format() {
+    let cases = [
+        ("Ctrl+Shift+F5", "Ctrl+Shift+F5"),
+        ("alt+enter", "Alt+Enter"),
+        ("Shift+Ctrl+a", "Ctrl+Shift+A"),
+        ("RCtrl+LAlt+Del", "RightCtrl+LeftAlt+Delete"),
+        ("Ctrl+LeftCtrl+Esc", "Ctrl+Esc"),
+        ("Ctrl++", "Ctrl+="),
+        ("Ctrl+-", "Ctrl+-"),
+        ("/", "/"),
+        ("PageDown", "PageDown"),
+        ("  Ctrl+0 ", "Ctrl+0"),
+    ];
+    for &(text, formatted) in cases.iter() {
+        let chord: KeyChord = text.parse().unwrap();
+        assert_eq!(chord.to_string(), formatted);
+        assert_eq!(formatted.parse::<KeyChord>().unwrap(), chord);
+    }
+
+    for text in ["", "Ctrl+", "Hyper+A", "Ctrl+Nope", "Ctrl+K Ctrl+C"].iter() {
+        assert!(text.parse::<KeyChord>().is_err(), "{:?} was parsed", text);
+    }
+    let err = "Ctrl+Nope".parse::<KeyChord>().unwrap_err();
+    assert!(err.message.contains("Nope"));
+}
+
+#[test]
+fn matches() {
+    let chord: KeyChord = "Ctrl+Shift+F5".parse().unwrap();
+    let mut kev = event(KeyCode::F5);
+    kev.modifiers.ShiftPressed = true;
+    kev.modifiers.LeftCtrlPressed = true;
+    kev.modifiers.NumLockOn = true;
+    assert!(chord.matches(&kev));
+    kev.modifiers.LeftCtrlPressed = false;
+    kev.modifiers.RightCtrlPressed = true;
+    assert!(chord.matches(&kev));
+    kev.modifiers.LeftAltPressed = true;
+    assert!(!chord.matches(&kev));
+    assert!(!chord.matches(&event(KeyCode::F5)));
+
+    let right: KeyChord = "RightAlt+Q".parse().unwrap();
+    let mut kev = event(KeyCode::Q);
+    kev.modifiers.RightAltPressed = true;
+    assert!(right.matches(&kev));
+    kev.modifiers.LeftAltPressed = true;
+    assert!(!right.matches(&kev));
+    kev.modifiers.RightAltPressed = false;
+    assert!(!right.matches(&kev));
+
+    let chord = KeyChord::from(kev);
+    assert_eq!(chord.key_code, KeyCode::Q);
+    assert_eq!(
+        chord.modifiers,
+        KeyModifiers {
+            Alt: true,
+            ..Default::default()
+        }
+    );
+    assert!(chord.matches(&kev));
+}
+
+#[test]
+fn sequence() {
+    let sequence: KeySequence = " Ctrl+K\tctrl+c ".parse().unwrap();
+    assert_eq!(
+        sequence.chords,
+        vec![
+            "Ctrl+K".parse::<KeyChord>().unwrap(),
+            "Ctrl+C".parse::<KeyChord>().unwrap(),
+        ]
+    );
+    assert_eq!(sequence.to_string(), "Ctrl+K Ctrl+C");
+    assert_eq!(
+        KeySequence::from("F2".parse::<KeyChord>().unwrap()).to_string(),
+        "F2"
+    );
+    assert!("".parse::<KeySequence>().is_err());
+    assert!("Ctrl+K Ctrl+Nope".parse::<KeySequence>().is_err());
+}
@@ -0,0 +1,263 @@
+#![cfg(test)]
+#![cfg(feature = "input")]
+extern crate winconsole;
+
+use std::time::{Duration, Instant};
+use winconsole::input::{
+    InputEvent, KeyChord, KeyCode, KeyEvent, KeyMatch, KeySequence, Keymap, MouseWheelEvent,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Comment,
+    Delete,
+    Insert,
+    Quit,
+    Save,
+}
+
+fn key(key_code: KeyCode, ctrl: bool) -> InputEvent {
+    let mut kev = KeyEvent::new();
+    kev.key_code = key_code;
+    kev.modifiers.LeftCtrlPressed = ctrl;
+    kev.pressed = true;
+    InputEvent::KeyDown(kev)
+}
+
+fn sequence(text: &str) -> KeySequence {
+    text.parse().unwrap()
+}
+
+fn keymap() -> Keymap<Action> {
+    let mut keymap = Keymap::new("normal");
+    keymap
+        .load(
+            "normal",
+            vec![
+                ("Ctrl+K Ctrl+C", Action::Comment),
+                ("D D", Action::Delete),
+                ("I", Action::Insert),
+                ("Ctrl+S", Action::Save),
+            ],
+        )
+        .unwrap();
+    keymap
+        .bind("insert", sequence("Esc"), Action::Quit)
+        .unwrap();
+    keymap
+}
+
+#[test]
+fn conflicts() {
+    let mut keymap = keymap();
+    for text in ["Ctrl+K", "Ctrl+K Ctrl+C Ctrl+U", "LeftCtrl+S", "D"].iter() {
+        assert!(
+            keymap.bind("normal", sequence(text), Action::Quit).is_err(),
+            "{} was bound",
+            text
+        );
+    }
+    keymap
+        .bind("normal", sequence("RightCtrl+K"), Action::Quit)
+        .unwrap_err();
+    keymap
+        .bind("normal", sequence("Ctrl+Shift+S"), Action::Quit)
+        .unwrap();
+    keymap
+        .bind("insert", sequence("Ctrl+K"), Action::Quit)
+        .unwrap();
+    keymap
+        .bind("normal", KeySequence::new(Vec::new()), Action::Quit)
+        .unwrap_err();
+
+    // A failed load doesn't add any of its bindings.
+    let bindings = vec![("Ctrl+Q", Action::Quit), ("Ctrl+Q Ctrl+Q", Action::Quit)];
+    assert!(keymap.load("normal", bindings).is_err());
+    assert!(keymap
+        .load("normal", vec![("Ctrl+", Action::Quit)])
+        .is_err());
+    assert_eq!(keymap.get_action("normal", &sequence("Ctrl+Q")), None);
+
+    assert_eq!(
+        keymap.unbind("normal", &sequence("Ctrl+S")),
+        Some(Action::Save)
+    );
+    keymap
+        .bind("normal", sequence("LeftCtrl+S"), Action::Save)
+        .unwrap();
+    assert_eq!(
+        keymap.get_action("normal", &sequence("LeftCtrl+S")),
+        Some(&Action::Save)
+    );
+    // Sided modifiers conflict only with the same side.
+    keymap
+        .bind("normal", sequence("LeftCtrl+S"), Action::Quit)
+        .unwrap_err();
+    keymap
+        .bind("normal", sequence("RightCtrl+S"), Action::Quit)
+        .unwrap();
+    let bindings = vec![("RightAlt+F", Action::Quit), ("RightAlt+F", Action::Save)];
+    assert!(keymap.load("normal", bindings).is_err());
+    let bindings = vec![("LeftAlt+F", Action::Quit), ("RightAlt+F", Action::Save)];
+    keymap.load("normal", bindings).unwrap();
+    keymap
+        .bind("normal", sequence("LeftCtrl+RightCtrl+X"), Action::Quit)
+        .unwrap();
+    keymap
+        .bind("normal", sequence("LeftCtrl+X"), Action::Quit)
+        .unwrap();
+    keymap
+        .bind("normal", sequence("Ctrl+X"), Action::Quit)
+        .unwrap_err();
+}
+
+#[test]
+fn held() {
+    let mut keymap = keymap();
+    let ctrl_k = match key(KeyCode::K, true) {
+        InputEvent::KeyDown(kev) => kev,
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        keymap.handle(&InputEvent::KeyDown(ctrl_k)),
+        KeyMatch::Pending
+    );
+    for _ in 0..3 {
+        assert_eq!(
+            keymap.handle(&InputEvent::KeyHeld(ctrl_k)),
+            KeyMatch::Ignored
+        );
+    }
+    assert_eq!(keymap.get_pending(), sequence("Ctrl+K"));
+    assert_eq!(
+        keymap.handle(&key(KeyCode::C, true)),
+        KeyMatch::Matched(Action::Comment)
+    );
+}
+
+#[test]
+fn modes() {
+    let mut keymap = keymap();
+    assert_eq!(keymap.get_mode(), "normal");
+    assert_eq!(
+        keymap.handle(&key(KeyCode::I, false)),
+        KeyMatch::Matched(Action::Insert)
+    );
+
+    keymap.set_mode("insert");
+    assert_eq!(
+        keymap.handle(&key(KeyCode::I, false)),
+        KeyMatch::Unmatched(sequence("I"))
+    );
+    assert_eq!(
+        keymap.handle(&key(KeyCode::Escape, false)),
+        KeyMatch::Matched(Action::Quit)
+    );
+
+    keymap.set_mode("normal");
+    assert_eq!(keymap.handle(&key(KeyCode::D, false)), KeyMatch::Pending);
+    keymap.set_mode("insert");
+    keymap.set_mode("normal");
+    assert_eq!(keymap.handle(&key(KeyCode::D, false)), KeyMatch::Pending);
+    assert_eq!(
+        keymap.handle(&key(KeyCode::D, false)),
+        KeyMatch::Matched(Action::Delete)
+    );
+}
+
+#[test]
+fn retries() {
+    let mut keymap = keymap();
+    assert_eq!(keymap.handle(&key(KeyCode::D, false)), KeyMatch::Pending);
+    assert_eq!(
+        keymap.handle(&key(KeyCode::I, false)),
+        KeyMatch::Matched(Action::Insert)
+    );
+    assert_eq!(keymap.get_pending(), KeySequence::default());
+
+    assert_eq!(keymap.handle(&key(KeyCode::D, false)), KeyMatch::Pending);
+    assert_eq!(keymap.handle(&key(KeyCode::K, true)), KeyMatch::Pending);
+    assert_eq!(keymap.get_pending(), sequence("Ctrl+K"));
+    assert_eq!(
+        keymap.handle(&key(KeyCode::C, true)),
+        KeyMatch::Matched(Action::Comment)
+    );
+}
+
+#[test]
+fn sequences() {
+    let mut keymap = keymap();
+    let ignored = [
+        MouseWheelEvent::new().into(),
+        key(KeyCode::Control, true),
+        key(KeyCode::LShift, false),
+    ];
+    for event in ignored.iter() {
+        assert_eq!(keymap.handle(event), KeyMatch::Ignored);
+    }
+
+    assert_eq!(keymap.handle(&key(KeyCode::K, true)), KeyMatch::Pending);
+    assert_eq!(keymap.get_pending(), sequence("Ctrl+K"));
+    assert_eq!(
+        keymap.handle(&key(KeyCode::Control, true)),
+        KeyMatch::Ignored
+    );
+    assert_eq!(
+        keymap.handle(&key(KeyCode::C, true)),
+        KeyMatch::Matched(Action::Comment)
+    );
+    assert_eq!(keymap.get_pending(), KeySequence::default());
+
+    assert_eq!(keymap.handle(&key(KeyCode::K, true)), KeyMatch::Pending);
+    assert_eq!(
+        keymap.handle(&key(KeyCode::X, false)),
+        KeyMatch::Unmatched(sequence("Ctrl+K X"))
+    );
+
+    let mut held = KeyEvent::new();
+    held.key_code = KeyCode::S;
+    held.modifiers.RightCtrlPressed = true;
+    assert_eq!(
+        keymap.handle(&InputEvent::KeyHeld(held)),
+        KeyMatch::Matched(Action::Save)
+    );
+    assert_eq!(keymap.handle(&InputEvent::KeyUp(held)), KeyMatch::Ignored);
+}
+
+#[test]
+fn timeout() {
+    let mut keymap = keymap();
+    keymap.set_timeout(Duration::from_millis(500));
+    let start = Instant::now();
+
+    assert_eq!(
+        keymap.handle_at(&key(KeyCode::K, true), start),
+        KeyMatch::Pending
+    );
+    let deadline = start + Duration::from_millis(500);
+    assert_eq!(keymap.get_deadline(), Some(deadline));
+    assert_eq!(keymap.expire(start + Duration::from_millis(499)), None);
+    assert_eq!(keymap.expire(deadline), Some(sequence("Ctrl+K")));
+    assert_eq!(keymap.get_deadline(), None);
+
+    assert_eq!(
+        keymap.handle_at(&key(KeyCode::K, true), start),
+        KeyMatch::Pending
+    );
+    let late = start + Duration::from_secs(1);
+    assert_eq!(
+        keymap.handle_at(&key(KeyCode::C, true), late),
+        KeyMatch::Unmatched(sequence("Ctrl+C"))
+    );
+
+    assert_eq!(
+        keymap.handle_at(&key(KeyCode::K, true), late),
+        KeyMatch::Pending
+    );
+    keymap.reset();
+    assert_eq!(keymap.get_deadline(), None);
+    assert_eq!(
+        keymap.handle_at(&key(KeyCode::C, true), late),
+        KeyMatch::Unmatched("Ctrl+C".parse::<KeyChord>().unwrap().into())
+    );
+}
@@ -0,0 +1,253 @@
+#![cfg(test)]
+extern crate rgb;
+extern crate winconsole;
+
+use rgb::RGB8;
+use std::panic;
+use winconsole::console::{
+    self, Cell, CellBuffer, ColorGuard, ConsoleColor, ConsoleFont, CursorVisibilityGuard,
+    MemoryConsole, Rect, StandardStream, StateGuard, StreamKind, TextAttributes, TitleGuard,
+    Vector2,
+};
+
+fn install(columns: u16, rows: u16) -> MemoryConsole {
+    let memory = MemoryConsole::new(columns, rows);
+    console::set_backend(memory.clone());
+    memory
+}
+
+#[test]
+fn attributes() {
+    install(10, 3);
+    let mut underlined = TextAttributes::new(ConsoleColor::Red, ConsoleColor::Black);
+    underlined.flags.Underscore = true;
+    console::write_output_attributes(&[underlined; 2], 0, 0).unwrap();
+    console::fill_colors((ConsoleColor::Blue, ConsoleColor::Black), 1, 0, 1).unwrap();
+
+    let attributes = console::read_output_attributes(0, 0, 2).unwrap();
+    assert_eq!(attributes[0], underlined);
+    assert_eq!(
+        attributes[1],
+        TextAttributes::new(ConsoleColor::Blue, ConsoleColor::Black)
+    );
+
+    console::set_text_attributes(underlined).unwrap();
+    console::set_foreground_color(ConsoleColor::Green).unwrap();
+    let current = console::get_text_attributes().unwrap();
+    assert_eq!(current.foreground, ConsoleColor::Green);
+    assert!(current.flags.Underscore);
+    console::reset_backend();
+}
+#[test]
+fn capabilities() {
+    let memory = install(10, 3);
+    assert_eq!(
+        console::get_stream_kind(StandardStream::Error),
+        StreamKind::Console
+    );
+    let mode = console::get_output_mode().unwrap();
+    let font = ConsoleFont {
+        family: 0x36,
+        ..ConsoleFont::default()
+    };
+    console::set_font(&font).unwrap();
+
+    let capabilities = console::get_capabilities();
+    assert_eq!(capabilities.output, StreamKind::Console);
+    assert!(capabilities.output.is_terminal());
+    assert!(capabilities.virtual_terminal);
+    assert!(!capabilities.mouse_input);
+    assert!(capabilities.unicode_font);
+    assert_eq!(console::get_output_mode().unwrap(), mode);
+
+    memory.set_stream_kind(StandardStream::Output, StreamKind::Pipe);
+    let capabilities = console::get_capabilities();
+    assert_eq!(capabilities.output, StreamKind::Pipe);
+    assert_eq!(capabilities.error, StreamKind::Console);
+    assert!(!capabilities.virtual_terminal);
+    console::reset_backend();
+}
+#[test]
+fn clear() {
+    let memory = install(10, 3);
+    console::write_output("abc", 0, 1).unwrap();
+    console::set_cursor_position(3, 1).unwrap();
+    console::clear().unwrap();
+
+    assert_eq!(memory.lines(), vec!["", "", ""]);
+    assert_eq!(console::get_cursor_position().unwrap(), Vector2::new(0, 0));
+    console::reset_backend();
+}
+#[test]
+fn color_mapping() {
+    install(10, 3);
+    let color = RGB8 {
+        r: 50,
+        g: 210,
+        b: 15,
+    };
+    console::map_color(ConsoleColor::Black, color).unwrap();
+    assert_eq!(console::get_color(ConsoleColor::Black).unwrap(), color);
+    console::reset_backend();
+}
+#[test]
+fn colors() {
+    install(10, 3);
+    console::set_foreground_color(ConsoleColor::Red).unwrap();
+    console::set_background_color(ConsoleColor::Blue).unwrap();
+    assert_eq!(console::get_foreground_color().unwrap(), ConsoleColor::Red);
+    assert_eq!(console::get_background_color().unwrap(), ConsoleColor::Blue);
+
+    console::fill_colors((ConsoleColor::Yellow, ConsoleColor::Black), 8, 0, 2).unwrap();
+    let colors = console::read_output_colors(8, 0, 4).unwrap();
+    assert_eq!(colors[1], (ConsoleColor::Yellow, ConsoleColor::Black));
+    assert_eq!(colors[3], (ConsoleColor::Gray, ConsoleColor::Black));
+    console::reset_backend();
+}
+#[test]
+fn cursor() {
+    install(10, 3);
+    console::set_cursor_position(9, 2).unwrap();
+    assert_eq!(console::get_cursor_position().unwrap(), Vector2::new(9, 2));
+    assert!(console::set_cursor_position(10, 2).is_err());
+
+    console::set_cursor_size(35).unwrap();
+    assert_eq!(console::get_cursor_size().unwrap(), 35);
+    assert!(console::set_cursor_size(101).is_err());
+
+    console::set_cursor_visible(false).unwrap();
+    assert!(!console::is_cursor_visible().unwrap());
+    console::reset_backend();
+}
+#[test]
+fn guards() {
+    install(10, 3);
+    console::set_title("Title").unwrap();
+    {
+        let _color = ColorGuard::new(ConsoleColor::Red, ConsoleColor::Blue).unwrap();
+        let _cursor = CursorVisibilityGuard::new(false).unwrap();
+        let _title = TitleGuard::new("Working").unwrap();
+        assert_eq!(console::get_foreground_color().unwrap(), ConsoleColor::Red);
+        assert!(!console::is_cursor_visible().unwrap());
+        assert_eq!(console::get_title().unwrap(), "Working");
+    }
+    assert_eq!(
+        console::get_text_attributes().unwrap(),
+        TextAttributes::default()
+    );
+    assert!(console::is_cursor_visible().unwrap());
+    assert_eq!(console::get_title().unwrap(), "Title");
+
+    let result = panic::catch_unwind(|| {
+        let _guard = ColorGuard::foreground(ConsoleColor::Green).unwrap();
+        panic!("restored while unwinding");
+    });
+    assert!(result.is_err());
+    assert_eq!(console::get_foreground_color().unwrap(), ConsoleColor::Gray);
+
+    let guard = StateGuard::new(false, false).unwrap();
+    console::set_background_color(ConsoleColor::Yellow).unwrap();
+    console::set_title("Changed").unwrap();
+    guard.restore().unwrap();
+    assert_eq!(
+        console::get_background_color().unwrap(),
+        ConsoleColor::Black
+    );
+    assert_eq!(console::get_title().unwrap(), "Title");
+    console::reset_backend();
+}
+#[test]
+fn move_contents() {
+    let memory = install(10, 3);
+    console::write_output("abc", 0, 0).unwrap();
+    console::move_contents(
+        Rect::new(0, 0, 2, 0),
+        Vector2::new(5, 1),
+        None,
+        '.',
+        None,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(memory.lines(), vec!["...", "     abc", ""]);
+    console::reset_backend();
+}
+#[test]
+#[cfg(not(windows))]
+fn no_backend() {
+    assert!(console::get_buffer_size().is_err());
+    assert!(console::write_output("text", 0, 0).is_err());
+    assert_ne!(
+        console::get_stream_kind(StandardStream::Output),
+        StreamKind::Console
+    );
+
+    install(10, 3);
+    assert!(console::get_buffer_size().is_ok());
+    console::reset_backend();
+    assert!(console::get_buffer_size().is_err());
+}
+#[test]
+fn output() {
+    let memory = install(10, 3);
+    assert_eq!(console::write_output("Hello, world!", 0, 1).unwrap(), 13);
+    assert_eq!(memory.lines(), vec!["", "Hello, wor", "ld!"]);
+    assert_eq!(console::read_output(7, 1, 6).unwrap(), "world!");
+    assert!(console::write_output("a", 0, 3).is_err());
+    console::reset_backend();
+}
+#[test]
+fn region() {
+    let memory = install(10, 3);
+    console::write_output("abcdef", 0, 0).unwrap();
+    console::fill_colors((ConsoleColor::Red, ConsoleColor::Blue), 1, 0, 1).unwrap();
+
+    let region = console::read_region(Rect::new(0, 1, 3, 1)).unwrap();
+    assert_eq!(region.lines(), vec!["bcd", ""]);
+    assert_eq!(region.get(0, 0).unwrap().foreground, ConsoleColor::Red);
+    assert_eq!(region.get(1, 0).unwrap().foreground, ConsoleColor::Gray);
+
+    let mut buffer = CellBuffer::new(2, 2);
+    buffer.fill(
+        Rect::new(0, 0, 1, 1),
+        Cell::new('z', ConsoleColor::Green, ConsoleColor::Black),
+    );
+    console::write_region(Rect::new(1, 8, 9, 2), &buffer).unwrap();
+    assert_eq!(memory.lines(), vec!["abcdef", "        zz", "        zz"]);
+    assert_eq!(
+        console::read_output_colors(8, 1, 1).unwrap()[0].0,
+        ConsoleColor::Green
+    );
+
+    assert!(console::read_region(Rect::new(0, 5, 10, 0)).is_err());
+    assert!(console::write_region(Rect::new(1, 0, 0, 0), &buffer).is_err());
+    console::reset_backend();
+}
+#[test]
+fn state() {
+    install(10, 3);
+    console::write_output("abc", 0, 0).unwrap();
+    console::set_cursor_position(3, 0).unwrap();
+    console::set_title("Old title").unwrap();
+    let state = console::get_state(true, false).unwrap();
+    assert_eq!(state.output, "abc");
+
+    install(10, 3);
+    console::set_state(&state, true, true).unwrap();
+    assert_eq!(console::read_output(0, 0, 3).unwrap(), "abc");
+    assert_eq!(console::get_title().unwrap(), "Old title");
+    console::reset_backend();
+}
+#[test]
+fn window_size() {
+    let memory = install(10, 3);
+    memory.set_largest_window_size(Vector2::new(20, 5));
+
+    console::set_window_size(15, 4).unwrap();
+    assert_eq!(console::get_window_size().unwrap(), Vector2::new(15, 4));
+    assert_eq!(console::get_buffer_size().unwrap(), Vector2::new(15, 4));
+    assert!(console::set_window_size(21, 4).is_err());
+    assert_eq!(console::get_buffer_size().unwrap(), Vector2::new(15, 4));
+    console::reset_backend();
+}
@@ -0,0 +1,103 @@
+#![cfg(test)]
+#![cfg(feature = "recording")]
+extern crate winconsole;
+
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+use winconsole::console::Vector2;
+use winconsole::errors::WinError;
+use winconsole::input::{
+    FocusEvent, InputEvent, InputReplayer, KeyCode, KeyEvent, LogFormat, MouseWheelEvent,
+    RecordedEvent, ReplayTiming,
+};
+
+fn events() -> Vec<RecordedEvent> {
+    let mut key = KeyEvent::new();
+    key.key_code = KeyCode::A;
+    key.character = 'a';
+    key.pressed = true;
+    let mut wheel = MouseWheelEvent::new();
+    wheel.delta = -120;
+    wheel.position = Vector2::new(4, 2);
+
+    vec![
+        RecordedEvent::new(Duration::from_millis(20), key),
+        RecordedEvent::new(Duration::from_millis(30), wheel),
+        RecordedEvent::new(Duration::from_millis(1030), FocusEvent::new()),
+    ]
+}
+
+#[test]
+fn log_formats() {
+    for &format in &[LogFormat::JsonLines, LogFormat::Binary] {
+        let mut log = Vec::new();
+        for event in events() {
+            event.write(&mut log, format).unwrap();
+        }
+        assert_eq!(RecordedEvent::read_all(&log[..], format).unwrap(), events());
+        assert!(RecordedEvent::read_all(&[][..], format).unwrap().is_empty());
+    }
+
+    let mut log = Vec::new();
+    events()[0].write(&mut log, LogFormat::JsonLines).unwrap();
+    let text = String::from_utf8(log).unwrap();
+    assert_eq!(text.lines().count(), 1);
+    assert!(text.contains("KeyDown"));
+
+    match RecordedEvent::read_all(&b"{\"time\": 1}\n"[..], LogFormat::JsonLines) {
+        Err(WinError::Io(err)) => assert_eq!(err.kind(), ErrorKind::InvalidData),
+        other => panic!("expected an IO error, got {:?}", other),
+    }
+
+    let mut log = Vec::new();
+    events()[0].write(&mut log, LogFormat::Binary).unwrap();
+    log.pop();
+    match RecordedEvent::read_all(&log[..], LogFormat::Binary) {
+        Err(WinError::Io(err)) => assert_eq!(err.kind(), ErrorKind::InvalidData),
+        other => panic!("expected an IO error, got {:?}", other),
+    }
+}
+
+#[test]
+fn replay() {
+    let mut replayer = InputReplayer::new(events(), ReplayTiming::Real);
+    let delays: Vec<u64> = replayer
+        .get_delays()
+        .iter()
+        .map(|delay| delay.as_millis() as u64)
+        .collect();
+    assert_eq!(delays, vec![20, 10, 1000]);
+
+    replayer.set_timing(ReplayTiming::Compressed(Duration::from_millis(15)));
+    let delays: Vec<u64> = replayer
+        .get_delays()
+        .iter()
+        .map(|delay| delay.as_millis() as u64)
+        .collect();
+    assert_eq!(delays, vec![15, 10, 15]);
+
+    let start = Instant::now();
+    let mut replayed = Vec::new();
+    replayer
+        .replay(|event| {
+            replayed.push(event);
+            Ok(())
+        })
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(40));
+    let expected: Vec<InputEvent> = events().iter().map(|recorded| recorded.event).collect();
+    assert_eq!(replayed, expected);
+
+    replayer.set_timing(ReplayTiming::Immediate);
+    assert!(replayer
+        .get_delays()
+        .iter()
+        .all(|delay| *delay == Duration::from_secs(0)));
+    let mut count = 0;
+    let result = replayer.replay(|_| {
+        count += 1;
+        Err(WinError::from(std::io::Error::from(ErrorKind::Interrupted)))
+    });
+    assert!(result.is_err());
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,61 @@
+#![cfg(test)]
+extern crate winconsole;
+
+use winconsole::console::{self, ConsoleColor, MemoryConsole, Rect, Renderer};
+
+#[test]
+fn changes() {
+    let mut renderer = Renderer::new(10, 2);
+    assert_eq!(renderer.get_changes(), vec![Rect::new(0, 0, 9, 1)]);
+    renderer.mark_presented();
+    assert!(renderer.get_changes().is_empty());
+
+    renderer.merge_distance = 1;
+    let frame = renderer.get_frame_mut();
+    frame.write_str("ab", 0, 0, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str("c", 3, 0, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str("d", 6, 0, ConsoleColor::Gray, ConsoleColor::Black);
+    frame.write_str("e", 9, 0, ConsoleColor::Red, ConsoleColor::Black);
+    frame.write_str("f", 0, 1, ConsoleColor::Gray, ConsoleColor::Black);
+    assert_eq!(
+        renderer.get_changes(),
+        vec![
+            Rect::new(0, 0, 3, 0),
+            Rect::new(0, 6, 6, 0),
+            Rect::new(0, 9, 9, 0),
+            Rect::new(1, 0, 0, 1),
+        ]
+    );
+
+    renderer.invalidate();
+    assert_eq!(renderer.get_changes(), vec![Rect::new(0, 0, 9, 1)]);
+}
+#[test]
+fn present() {
+    let memory = MemoryConsole::new(12, 4);
+    console::set_backend(memory.clone());
+
+    let mut renderer = Renderer::new(5, 2);
+    renderer.set_origin(9, 2);
+    renderer
+        .get_frame_mut()
+        .write_str("abcde", 0, 0, ConsoleColor::Gray, ConsoleColor::Black);
+    assert_eq!(renderer.present().unwrap(), 6);
+    assert_eq!(memory.lines(), vec!["", "", "         abc", ""]);
+    assert_eq!(renderer.present().unwrap(), 0);
+
+    renderer
+        .get_frame_mut()
+        .write_str("x", 1, 1, ConsoleColor::Yellow, ConsoleColor::Black);
+    console::write_output("untouched", 0, 0).unwrap();
+    assert_eq!(renderer.present().unwrap(), 1);
+    assert_eq!(
+        memory.lines(),
+        vec!["untouched", "", "         abc", "          x"]
+    );
+    assert_eq!(
+        console::read_output_colors(10, 3, 1).unwrap()[0].0,
+        ConsoleColor::Yellow
+    );
+    console::reset_backend();
+}
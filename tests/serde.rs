@@ -29,12 +29,19 @@ macro_rules! serde_test {
     };
 }
 
+// Functions in the console module need a backend on other platforms than Windows.
+fn install_backend() {
+    #[cfg(not(windows))]
+    console::set_backend(console::MemoryConsole::new(80, 25));
+}
+
 #[test]
 fn code_page() {
     use winconsole::console::CodePage;
     serde_test!(console::get_input_code_page(), CodePage);
 }
 #[test]
+#[cfg(windows)]
 fn code_page_info() {
     use winconsole::console::CodePageInfo;
     serde_test!(
@@ -50,11 +57,13 @@ fn console_color() {
 #[test]
 fn console_font() {
     use winconsole::console::ConsoleFont;
+    install_backend();
     serde_test!(console::get_font().unwrap(), ConsoleFont);
 }
 #[test]
 fn console_state() {
     use winconsole::console::ConsoleState;
+    install_backend();
     serde_test!(
         console::get_state(true, false).unwrap(),
         ConsoleState,
@@ -64,16 +73,19 @@ fn console_state() {
 #[test]
 fn history_info() {
     use winconsole::console::HistoryInfo;
+    install_backend();
     serde_test!(console::get_history_info().unwrap(), HistoryInfo);
 }
 #[test]
 fn input_settings() {
     use winconsole::console::InputSettings;
+    install_backend();
     serde_test!(console::get_input_mode().unwrap(), InputSettings);
 }
 #[test]
 fn output_settings() {
     use winconsole::console::OutputSettings;
+    install_backend();
     serde_test!(console::get_output_mode().unwrap(), OutputSettings);
 }
 #[test]
@@ -84,9 +96,17 @@ fn rect() {
 #[test]
 fn selection_info() {
     use winconsole::console::SelectionInfo;
+    install_backend();
     serde_test!(console::get_selection_info().unwrap(), SelectionInfo);
 }
 #[test]
+fn text_attributes() {
+    use winconsole::console::{ConsoleColor, TextAttributes};
+    let mut attributes = TextAttributes::new(ConsoleColor::Red, ConsoleColor::DarkBlue);
+    attributes.flags.ReverseVideo = true;
+    serde_test!(attributes, TextAttributes);
+}
+#[test]
 fn vector2() {
 	use winconsole::console::Vector2;
 	serde_test!(Vector2::<u8>::new(10, 10), Vector2::<u8>);
@@ -49,6 +49,13 @@ fn input_filter() {
     serde_test!(InputFilter::from(9), InputFilter);
 }
 #[test]
+fn key_chord() {
+    use winconsole::input::KeyChord;
+    let chord: KeyChord = "Ctrl+RightAlt+F5".parse().unwrap();
+    serde_test!(chord, KeyChord);
+    assert_eq!(serde_json::to_string(&chord).unwrap(), "\"Ctrl+RightAlt+F5\"");
+}
+#[test]
 fn key_code() {
     use winconsole::input::KeyCode;
     serde_test!(KeyCode::Return, KeyCode);
@@ -59,6 +66,18 @@ fn key_event() {
     serde_test!(KeyEvent::new(), KeyEvent);
 }
 #[test]
+fn key_modifiers() {
+    use winconsole::input::KeyModifiers;
+    serde_test!(KeyModifiers::from(0x42), KeyModifiers);
+}
+#[test]
+fn key_sequence() {
+    use winconsole::input::KeySequence;
+    let sequence: KeySequence = "Ctrl+K Ctrl+C".parse().unwrap();
+    serde_test!(sequence, KeySequence);
+    assert!(serde_json::from_str::<KeySequence>("\"Ctrl+\"").is_err());
+}
+#[test]
 fn mouse_event() {
     use winconsole::input::MouseEvent;
     serde_test!(MouseEvent::new(), MouseEvent);
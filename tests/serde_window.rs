@@ -6,8 +6,6 @@ extern crate serde_cbor;
 extern crate serde_json;
 extern crate winconsole;
 
-use winconsole::window;
-
 macro_rules! serde_test {
     ($obj:expr, $type:ty, $output:expr) => {
         let obj = $obj;
@@ -31,8 +29,9 @@ macro_rules! serde_test {
 }
 
 #[test]
+#[cfg(windows)]
 fn display_state() {
-    use winconsole::window::DisplayState;
+    use winconsole::window::{self, DisplayState};
     serde_test!(window::get_display_state().unwrap(), DisplayState);
 }
 #[test]
@@ -0,0 +1,87 @@
+#![cfg(test)]
+#[macro_use]
+extern crate winconsole;
+
+use winconsole::console::{
+    self, ConsoleColor, MemoryConsole, StyledString, TextAttributes, TextStyle,
+};
+use winconsole::errors::WinError;
+
+fn error_message(markup: &str) -> String {
+    match StyledString::parse(markup, &[&1]) {
+        Err(WinError::Argument(err)) => {
+            assert_eq!(err.argument, "markup");
+            err.message
+        }
+        other => panic!("expected an argument error, got {:?}", other),
+    }
+}
+
+#[test]
+fn errors() {
+    assert!(error_message("{fg:redd}{}{/}").contains("unknown color \"redd\" in tag at position 0"));
+    assert!(error_message("{} {bold}").contains("unknown style \"bold\""));
+    assert!(error_message("{} {fg:red").contains("position 3 is not terminated"));
+    assert!(error_message("{}{/}").contains("`{/}` at position 2 does not close"));
+    assert!(error_message("{} }").contains("unmatched `}` at position 3"));
+    assert!(error_message("{bg:blue}{}").contains("position 0 is never closed"));
+    assert!(error_message("{} {}").contains("refers to argument 2, but only 1 were given"));
+    assert!(error_message("none").contains("1 arguments were given"));
+}
+#[test]
+fn parse() {
+    let name = "main.rs";
+    let string = cformat!(
+        "{{{fg:dark_blue, underscore}x{bg:Red}{}{/}{/}}} {}",
+        name,
+        4,
+    )
+    .unwrap();
+    assert_eq!(string.to_string(), "{xmain.rs} 4");
+
+    let spans = string.get_spans();
+    assert_eq!(spans.len(), 4);
+    assert_eq!(spans[0].text, "{");
+    assert_eq!(spans[0].style, TextStyle::new());
+    assert_eq!(spans[1].text, "x");
+    assert_eq!(spans[1].style.foreground, Some(ConsoleColor::DarkBlue));
+    assert!(spans[1].style.flags.Underscore);
+    assert_eq!(spans[2].text, "main.rs");
+    assert_eq!(spans[2].style.foreground, Some(ConsoleColor::DarkBlue));
+    assert_eq!(spans[2].style.background, Some(ConsoleColor::Red));
+    assert!(spans[2].style.flags.Underscore);
+    assert_eq!(spans[3].text, "} 4");
+    assert_eq!(spans[3].style, TextStyle::new());
+    assert_eq!(StyledString::from("plain"), cformat!("plain").unwrap());
+}
+#[test]
+fn write_at() {
+    let memory = MemoryConsole::new(10, 2);
+    console::set_backend(memory.clone());
+    console::set_text_attributes(TextAttributes::new(
+        ConsoleColor::White,
+        ConsoleColor::Black,
+    ))
+    .unwrap();
+
+    let string = cformat!("a{fg:red,reverse}b{/}{bg:blue}{}{/}", "cd").unwrap();
+    assert_eq!(string.write_at(1, 1).unwrap(), 4);
+    assert_eq!(console::read_output(0, 1, 5).unwrap(), " abcd");
+
+    let attributes = console::read_output_attributes(1, 1, 4).unwrap();
+    let mut red = TextAttributes::new(ConsoleColor::Red, ConsoleColor::Black);
+    red.flags.ReverseVideo = true;
+    assert_eq!(
+        attributes[0],
+        TextAttributes::new(ConsoleColor::White, ConsoleColor::Black)
+    );
+    assert_eq!(attributes[1], red);
+    assert_eq!(
+        attributes[2],
+        TextAttributes::new(ConsoleColor::White, ConsoleColor::Blue)
+    );
+    assert_eq!(attributes[3], attributes[2]);
+    assert_eq!(console::get_cursor_position().unwrap().x, 0);
+    assert!(string.write_at(10, 0).is_err());
+    console::reset_backend();
+}
@@ -0,0 +1,40 @@
+#![cfg(test)]
+extern crate winconsole;
+
+use winconsole::utf16::{self, Utf16Decoder};
+
+#[test]
+fn decode() {
+    assert_eq!(utf16::decode(&[0x48, 0x69, 0, 0x21]).unwrap(), "Hi");
+    assert_eq!(utf16::decode(&[0xD83D, 0xDE00]).unwrap(), "😀");
+    assert!(utf16::decode(&[0x61, 0xDE00]).is_err());
+    assert_eq!(utf16::decode_lossy(&[0xDE00, 0x61]), "\u{FFFD}a");
+}
+#[test]
+fn decoder() {
+    let mut decoder = Utf16Decoder::new();
+    assert_eq!(decoder.push(0xD83D), None);
+    assert!(decoder.is_pending());
+    assert_eq!(decoder.push(0xDE00), Some('😀'));
+    assert!(!decoder.is_pending());
+
+    assert_eq!(decoder.push(0xDE00), Some('\u{FFFD}'));
+    assert_eq!(decoder.push(0xD83D), None);
+    assert_eq!(decoder.push(0x62), Some('b'));
+    assert_eq!(decoder.push(0xD83D), None);
+    decoder.reset();
+    assert_eq!(decoder.push(0xDE00), Some('\u{FFFD}'));
+}
+#[test]
+fn encode() {
+    let string = "Grüße, 世界 🌍";
+    let units = utf16::encode(string);
+    assert_eq!(units.len(), 12);
+    assert_eq!(utf16::decode(&units).unwrap(), string);
+    assert_eq!(utf16::encode_with_nul(string).last(), Some(&0));
+
+    let mut buffer = [0xFFFF; 3];
+    assert_eq!(utf16::encode_into("a🌍", &mut buffer), 1);
+    assert_eq!(buffer, [0x61, 0, 0]);
+    assert_eq!(utf16::encode_into("", &mut []), 0);
+}
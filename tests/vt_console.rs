@@ -0,0 +1,117 @@
+#![cfg(test)]
+extern crate rgb;
+extern crate winconsole;
+
+use rgb::RGB8;
+use winconsole::console::{self, Color, ColorMetric, ConsoleColor, VtConsole};
+
+fn install(columns: u16, rows: u16) -> VtConsole<Vec<u8>> {
+    let vt = VtConsole::new(Vec::new(), columns, rows);
+    console::set_backend(vt.clone());
+    vt
+}
+fn take(vt: &VtConsole<Vec<u8>>) -> String {
+    let mut output = vt.get_output();
+    let string = String::from_utf8(output.clone()).unwrap();
+    output.clear();
+    string
+}
+
+#[test]
+fn clear() {
+    let vt = install(10, 3);
+    console::write_output("abc", 0, 1).unwrap();
+    take(&vt);
+
+    console::clear().unwrap();
+    assert_eq!(
+        take(&vt),
+        "\x1b7\x1b[0;37;40m\x1b[2J\x1b8\x1b7\x1b[0;37;40m\x1b[2J\x1b8\x1b[1;1H"
+    );
+    assert_eq!(vt.get_screen().lines(), vec!["", "", ""]);
+    console::reset_backend();
+}
+#[test]
+fn color_mapping() {
+    let vt = install(10, 3);
+    let color = RGB8 {
+        r: 50,
+        g: 210,
+        b: 15,
+    };
+    console::map_color(ConsoleColor::DarkRed, color).unwrap();
+    assert_eq!(take(&vt), "\x1b]4;1;rgb:32/d2/0f\x07");
+    console::reset_backend();
+}
+#[test]
+fn colors() {
+    let vt = install(10, 3);
+    console::set_foreground_color(ConsoleColor::Yellow).unwrap();
+    console::set_background_color(ConsoleColor::DarkBlue).unwrap();
+    assert_eq!(take(&vt), "\x1b[0;93;40m\x1b[0;93;44m");
+    assert_eq!(
+        console::get_foreground_color().unwrap(),
+        ConsoleColor::Yellow
+    );
+    console::reset_backend();
+}
+#[test]
+fn cursor() {
+    let vt = install(10, 3);
+    console::set_cursor_position(4, 2).unwrap();
+    console::set_cursor_visible(false).unwrap();
+    console::set_cursor_visible(true).unwrap();
+    assert_eq!(take(&vt), "\x1b[3;5H\x1b[?25l\x1b[?25h");
+    console::reset_backend();
+}
+#[test]
+fn output() {
+    let vt = install(10, 3);
+    console::write_output("Hello, world!", 0, 1).unwrap();
+    assert_eq!(
+        take(&vt),
+        "\x1b7\x1b[2;1H\x1b[0;37;40mHello, wor\x1b[3;1Hld!\x1b8"
+    );
+
+    console::fill_colors((ConsoleColor::Red, ConsoleColor::Black), 1, 2, 2).unwrap();
+    assert_eq!(take(&vt), "\x1b7\x1b[3;2H\x1b[0;91;40md!\x1b8");
+    console::reset_backend();
+}
+#[test]
+fn true_colors() {
+    let vt = install(10, 3);
+    let orange = RGB8 {
+        r: 250,
+        g: 40,
+        b: 20,
+    };
+    console::set_foreground(orange).unwrap();
+    console::set_background(Color::Indexed(27)).unwrap();
+    assert_eq!(take(&vt), "\x1b[38;2;250;40;20m\x1b[48;5;27m");
+    assert_eq!(console::get_foreground_color().unwrap(), ConsoleColor::Red);
+    assert_eq!(
+        console::get_background_color().unwrap(),
+        ConsoleColor::DarkBlue
+    );
+    let screen = vt.get_screen();
+    assert_eq!(screen.get_color(false), Color::Rgb(orange));
+    assert_eq!(screen.get_color(true), Color::Indexed(27));
+
+    console::set_color_metric(ColorMetric::Euclidean);
+    console::set_background(Color::Indexed(27)).unwrap();
+    console::set_color_metric(ColorMetric::default());
+    assert_eq!(take(&vt), "\x1b[48;5;27m");
+    assert_eq!(console::get_background_color().unwrap(), ConsoleColor::Blue);
+
+    console::set_foreground_color(ConsoleColor::Yellow).unwrap();
+    assert_eq!(screen.get_color(false), Color::Named(ConsoleColor::Yellow));
+    assert_eq!(screen.get_color(true), Color::Indexed(27));
+    console::reset_backend();
+}
+#[test]
+fn title() {
+    let vt = install(10, 3);
+    console::set_title("Title\x07").unwrap();
+    assert_eq!(take(&vt), "\x1b]2;Title\x07");
+    console::reset_backend();
+}
@@ -0,0 +1,202 @@
+#![cfg(test)]
+#![cfg(feature = "input")]
+extern crate winconsole;
+
+use std::time::{Duration, Instant};
+use winconsole::console::Vector2;
+use winconsole::input::{InputEvent, KeyCode, KeyEvent, VtInputParser};
+
+fn key(event: InputEvent) -> KeyEvent {
+    match event {
+        InputEvent::KeyDown(kev) => kev,
+        other => panic!("expected a KeyDown event, got {:?}", other),
+    }
+}
+
+#[test]
+fn escape() {
+    let mut parser = VtInputParser::new();
+    assert!(parser.feed(b"\x1b").is_empty());
+    assert!(parser.is_pending());
+    let events = parser.flush();
+    assert_eq!(events.len(), 1);
+    assert_eq!(key(events[0]).key_code, KeyCode::Escape);
+    assert!(!parser.is_pending());
+
+    let events = parser.feed(b"\x1bx\x1b\x1b");
+    let alt = key(events[0]);
+    assert_eq!(alt.key_code, KeyCode::X);
+    assert_eq!(alt.character, 'x');
+    assert!(alt.modifiers.LeftAltPressed);
+    assert_eq!(key(events[1]).key_code, KeyCode::Escape);
+    assert_eq!(key(parser.flush()[0]).key_code, KeyCode::Escape);
+}
+
+#[test]
+fn keys() {
+    let mut parser = VtInputParser::new();
+    let events = parser.feed(b"aZ!\r\x7f\x03\x1b[A\x1bOP\x1b[1;5C\x1b[3;2~\x1b[24~\x1b[Z");
+    let keys: Vec<KeyEvent> = events.into_iter().map(key).collect();
+    let codes: Vec<KeyCode> = keys.iter().map(|kev| kev.key_code).collect();
+    assert_eq!(
+        codes,
+        vec![
+            KeyCode::A,
+            KeyCode::Z,
+            KeyCode::One,
+            KeyCode::Return,
+            KeyCode::Backspace,
+            KeyCode::C,
+            KeyCode::Up,
+            KeyCode::F1,
+            KeyCode::Right,
+            KeyCode::Delete,
+            KeyCode::F12,
+            KeyCode::Tab,
+        ]
+    );
+
+    assert_eq!(keys[0].character, 'a');
+    assert!(!keys[0].modifiers.ShiftPressed);
+    assert!(keys[1].modifiers.ShiftPressed);
+    assert!(keys[2].modifiers.ShiftPressed);
+    assert_eq!(keys[4].character, '\x08');
+    assert!(keys[5].modifiers.LeftCtrlPressed);
+    assert!(keys[6].modifiers.EnhancedKey);
+    assert!(!keys[7].modifiers.EnhancedKey);
+    assert!(keys[8].modifiers.LeftCtrlPressed);
+    assert!(!keys[8].modifiers.ShiftPressed);
+    assert!(keys[9].modifiers.ShiftPressed);
+    assert!(keys[11].modifiers.ShiftPressed);
+    assert!(keys.iter().all(|kev| kev.pressed));
+
+    assert!(parser.feed(b"\x1b[99~\x1b[?1;2c").is_empty());
+    assert!(!parser.is_pending());
+}
+
+#[test]
+fn mouse() {
+    let mut parser = VtInputParser::new();
+    let events = parser.feed(b"\x1b[<0;10;5M\x1b[<32;11;5M\x1b[<0;11;5m\x1b[<18;1;1M");
+    match events[0] {
+        InputEvent::MouseDown(mev) => {
+            assert_eq!(mev.button, 1);
+            assert_eq!(mev.key_code, KeyCode::LButton);
+            assert_eq!(mev.position, Vector2::new(9, 4));
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    match events[1] {
+        InputEvent::MouseMove(mmev) => assert_eq!(mmev.position, Vector2::new(10, 4)),
+        other => panic!("unexpected event: {:?}", other),
+    }
+    match events[2] {
+        InputEvent::MouseUp(mev) => assert_eq!(mev.key_code, KeyCode::LButton),
+        other => panic!("unexpected event: {:?}", other),
+    }
+    match events[3] {
+        InputEvent::MouseDown(mev) => {
+            assert_eq!(mev.key_code, KeyCode::RButton);
+            assert!(mev.modifiers.LeftCtrlPressed);
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+
+    let events = parser.feed(b"\x1b[<65;3;4M\x1b[<66;3;4M");
+    match (events[0], events[1]) {
+        (InputEvent::MouseWheel(down), InputEvent::MouseWheel(left)) => {
+            assert_eq!(down.delta, -120);
+            assert!(!down.horizontal);
+            assert_eq!(down.position, Vector2::new(2, 3));
+            assert_eq!(left.delta, -120);
+            assert!(left.horizontal);
+        }
+        other => panic!("unexpected events: {:?}", other),
+    }
+
+    // X10 reporting, where releases don't say which button was released.
+    let events = parser.feed(b"\x1b[M\x21\x25\x26\x1b[M\x23\x25\x26");
+    match (events[0], events[1]) {
+        (InputEvent::MouseDown(down), InputEvent::MouseUp(up)) => {
+            assert_eq!(down.key_code, KeyCode::MButton);
+            assert_eq!(up.key_code, KeyCode::MButton);
+            assert_eq!(up.position, Vector2::new(4, 5));
+        }
+        other => panic!("unexpected events: {:?}", other),
+    }
+}
+
+#[test]
+fn paste() {
+    let mut parser = VtInputParser::new();
+    let events = parser.feed(b"\x1b[200~hi\x1b[A");
+    assert!(parser.is_pasting());
+    let characters: String = events.iter().map(|event| key(*event).character).collect();
+    assert_eq!(characters, "hi\x1b[A");
+
+    assert!(parser.feed(b"\x1b[20").is_empty());
+    assert!(parser.feed(b"1~").is_empty());
+    assert!(!parser.is_pasting());
+    assert_eq!(key(parser.feed(b"\x1b[A")[0]).key_code, KeyCode::Up);
+}
+
+#[test]
+fn reports() {
+    let mut parser = VtInputParser::new();
+    let events = parser.feed(b"\x1b[I\x1b[O\x1b[8;24;80t");
+    match events[..] {
+        [InputEvent::Focused(focused), InputEvent::FocusLost(lost), InputEvent::Resize(rev)] => {
+            assert!(focused.focused);
+            assert!(!lost.focused);
+            assert_eq!(rev.size, Vector2::new(80, 24));
+        }
+        ref other => panic!("unexpected events: {:?}", other),
+    }
+}
+
+#[test]
+fn split() {
+    let input = "é\x1b[1;3D\x1b[<2;5;6M😀".as_bytes();
+    let expected = VtInputParser::new().feed(input);
+    assert_eq!(expected.len(), 4);
+
+    let mut parser = VtInputParser::new();
+    let mut events = Vec::new();
+    for byte in input {
+        events.extend(parser.feed(&[*byte]));
+    }
+    assert_eq!(events, expected);
+    assert!(!parser.is_pending());
+    assert_eq!(key(events[0]).character, 'é');
+    assert!(key(events[1]).modifiers.LeftAltPressed);
+    assert_eq!(key(events[3]).character, '😀');
+
+    let events = parser.feed(b"\xffa");
+    assert_eq!(key(events[0]).character, '\u{fffd}');
+    assert_eq!(key(events[1]).character, 'a');
+}
+
+#[test]
+fn timeout() {
+    let mut parser = VtInputParser::new();
+    let start = Instant::now();
+    assert_eq!(parser.get_deadline(), None);
+    assert!(parser.feed_at(b"\x1b", start).is_empty());
+    let deadline = parser.get_deadline().unwrap();
+    assert!(deadline > start);
+
+    let before = deadline - Duration::from_millis(1);
+    assert!(parser.expire(before).is_empty());
+    let events = parser.feed_at(b"[A", before);
+    assert_eq!(events.len(), 1);
+    assert_eq!(key(events[0]).key_code, KeyCode::Up);
+    assert_eq!(parser.get_deadline(), None);
+    assert!(parser.expire(deadline).is_empty());
+
+    assert!(parser.feed_at(b"\x1b", start).is_empty());
+    let events = parser.expire(parser.get_deadline().unwrap());
+    assert_eq!(events.len(), 1);
+    assert_eq!(key(events[0]).key_code, KeyCode::Escape);
+    assert!(!parser.is_pending());
+    assert_eq!(parser.get_deadline(), None);
+}
@@ -0,0 +1,121 @@
+#![cfg(test)]
+extern crate winconsole;
+
+use winconsole::console::{ConsoleColor, TextAttributes, Vector2, VtParser};
+
+// Output recorded from `cmd.exe /c echo hello` running in a 20x4 pseudo console.
+const RECORDED: &[u8] = b"\x1b[?25l\x1b[2J\x1b[m\x1b[Hhello\r\n\x1b]0;C:\\WINDOWS\\system32\\cmd.exe\x07\x1b[?25h\x1b[?25l\x1b[H\x1b[1B\x1b[K\x1b[4;1H\x1b[32mdone\x1b[m\x1b[?25h";
+
+#[test]
+fn colors() {
+    let mut parser = VtParser::new(10, 1);
+    parser.feed(
+        b"\x1b[31;44ma\x1b[1mb\x1b[22;7mc\x1b[0;38;5;196md\x1b[48;2;0;0;255;4me\x1b[39;49;24mf",
+    );
+    let row = parser.get_screen().get_row(0).unwrap();
+
+    assert_eq!(row[0].foreground, ConsoleColor::DarkRed);
+    assert_eq!(row[0].background, ConsoleColor::DarkBlue);
+    assert_eq!(row[1].foreground, ConsoleColor::Red);
+    assert_eq!(row[2].foreground, ConsoleColor::DarkRed);
+    assert!(row[2].flags.ReverseVideo);
+    assert_eq!(row[3].foreground, ConsoleColor::Red);
+    assert_eq!(row[3].background, ConsoleColor::Black);
+    assert_eq!(row[4].background, ConsoleColor::Blue);
+    assert!(row[4].flags.Underscore);
+    assert_eq!(row[5].get_text_attributes(), TextAttributes::default());
+}
+#[test]
+fn bold() {
+    let mut parser = VtParser::new(10, 1);
+    parser.feed(b"\x1b[1;31ma\x1b[0;1mb\x1b[32mc\x1b[22md\x1b[1;94me\x1b[0mf");
+    let row = parser.get_screen().get_row(0).unwrap();
+
+    assert_eq!(row[0].foreground, ConsoleColor::Red);
+    assert_eq!(row[1].foreground, ConsoleColor::White);
+    assert_eq!(row[2].foreground, ConsoleColor::Green);
+    assert_eq!(row[3].foreground, ConsoleColor::DarkGreen);
+    assert_eq!(row[4].foreground, ConsoleColor::Blue);
+    assert_eq!(row[5].foreground, ConsoleColor::Gray);
+
+    parser.feed(b"\x1b[1;33m\x1b7\x1b[0m\x1b8");
+    assert_eq!(
+        parser.get_text_attributes().foreground,
+        ConsoleColor::Yellow
+    );
+}
+#[test]
+fn editing() {
+    let mut parser = VtParser::new(8, 3);
+    parser.feed(b"abcdefgh\r\nabcdefgh\r\nabcdefgh");
+    parser.feed(b"\x1b[1;3H\x1b[2@\x1b[2;3H\x1b[2P\x1b[3;3H\x1b[3X");
+    assert_eq!(parser.lines(), vec!["ab  cdef", "abefgh", "ab   fgh"]);
+
+    parser.feed(b"\x1b[2;4H\x1b[1K\x1b[3;4H\x1b[K");
+    assert_eq!(parser.lines(), vec!["ab  cdef", "    gh", "ab"]);
+    parser.feed(b"\x1b[2;1H\x1b[J");
+    assert_eq!(parser.lines(), vec!["ab  cdef", "", ""]);
+    assert_eq!(parser.get_cursor_position(), Vector2::new(0, 1));
+}
+#[test]
+fn recorded() {
+    let mut parser = VtParser::new(20, 4);
+    parser.feed(RECORDED);
+
+    assert_eq!(parser.lines(), vec!["hello", "", "", "done"]);
+    assert_eq!(parser.get_title(), "C:\\WINDOWS\\system32\\cmd.exe");
+    assert_eq!(parser.get_cursor_position(), Vector2::new(4, 3));
+    assert!(parser.is_cursor_visible());
+    let cell = parser.get_screen().get(0, 3).unwrap();
+    assert_eq!(cell.foreground, ConsoleColor::DarkGreen);
+    assert_eq!(parser.get_text_attributes(), TextAttributes::default());
+
+    parser.resize(3, 2);
+    assert_eq!(parser.lines(), vec!["hel", ""]);
+    assert_eq!(parser.get_cursor_position(), Vector2::new(2, 1));
+    parser.reset();
+    assert_eq!(parser.lines(), vec!["", ""]);
+    assert_eq!(parser.get_title(), "");
+}
+#[test]
+fn scroll_region() {
+    let mut parser = VtParser::new(4, 5);
+    parser.feed(b"1\r\n2\r\n3\r\n4\r\n5");
+    parser.feed(b"\x1b[2;4r\x1b[4;1H\nx");
+    assert_eq!(parser.lines(), vec!["1", "3", "4", "x", "5"]);
+
+    parser.feed(b"\x1b[2;1H\x1bMy\x1b[2T");
+    assert_eq!(parser.lines(), vec!["1", "", "", "y", "5"]);
+    parser.feed(b"\x1b[r\x1b[5;1H\n");
+    assert_eq!(parser.lines(), vec!["", "", "y", "5", ""]);
+}
+#[test]
+fn split() {
+    let mut whole = VtParser::new(10, 2);
+    whole.feed(RECORDED);
+    let mut split = VtParser::new(10, 2);
+    for byte in RECORDED {
+        split.feed(&[*byte]);
+    }
+    assert_eq!(split.lines(), whole.lines());
+    assert_eq!(split.get_title(), whole.get_title());
+    assert_eq!(split.get_cursor_position(), whole.get_cursor_position());
+
+    let mut parser = VtParser::new(10, 1);
+    let text = "é€😀".as_bytes();
+    parser.feed(&text[..1]);
+    parser.feed(&text[1..4]);
+    parser.feed(&text[4..]);
+    parser.feed(b"\xffa");
+    assert_eq!(parser.lines(), vec!["é€😀\u{FFFD}a"]);
+}
+#[test]
+fn wrap() {
+    let mut parser = VtParser::new(4, 2);
+    parser.feed(b"abcdefgh");
+    assert_eq!(parser.lines(), vec!["abcd", "efgh"]);
+    assert_eq!(parser.get_cursor_position(), Vector2::new(3, 1));
+    parser.feed(b"i\tj\x08k");
+    assert_eq!(parser.lines(), vec!["efgh", "i kj"]);
+    assert_eq!(parser.get_cursor_position(), Vector2::new(3, 1));
+}
@@ -1,4 +1,5 @@
 #![cfg(test)]
+#![cfg(windows)]
 #![cfg(feature = "window")]
 extern crate winconsole;
 